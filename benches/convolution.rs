@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ril::prelude::*;
+
+const SAMPLES: [u32; 4] = [256, 512, 1024, 2048];
+
+pub fn bench_convolution(c: &mut Criterion) {
+    let mut c = c.benchmark_group("convolution");
+    c.sample_size(10)
+        .warm_up_time(Duration::from_millis(1500))
+        .measurement_time(Duration::from_secs(3));
+
+    // Separable kernels
+    let box_blur = Convolution::<31, 31, 3, Rgb>::box_blur();
+    let gaussian_blur = SeparableConvolution::<3, Rgb>::gaussian_blur(5.0);
+    // Non-separable kernel
+    let motion_blur = DynamicConvolution::<3, Rgb>::motion_blur(15, 30.0);
+
+    for size in SAMPLES {
+        let image = Image::from_fn(size, size, |x, y| Rgb::new(x as u8, y as u8, 128));
+
+        c.bench_function(format!("box blur 31x31 {0}x{0} RGB", size).as_str(), |b| {
+            b.iter(|| image.clone().filtered(&box_blur))
+        });
+
+        c.bench_function(
+            format!("gaussian blur σ=5 {0}x{0} RGB", size).as_str(),
+            |b| b.iter(|| image.clone().filtered(&gaussian_blur)),
+        );
+
+        c.bench_function(
+            format!("motion blur 15x15 {0}x{0} RGB", size).as_str(),
+            |b| b.iter(|| image.clone().filtered(&motion_blur)),
+        );
+    }
+
+    c.finish();
+}
+
+criterion_group!(benches, bench_convolution);
+criterion_main!(benches);
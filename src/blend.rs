@@ -0,0 +1,268 @@
+//! Blend modes, which determine how the colors of overlapping pixels are combined.
+//!
+//! Blend modes are applied through [`OverlayMode::Blend`], which is accepted anywhere an overlay
+//! mode is, e.g. [`Image::with_overlay_mode`], draw entities such as [`Paste`], and text segments.
+
+use std::fmt::{self, Display};
+
+#[cfg(doc)]
+use crate::{Image, OverlayMode, Paste, RgbaF32};
+
+/// A blend mode, which determines how the color of an overlay pixel is combined with the color
+/// beneath it before it is composited according to its alpha. These follow the definitions of
+/// the W3C Compositing and Blending specification, which match those of most image editors.
+///
+/// The specification only defines blend modes for colors between `0.0` and `1.0`. The colors of
+/// floating-point pixels such as [`RgbaF32`], which may exceed this range, are clamped into it
+/// before blending with any mode other than [`Normal`][Self::Normal].
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::<Rgba>::open("sample.png")?;
+/// let tint = Image::new(100, 100, Rgba::new(64, 32, 128, 255));
+///
+/// image.draw(&Paste::new(&tint).with_overlay_mode(OverlayMode::Blend(BlendMode::Multiply)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// Use the color of the overlay. This is equivalent to [`OverlayMode::Merge`].
+    Normal,
+    /// Multiply the colors, which always results in a darker color.
+    Multiply,
+    /// Multiply the complements of the colors, which always results in a lighter color.
+    Screen,
+    /// Multiply or screen the colors depending on the color beneath, which increases contrast
+    /// while preserving its highlights and shadows.
+    Overlay,
+    /// Use the darker of the colors, per channel.
+    Darken,
+    /// Use the lighter of the colors, per channel.
+    Lighten,
+    /// Brighten the color beneath to reflect the color of the overlay.
+    ColorDodge,
+    /// Darken the color beneath to reflect the color of the overlay.
+    ColorBurn,
+    /// Multiply or screen the colors depending on the color of the overlay, as if shining a harsh
+    /// light on the color beneath.
+    HardLight,
+    /// Darken or lighten the colors depending on the color of the overlay, as if shining a
+    /// diffused light on the color beneath.
+    SoftLight,
+    /// Subtract the darker of the colors from the lighter one, per channel.
+    Difference,
+    /// Similar to [`Self::Difference`], but with lower contrast.
+    Exclusion,
+    /// Use the hue of the overlay with the saturation and luminosity of the color beneath.
+    Hue,
+    /// Use the saturation of the overlay with the hue and luminosity of the color beneath.
+    Saturation,
+    /// Use the hue and saturation of the overlay with the luminosity of the color beneath. This
+    /// is useful for tinting images.
+    Color,
+    /// Use the luminosity of the overlay with the hue and saturation of the color beneath.
+    Luminosity,
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl Display for BlendMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Normal => "normal",
+            Self::Multiply => "multiply",
+            Self::Screen => "screen",
+            Self::Overlay => "overlay",
+            Self::Darken => "darken",
+            Self::Lighten => "lighten",
+            Self::ColorDodge => "color-dodge",
+            Self::ColorBurn => "color-burn",
+            Self::HardLight => "hard-light",
+            Self::SoftLight => "soft-light",
+            Self::Difference => "difference",
+            Self::Exclusion => "exclusion",
+            Self::Hue => "hue",
+            Self::Saturation => "saturation",
+            Self::Color => "color",
+            Self::Luminosity => "luminosity",
+        })
+    }
+}
+
+type Color = [f32; 3];
+
+fn luminosity([r, g, b]: Color) -> f32 {
+    0.3f32.mul_add(r, 0.59f32.mul_add(g, 0.11 * b))
+}
+
+fn saturation(color: Color) -> f32 {
+    color.iter().copied().fold(f32::MIN, f32::max) - color.iter().copied().fold(f32::MAX, f32::min)
+}
+
+/// Brings the channels of the given color into range while preserving its luminosity.
+fn clip_color(color: Color) -> Color {
+    let l = luminosity(color);
+    let min = color.iter().copied().fold(f32::MAX, f32::min);
+    let max = color.iter().copied().fold(f32::MIN, f32::max);
+
+    color.map(|c| {
+        let mut c = c;
+        if min < 0.0 {
+            c = l + (c - l) * l / (l - min);
+        }
+        if max > 1.0 {
+            c = l + (c - l) * (1.0 - l) / (max - l);
+        }
+        c
+    })
+}
+
+fn with_luminosity(color: Color, l: f32) -> Color {
+    let difference = l - luminosity(color);
+    clip_color(color.map(|c| c + difference))
+}
+
+fn with_saturation(color: Color, s: f32) -> Color {
+    let mut order = [0, 1, 2];
+    order.sort_unstable_by(|&a, &b| {
+        color[a]
+            .partial_cmp(&color[b])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let [min, mid, max] = order;
+
+    let mut result = [0.0; 3];
+    let range = color[max] - color[min];
+    if range > 0.0 {
+        result[mid] = (color[mid] - color[min]) * s / range;
+        result[max] = s;
+    }
+    result
+}
+
+impl BlendMode {
+    /// Blends the given color of an overlay onto the given color beneath it, where both colors
+    /// are opaque and their channels are between `0.0` and `1.0`.
+    fn blend(self, base: Color, overlay: Color) -> Color {
+        let separable = |f: fn(f32, f32) -> f32| [0, 1, 2].map(|c| f(base[c], overlay[c]));
+
+        match self {
+            Self::Normal => overlay,
+            Self::Multiply => separable(|b, s| b * s),
+            Self::Screen => separable(screen),
+            Self::Overlay => separable(|b, s| hard_light(s, b)),
+            Self::Darken => separable(f32::min),
+            Self::Lighten => separable(f32::max),
+            Self::ColorDodge => separable(|b, s| {
+                if b <= 0.0 {
+                    0.0
+                } else if s >= 1.0 {
+                    1.0
+                } else {
+                    (b / (1.0 - s)).min(1.0)
+                }
+            }),
+            Self::ColorBurn => separable(|b, s| {
+                if b >= 1.0 {
+                    1.0
+                } else if s <= 0.0 {
+                    0.0
+                } else {
+                    1.0 - ((1.0 - b) / s).min(1.0)
+                }
+            }),
+            Self::HardLight => separable(hard_light),
+            Self::SoftLight => separable(|b, s| {
+                if s <= 0.5 {
+                    b - (1.0 - 2.0 * s) * b * (1.0 - b)
+                } else {
+                    let d = if b <= 0.25 {
+                        ((16.0 * b - 12.0) * b + 4.0) * b
+                    } else {
+                        b.sqrt()
+                    };
+                    b + (2.0 * s - 1.0) * (d - b)
+                }
+            }),
+            Self::Difference => separable(|b, s| (b - s).abs()),
+            Self::Exclusion => separable(|b, s| b + s - 2.0 * b * s),
+            Self::Hue => {
+                with_luminosity(with_saturation(overlay, saturation(base)), luminosity(base))
+            }
+            Self::Saturation => {
+                with_luminosity(with_saturation(base, saturation(overlay)), luminosity(base))
+            }
+            Self::Color => with_luminosity(overlay, luminosity(base)),
+            Self::Luminosity => with_luminosity(base, luminosity(overlay)),
+        }
+    }
+}
+
+fn screen(b: f32, s: f32) -> f32 {
+    b + s - b * s
+}
+
+fn hard_light(b: f32, s: f32) -> f32 {
+    if s <= 0.5 {
+        b * 2.0 * s
+    } else {
+        screen(b, 2.0 * s - 1.0)
+    }
+}
+
+/// Blends the given overlay color onto the given base color with the given blend mode, then
+/// composites the result over the base color. Colors are in straight (non-premultiplied) RGBA,
+/// with channels between `0.0` and `1.0`.
+pub(crate) fn composite(base: [f32; 4], overlay: [f32; 4], mode: BlendMode) -> [f32; 4] {
+    let (base_a, overlay_a) = (base[3], overlay[3]);
+    let a = overlay_a + base_a * (1.0 - overlay_a);
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+
+    let blended = mode.blend(
+        [base[0], base[1], base[2]],
+        [overlay[0], overlay[1], overlay[2]],
+    );
+    let channel = |c: usize| {
+        // The blended color only applies where the overlay covers the base
+        let mixed = (1.0 - base_a) * overlay[c] + base_a * blended[c];
+        (overlay_a * mixed + (1.0 - overlay_a) * base_a * base[c]) / a
+    };
+
+    [channel(0), channel(1), channel(2), a]
+}
+
+/// Like [`composite`], but for 8-bit channels. The alpha of the overlay is multiplied by the
+/// given alpha.
+pub(crate) fn composite8(base: [u8; 4], overlay: [u8; 4], mode: BlendMode, alpha: u8) -> [u8; 4] {
+    let mut overlay = overlay.map(|c| f32::from(c) / 255.0);
+    overlay[3] *= f32::from(alpha) / 255.0;
+
+    composite(base.map(|c| f32::from(c) / 255.0), overlay, mode)
+        .map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8)
+}
+
+/// Like [`composite`], but for 16-bit channels. The alpha of the overlay is multiplied by the
+/// given alpha.
+pub(crate) fn composite16(
+    base: [u16; 4],
+    overlay: [u16; 4],
+    mode: BlendMode,
+    alpha: u8,
+) -> [u16; 4] {
+    const MAX: f32 = u16::MAX as f32;
+
+    let mut overlay = overlay.map(|c| f32::from(c) / MAX);
+    overlay[3] *= f32::from(alpha) / 255.0;
+
+    composite(base.map(|c| f32::from(c) / MAX), overlay, mode)
+        .map(|c| (c * MAX).round().clamp(0.0, MAX) as u16)
+}
@@ -0,0 +1,189 @@
+//! Porter-Duff compositing operators, which determine which parts of overlapping images are kept.
+//!
+//! Compositing operators are applied through [`OverlayMode::Composite`], which is accepted anywhere
+//! an overlay mode is, e.g. [`Paste`] and other draw entities. Images can also be composited onto
+//! each other with the [`Composite`] draw entity, or with [`Image::composite`] as a shorthand.
+
+use std::fmt::{self, Display};
+
+#[cfg(doc)]
+use crate::{Composite, Image, OverlayMode, Paste};
+
+/// A Porter-Duff compositing operator, which determines how the color and alpha of a source pixel
+/// (the overlay) are combined with the color and alpha of a destination pixel (the pixel beneath
+/// it) based on how much each of them covers.
+///
+/// Each operator keeps some fraction of the source and some fraction of the destination, where
+/// each fraction is either `0`, `1`, or depends on the alpha of the other pixel.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::<Rgba>::open("sample.png")?;
+/// let (w, h) = image.dimensions();
+///
+/// // Cut a circular hole out of the image
+/// let mut hole = Image::new(w, h, Rgba::transparent());
+/// hole.draw(&Ellipse::from_bounding_box(0, 0, w, h).with_fill(Rgba::black()));
+/// image.composite(0, 0, &hole, CompositeOperator::DestinationOut);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompositeOperator {
+    /// Clear both the source and the destination, resulting in a fully transparent pixel.
+    Clear,
+    /// Keep only the source.
+    Source,
+    /// Keep only the destination.
+    Destination,
+    /// Place the source over the destination. This is equivalent to [`OverlayMode::Merge`], and
+    /// is the default.
+    SourceOver,
+    /// Place the destination over the source.
+    DestinationOver,
+    /// Keep only the part of the source that is inside of the destination.
+    SourceIn,
+    /// Keep only the part of the destination that is inside of the source. This is useful for
+    /// masking the destination with the alpha of the source.
+    DestinationIn,
+    /// Keep only the part of the source that is outside of the destination.
+    SourceOut,
+    /// Keep only the part of the destination that is outside of the source. This is useful for
+    /// cutting the shape of the source out of the destination.
+    DestinationOut,
+    /// Place the part of the source that is inside of the destination over the destination.
+    SourceAtop,
+    /// Place the part of the destination that is inside of the source over the source.
+    DestinationAtop,
+    /// Keep the parts of the source and destination that do not overlap.
+    Xor,
+}
+
+impl Default for CompositeOperator {
+    fn default() -> Self {
+        Self::SourceOver
+    }
+}
+
+impl Display for CompositeOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Clear => "clear",
+            Self::Source => "source",
+            Self::Destination => "destination",
+            Self::SourceOver => "source-over",
+            Self::DestinationOver => "destination-over",
+            Self::SourceIn => "source-in",
+            Self::DestinationIn => "destination-in",
+            Self::SourceOut => "source-out",
+            Self::DestinationOut => "destination-out",
+            Self::SourceAtop => "source-atop",
+            Self::DestinationAtop => "destination-atop",
+            Self::Xor => "xor",
+        })
+    }
+}
+
+impl CompositeOperator {
+    /// Returns the fractions of the source and of the destination that this operator keeps, given
+    /// the alpha of the source and the alpha of the destination, between `0.0` and `1.0`.
+    #[must_use]
+    pub fn factors(self, source_alpha: f32, destination_alpha: f32) -> (f32, f32) {
+        match self {
+            Self::Clear => (0.0, 0.0),
+            Self::Source => (1.0, 0.0),
+            Self::Destination => (0.0, 1.0),
+            Self::SourceOver => (1.0, 1.0 - source_alpha),
+            Self::DestinationOver => (1.0 - destination_alpha, 1.0),
+            Self::SourceIn => (destination_alpha, 0.0),
+            Self::DestinationIn => (0.0, source_alpha),
+            Self::SourceOut => (1.0 - destination_alpha, 0.0),
+            Self::DestinationOut => (0.0, 1.0 - source_alpha),
+            Self::SourceAtop => (destination_alpha, 1.0 - source_alpha),
+            Self::DestinationAtop => (1.0 - destination_alpha, source_alpha),
+            Self::Xor => (1.0 - destination_alpha, 1.0 - source_alpha),
+        }
+    }
+
+    /// Composites the given source color onto the given destination color, where both colors are
+    /// RGBA with premultiplied alpha and channels between `0.0` and `1.0`. The result is also
+    /// premultiplied.
+    #[must_use]
+    pub fn composite_premultiplied(self, source: [f32; 4], destination: [f32; 4]) -> [f32; 4] {
+        let (fs, fd) = self.factors(source[3], destination[3]);
+
+        [0, 1, 2, 3].map(|c| source[c].mul_add(fs, destination[c] * fd))
+    }
+
+    /// Composites the given source color onto the given destination color, where both colors are
+    /// RGBA with straight (non-premultiplied) alpha and channels between `0.0` and `1.0`. The
+    /// result is also straight.
+    #[must_use]
+    pub fn composite(self, source: [f32; 4], destination: [f32; 4]) -> [f32; 4] {
+        unpremultiply(self.composite_premultiplied(premultiply(source), premultiply(destination)))
+    }
+}
+
+fn premultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r * a, g * a, b * a, a]
+}
+
+fn unpremultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+
+    [r / a, g / a, b / a, a]
+}
+
+/// Composites the given overlay color onto the given base color with the given operator, where
+/// the operator only applies to the given fraction of the pixel. This is used in anti-aliasing,
+/// where the fraction is how much of the pixel is covered. Colors are in straight RGBA, with
+/// channels between `0.0` and `1.0`.
+pub(crate) fn composite(
+    base: [f32; 4],
+    overlay: [f32; 4],
+    operator: CompositeOperator,
+    coverage: f32,
+) -> [f32; 4] {
+    let base = premultiply(base);
+    let composited = operator.composite_premultiplied(premultiply(overlay), base);
+
+    unpremultiply([0, 1, 2, 3].map(|c| (composited[c] - base[c]).mul_add(coverage, base[c])))
+}
+
+/// Like [`composite`], but for 8-bit channels. The coverage is given by the alpha.
+pub(crate) fn composite8(
+    base: [u8; 4],
+    overlay: [u8; 4],
+    operator: CompositeOperator,
+    alpha: u8,
+) -> [u8; 4] {
+    composite(
+        base.map(|c| f32::from(c) / 255.0),
+        overlay.map(|c| f32::from(c) / 255.0),
+        operator,
+        f32::from(alpha) / 255.0,
+    )
+    .map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8)
+}
+
+/// Like [`composite`], but for 16-bit channels. The coverage is given by the alpha.
+pub(crate) fn composite16(
+    base: [u16; 4],
+    overlay: [u16; 4],
+    operator: CompositeOperator,
+    alpha: u8,
+) -> [u16; 4] {
+    const MAX: f32 = u16::MAX as f32;
+
+    composite(
+        base.map(|c| f32::from(c) / MAX),
+        overlay.map(|c| f32::from(c) / MAX),
+        operator,
+        f32::from(alpha) / 255.0,
+    )
+    .map(|c| (c * MAX).round().clamp(0.0, MAX) as u16)
+}
@@ -0,0 +1,364 @@
+//! Dithering, which approximates colors that are unavailable in a limited palette by mixing the
+//! colors that are available.
+//!
+//! Dithering can be used when quantizing images with a [`Quantizer`][crate::Quantizer], when
+//! converting images to [`BitPixel`][crate::BitPixel] with [`Image::dither`][crate::Image::dither],
+//! and when encoding GIFs.
+
+use std::{collections::HashMap, rc::Rc};
+
+/// The dithering method used when reducing the colors of an image.
+///
+/// Error diffusion methods spread the error between each pixel and its closest available color
+/// over the pixels that have yet to be processed, which preserves detail well. Ordered methods
+/// instead offset each pixel by a threshold from a repeating matrix, which is faster and produces
+/// regular patterns that are stable across animation frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Dither {
+    /// Map each pixel to its closest available color without dithering. This is the default.
+    None,
+    /// Floyd-Steinberg error diffusion, which spreads the error over the four neighboring pixels.
+    FloydSteinberg,
+    /// Atkinson error diffusion, which only spreads three quarters of the error. This results in
+    /// higher contrast at the cost of losing detail in very bright and very dark regions.
+    Atkinson,
+    /// Jarvis-Judice-Ninke error diffusion, which spreads the error over the next two rows,
+    /// resulting in smoother gradients than Floyd-Steinberg.
+    JarvisJudiceNinke,
+    /// Sierra error diffusion, which is similar to Jarvis-Judice-Ninke but slightly faster.
+    Sierra,
+    /// Ordered dithering with a 2x2 Bayer matrix.
+    Bayer2,
+    /// Ordered dithering with a 4x4 Bayer matrix.
+    Bayer4,
+    /// Ordered dithering with an 8x8 Bayer matrix.
+    Bayer8,
+    /// Ordered dithering with a blue noise matrix, which avoids the cross-hatched patterns of
+    /// Bayer matrices.
+    BlueNoise,
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl Dither {
+    /// Returns whether this is an error diffusion method.
+    #[must_use]
+    pub const fn is_error_diffusion(self) -> bool {
+        matches!(
+            self,
+            Self::FloydSteinberg | Self::Atkinson | Self::JarvisJudiceNinke | Self::Sierra
+        )
+    }
+
+    /// Returns whether this is an ordered dithering method.
+    #[must_use]
+    pub const fn is_ordered(self) -> bool {
+        matches!(
+            self,
+            Self::Bayer2 | Self::Bayer4 | Self::Bayer8 | Self::BlueNoise
+        )
+    }
+
+    /// Returns the offsets and weights of the neighbors the error of a pixel is diffused to, along
+    /// with the divisor of the weights.
+    fn diffusion(self) -> (&'static [(isize, usize, f32)], f32) {
+        match self {
+            Self::FloydSteinberg => (&[(1, 0, 7.), (-1, 1, 3.), (0, 1, 5.), (1, 1, 1.)], 16.),
+            Self::Atkinson => (
+                &[
+                    (1, 0, 1.),
+                    (2, 0, 1.),
+                    (-1, 1, 1.),
+                    (0, 1, 1.),
+                    (1, 1, 1.),
+                    (0, 2, 1.),
+                ],
+                8.,
+            ),
+            Self::JarvisJudiceNinke => (
+                &[
+                    (1, 0, 7.),
+                    (2, 0, 5.),
+                    (-2, 1, 3.),
+                    (-1, 1, 5.),
+                    (0, 1, 7.),
+                    (1, 1, 5.),
+                    (2, 1, 3.),
+                    (-2, 2, 1.),
+                    (-1, 2, 3.),
+                    (0, 2, 5.),
+                    (1, 2, 3.),
+                    (2, 2, 1.),
+                ],
+                48.,
+            ),
+            Self::Sierra => (
+                &[
+                    (1, 0, 5.),
+                    (2, 0, 3.),
+                    (-2, 1, 2.),
+                    (-1, 1, 4.),
+                    (0, 1, 5.),
+                    (1, 1, 4.),
+                    (2, 1, 2.),
+                    (-1, 2, 2.),
+                    (0, 2, 3.),
+                    (1, 2, 2.),
+                ],
+                32.,
+            ),
+            _ => (&[], 1.),
+        }
+    }
+
+    /// Returns the threshold matrix of an ordered dithering method along with its size. Each
+    /// threshold is between `0.0` and `1.0`.
+    fn matrix(self) -> (Rc<[f32]>, usize) {
+        thread_local! {
+            // Generating blue noise is expensive, so it is only generated once and then reused,
+            // e.g. for every frame of an animated GIF
+            static BLUE_NOISE: (Rc<[f32]>, usize) = {
+                let (matrix, size) = blue_noise_matrix(32);
+                (matrix.into(), size)
+            };
+        }
+
+        let (matrix, size) = match self {
+            Self::Bayer2 => bayer_matrix(2),
+            Self::Bayer4 => bayer_matrix(4),
+            Self::Bayer8 => bayer_matrix(8),
+            Self::BlueNoise => return BLUE_NOISE.with(|(matrix, size)| (matrix.clone(), *size)),
+            _ => (vec![0.5], 1),
+        };
+        (matrix.into(), size)
+    }
+}
+
+/// Generates a Bayer matrix of the given size, which must be a power of two.
+fn bayer_matrix(size: usize) -> (Vec<f32>, usize) {
+    let mut matrix = vec![0_usize];
+    let mut n = 1;
+    while n < size {
+        matrix = (0..4 * n * n)
+            .map(|i| {
+                let (x, y) = (i % (2 * n), i / (2 * n));
+                let quadrant = [0, 2, 3, 1][(y / n) * 2 + x / n];
+                4 * matrix[(y % n) * n + x % n] + quadrant
+            })
+            .collect();
+        n *= 2;
+    }
+
+    let count = (size * size) as f32;
+    (
+        matrix
+            .into_iter()
+            .map(|rank| (rank as f32 + 0.5) / count)
+            .collect(),
+        size,
+    )
+}
+
+/// Generates a tileable blue noise matrix of the given size with the void-and-cluster method.
+fn blue_noise_matrix(size: usize) -> (Vec<f32>, usize) {
+    const SIGMA: f32 = 1.5;
+
+    let count = size * size;
+    // The energy each point contributes to every other point, indexed by their toroidal offset
+    let kernel = (0..count)
+        .map(|i| {
+            let distance = |d: usize| d.min(size - d) as f32;
+            let (dx, dy) = (distance(i % size), distance(i / size));
+            (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+        })
+        .collect::<Vec<_>>();
+
+    let mut points = vec![false; count];
+    let mut energy = vec![0.0_f32; count];
+    let toggle = |points: &mut [bool], energy: &mut [f32], i: usize| {
+        points[i] = !points[i];
+        let sign = if points[i] { 1.0 } else { -1.0 };
+        let (x, y) = (i % size, i / size);
+        for (j, e) in energy.iter_mut().enumerate() {
+            let (dx, dy) = ((j % size + size - x) % size, (j / size + size - y) % size);
+            *e += sign * kernel[dy * size + dx];
+        }
+    };
+    // The set point with the most energy, i.e. the tightest cluster
+    let cluster = |points: &[bool], energy: &[f32]| {
+        (0..count)
+            .filter(|&i| points[i])
+            .fold(None, |best: Option<usize>, i| match best {
+                Some(b) if energy[b] >= energy[i] => best,
+                _ => Some(i),
+            })
+            .expect("no set points")
+    };
+    // The unset point with the least energy, i.e. the largest void
+    let void = |points: &[bool], energy: &[f32]| {
+        (0..count)
+            .filter(|&i| !points[i])
+            .fold(None, |best: Option<usize>, i| match best {
+                Some(b) if energy[b] <= energy[i] => best,
+                _ => Some(i),
+            })
+            .expect("no unset points")
+    };
+
+    // Start with a deterministic pseudo-random pattern covering a tenth of the points
+    let mut state = 0x2545_f491_u32;
+    let initial = count / 10;
+    while points.iter().filter(|&&p| p).count() < initial {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        let i = state as usize % count;
+        if !points[i] {
+            toggle(&mut points, &mut energy, i);
+        }
+    }
+
+    // Move points from the tightest clusters into the largest voids until the pattern is even
+    for _ in 0..count {
+        let c = cluster(&points, &energy);
+        toggle(&mut points, &mut energy, c);
+        let v = void(&points, &energy);
+        toggle(&mut points, &mut energy, v);
+        if c == v {
+            break;
+        }
+    }
+
+    // Rank the initial points by repeatedly removing the tightest cluster, and the remaining
+    // points by repeatedly filling the largest void
+    let mut ranks = vec![0; count];
+    let (mut removed, mut removed_energy) = (points.clone(), energy.clone());
+    for rank in (0..initial).rev() {
+        let c = cluster(&removed, &removed_energy);
+        toggle(&mut removed, &mut removed_energy, c);
+        ranks[c] = rank;
+    }
+    for rank in initial..count {
+        let v = void(&points, &energy);
+        toggle(&mut points, &mut energy, v);
+        ranks[v] = rank;
+    }
+
+    (
+        ranks
+            .into_iter()
+            .map(|rank| (rank as f32 + 0.5) / count as f32)
+            .collect(),
+        size,
+    )
+}
+
+/// Maps each of the given colors, which are laid out in rows of the given width, to the index of
+/// an entry of the given palette using the given dithering method.
+///
+/// # Panics
+/// * The palette is empty.
+pub(crate) fn dither<const C: usize>(
+    colors: &[[f32; C]],
+    width: usize,
+    palette: &[[f32; C]],
+    method: Dither,
+) -> Vec<usize> {
+    assert!(!palette.is_empty(), "cannot dither to an empty palette");
+
+    let mut cache = HashMap::new();
+    let mut nearest = |color: [f32; C]| {
+        let key = color.map(|c| c.round() as u8);
+        *cache.entry(key).or_insert_with(|| {
+            let distance = |entry: &[f32; C]| {
+                entry
+                    .iter()
+                    .zip(&key)
+                    .map(|(&a, &b)| (a - f32::from(b)).powi(2))
+                    .sum::<f32>()
+            };
+            (0..palette.len()).fold(0, |best, i| {
+                if distance(&palette[i]) < distance(&palette[best]) {
+                    i
+                } else {
+                    best
+                }
+            })
+        })
+    };
+
+    if method.is_error_diffusion() {
+        let (neighbors, divisor) = method.diffusion();
+        let height = colors.len() / width.max(1);
+        let mut working = colors.to_vec();
+
+        return (0..working.len())
+            .map(|i| {
+                let color = working[i].map(|c| c.clamp(0.0, 255.0));
+                let index = nearest(color);
+                let (x, y) = (i % width, i / width);
+
+                for &(dx, dy, weight) in neighbors {
+                    let nx = x as isize + dx;
+                    if nx < 0 || nx >= width as isize || y + dy >= height {
+                        continue;
+                    }
+                    let neighbor = &mut working[(y + dy) * width + nx as usize];
+                    for c in 0..C {
+                        neighbor[c] += (color[c] - palette[index][c]) * weight / divisor;
+                    }
+                }
+                index
+            })
+            .collect();
+    }
+
+    if method.is_ordered() {
+        let (matrix, size) = method.matrix();
+        // Offset by the average distance between neighboring palette entries, so that pixels
+        // can reach the entries surrounding their color
+        let spread = if palette.len() < 2 {
+            0.0
+        } else {
+            let distance = |a: &[f32; C], b: &[f32; C]| {
+                a.iter()
+                    .zip(b)
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum::<f32>()
+                    .sqrt()
+            };
+            palette
+                .iter()
+                .enumerate()
+                .map(|(i, a)| {
+                    palette
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| i != j)
+                        .map(|(_, b)| distance(a, b))
+                        .fold(f32::INFINITY, f32::min)
+                })
+                .sum::<f32>()
+                / palette.len() as f32
+        };
+
+        return colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| {
+                let (x, y) = (i % width, i / width);
+                let offset = (matrix[(y % size) * size + x % size] - 0.5) * spread;
+                nearest(color.map(|c| (c + offset).clamp(0.0, 255.0)))
+            })
+            .collect();
+    }
+
+    colors
+        .iter()
+        .map(|color| nearest(color.map(|c| c.clamp(0.0, 255.0))))
+        .collect()
+}
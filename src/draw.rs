@@ -1,10 +1,16 @@
 //! Encloses most drawing implementations and drawable objects.
 
 use crate::{
-    fill::{Fill, IntoFill},
+    composite::CompositeOperator,
+    encodings::ColorType,
+    fill::{BoundingBox, Fill, IntoFill, SolidFill},
     BitPixel, Image, OverlayMode, Pixel,
 };
-use std::ops::DerefMut;
+use std::{
+    cmp::Ordering,
+    f32::consts::{FRAC_PI_2, PI, TAU},
+    ops::DerefMut,
+};
 
 /// A common trait for all objects able to be drawn on an image.
 ///
@@ -107,6 +113,419 @@ impl<P: Pixel> Border<P> {
     }
 }
 
+/// The rule used to determine which regions are inside of a shape, which matters for shapes with
+/// overlapping or self-intersecting outlines.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// A region is inside of the shape if the outlines wind around it a non-zero number of times,
+    /// where clockwise and counterclockwise windings cancel each other out. This is the default.
+    NonZero,
+    /// A region is inside of the shape if the outlines wind around it an odd number of times,
+    /// regardless of their direction. Overlapping regions become holes.
+    EvenOdd,
+}
+
+impl Default for FillRule {
+    fn default() -> Self {
+        Self::NonZero
+    }
+}
+
+impl FillRule {
+    /// Converts the accumulated winding of a pixel, which is fractional for pixels that are only
+    /// partially covered by the outlines, into how much of the pixel is inside of the shape.
+    fn coverage(self, winding: f32) -> f32 {
+        let winding = winding.abs();
+
+        match self {
+            Self::NonZero => winding.min(1.0),
+            Self::EvenOdd => {
+                let winding = winding % 2.0;
+                if winding > 1.0 {
+                    2.0 - winding
+                } else {
+                    winding
+                }
+            }
+        }
+    }
+}
+
+/// The maximum distance, in pixels, between a curve and the line segments it is approximated by.
+const FLATTEN_TOLERANCE: f32 = 0.1;
+
+type Point = (f32, f32);
+
+/// A polyline approximating a subpath, along with whether it is closed.
+type Subpath = (Vec<Point>, bool);
+
+fn distance((x1, y1): Point, (x2, y2): Point) -> f32 {
+    (x2 - x1).hypot(y2 - y1)
+}
+
+/// The signed area of the given polygon, which is positive for polygons that wind clockwise.
+fn signed_area(polygon: &[Point]) -> f32 {
+    let wrapped = polygon.iter().zip(polygon.iter().cycle().skip(1));
+    wrapped
+        .map(|(&(x1, y1), &(x2, y2))| x1 * y2 - x2 * y1)
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Approximates an ellipse arc with points, including both of its ends.
+#[allow(clippy::cast_precision_loss)]
+fn arc_points(
+    (cx, cy): Point,
+    (rx, ry): Point,
+    start_angle: f32,
+    sweep_angle: f32,
+) -> impl Iterator<Item = Point> {
+    // Each segment deviates from the arc by about `r * angle^2 / 8`
+    let radius = rx.abs().max(ry.abs()).max(f32::EPSILON);
+    let step = (8.0 * FLATTEN_TOLERANCE / radius).sqrt();
+    let count = ((sweep_angle.abs() / step).ceil() as usize).clamp(1, 4096);
+
+    (0..=count).map(move |i| {
+        let angle = sweep_angle.mul_add(i as f32 / count as f32, start_angle);
+        let (sin, cos) = angle.sin_cos();
+        (rx.mul_add(cos, cx), ry.mul_add(sin, cy))
+    })
+}
+
+/// Approximates a full ellipse with a closed polygon.
+fn ellipse_points(center: Point, radii: Point) -> Vec<Point> {
+    let mut points = arc_points(center, radii, 0.0, TAU).collect::<Vec<_>>();
+    // The last point is the same as the first
+    points.pop();
+    points
+}
+
+/// The coverage of a shape over a region of an image, where each value is between `0.0` (not
+/// covered) and `1.0` (fully covered).
+///
+/// This is the rasterizer shared by paths and antialiased shapes. Rather than sampling pixels, it
+/// computes the exact area of each pixel that is covered by the shape, accumulating the signed area
+/// to the right of each edge in every row it crosses.
+struct Coverage {
+    x: u32,
+    y: u32,
+    width: u32,
+    values: Vec<f32>,
+}
+
+#[allow(clippy::cast_precision_loss)]
+impl Coverage {
+    /// Rasterizes the given closed polygons under the given fill rule, clipped to an image of the
+    /// given dimensions.
+    fn rasterize(polygons: &[Vec<Point>], rule: FillRule, (width, height): (u32, u32)) -> Self {
+        let points = polygons.iter().flatten();
+        let (x1, y1, x2, y2) = points.fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(x1, y1, x2, y2), &(x, y)| (x1.min(x), y1.min(y), x2.max(x), y2.max(y)),
+        );
+        // Bounds are inclusive here and exclusive below
+        let clamp = |value: f32, max: u32| value.max(0.0).min(max as f32) as u32;
+        let (x1, y1) = (clamp(x1.floor(), width), clamp(y1.floor(), height));
+        let (x2, y2) = (clamp(x2.ceil(), width), clamp(y2.ceil(), height));
+        let (width, height) = (x2.saturating_sub(x1), y2.saturating_sub(y1));
+
+        // Each row has two extra cells, since the areas to the right of edges are accumulated in
+        // the pixel after the edge
+        let stride = width as usize + 2;
+        let mut accumulation = vec![0.0; stride * height as usize];
+        let offset = |(x, y): Point| (x - x1 as f32, y - y1 as f32);
+
+        for polygon in polygons {
+            let edges = polygon.iter().zip(polygon.iter().cycle().skip(1));
+            for (&from, &to) in edges {
+                Self::accumulate_clipped(
+                    &mut accumulation,
+                    stride,
+                    (width as f32, height as f32),
+                    offset(from),
+                    offset(to),
+                );
+            }
+        }
+
+        let values = accumulation
+            .chunks_exact(stride)
+            .flat_map(|row| {
+                row[..width as usize].iter().scan(0.0, |winding, &area| {
+                    *winding += area;
+                    Some(rule.coverage(*winding))
+                })
+            })
+            .collect();
+
+        Self {
+            x: x1,
+            y: y1,
+            width,
+            values,
+        }
+    }
+
+    /// Accumulates the given edge, clipping it to the region horizontally. Parts of the edge
+    /// outside of the region are projected onto its sides, which keeps the coverage of the pixels
+    /// inside of the region intact.
+    fn accumulate_clipped(
+        accumulation: &mut [f32],
+        stride: usize,
+        (width, height): Point,
+        from: Point,
+        to: Point,
+    ) {
+        let mut cuts = [0.0, 1.0, 1.0, 1.0];
+        let mut count = 2;
+        for bound in [0.0, width] {
+            let t = (bound - from.0) / (to.0 - from.0);
+            if t > 0.0 && t < 1.0 {
+                cuts[count] = t;
+                count += 1;
+            }
+        }
+
+        let cuts = &mut cuts[..count];
+        cuts.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let lerp = |t: f32| {
+            let x = (to.0 - from.0).mul_add(t, from.0);
+            (x.max(0.0).min(width), (to.1 - from.1).mul_add(t, from.1))
+        };
+        for pair in cuts.windows(2) {
+            Self::accumulate(
+                accumulation,
+                stride,
+                (width, height),
+                lerp(pair[0]),
+                lerp(pair[1]),
+            );
+        }
+    }
+
+    /// Accumulates the signed area to the right of the given edge, which must lie within the
+    /// region horizontally, in each row it crosses.
+    fn accumulate(
+        accumulation: &mut [f32],
+        stride: usize,
+        (width, height): Point,
+        from: Point,
+        to: Point,
+    ) {
+        #[allow(clippy::float_cmp)]
+        if from.1 == to.1 {
+            return;
+        }
+        let (direction, (x1, y1), (x2, y2)) = if from.1 < to.1 {
+            (1.0, from, to)
+        } else {
+            (-1.0, to, from)
+        };
+        let slope = (x2 - x1) / (y2 - y1);
+
+        let start = y1.max(0.0).floor() as usize;
+        let end = y2.min(height).ceil().max(0.0) as usize;
+        for row in start..end {
+            let top = (row as f32).max(y1);
+            let bottom = ((row + 1) as f32).min(y2);
+            // Rounding errors could otherwise place the edge just outside of the region
+            let clamp = |x: f32| x.max(0.0).min(width);
+            let (xa, xb) = (
+                clamp(slope.mul_add(top - y1, x1)),
+                clamp(slope.mul_add(bottom - y1, x1)),
+            );
+            // The height of the part of the edge in this row, which is the total area it adds
+            let dy = (bottom - top) * direction;
+            let cells = &mut accumulation[row * stride..(row + 1) * stride];
+
+            let (left, right) = if xa < xb { (xa, xb) } else { (xb, xa) };
+            let (left_floor, right_ceil) = (left.floor(), right.ceil());
+            let (i, j) = (left_floor as usize, right_ceil as usize);
+
+            if j <= i + 1 {
+                // The edge lies within a single pixel, which is covered to the right of its
+                // midpoint
+                let mid = 0.5f32.mul_add(xa + xb, -left_floor);
+                cells[i] += dy - dy * mid;
+                cells[i + 1] += dy * mid;
+                continue;
+            }
+
+            // The edge spans multiple pixels, where the area to its right grows linearly
+            let scale = (right - left).recip();
+            let left_fraction = left - left_floor;
+            let first = 0.5 * scale * (1.0 - left_fraction).powi(2);
+            let last = 0.5 * scale * (right - right_ceil + 1.0).powi(2);
+
+            cells[i] += dy * first;
+            if j == i + 2 {
+                cells[i + 1] += dy * (1.0 - first - last);
+            } else {
+                let second = scale * (1.5 - left_fraction);
+                cells[i + 1] += dy * (second - first);
+                for cell in &mut cells[i + 2..j - 1] {
+                    *cell += dy * scale;
+                }
+                let before_last = ((j - i - 3) as f32).mul_add(scale, second);
+                cells[j - 1] += dy * (1.0 - before_last - last);
+            }
+            cells[j] += dy * last;
+        }
+    }
+
+    /// Returns the coverage at the given coordinates of the image.
+    fn get(&self, x: u32, y: u32) -> f32 {
+        if x < self.x || y < self.y || x >= self.x + self.width {
+            return 0.0;
+        }
+        let index = ((y - self.y) * self.width + x - self.x) as usize;
+        self.values.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// Keeps only the coverage which is inside of the given coverage if `inside` is `true`, or
+    /// outside of it otherwise.
+    fn mask(&mut self, other: &Self, inside: bool) {
+        for (i, value) in self.values.iter_mut().enumerate() {
+            let (x, y) = (i as u32 % self.width, i as u32 / self.width);
+            let covered = other.get(self.x + x, self.y + y);
+            *value *= if inside { covered } else { 1.0 - covered };
+        }
+    }
+
+    /// The bounding box of the rasterized region.
+    fn bounding_box(&self) -> BoundingBox<u32> {
+        let height = self.values.len() as u32 / self.width.max(1);
+        (self.x, self.y, self.x + self.width, self.y + height)
+    }
+
+    /// Plots the given fill over the covered pixels of the image. If `antialiased` is `true`,
+    /// partially covered pixels are plotted with an alpha proportional to their coverage, otherwise
+    /// only pixels which are at least half covered are plotted.
+    fn plot<P: Pixel>(
+        &self,
+        image: &mut Image<P>,
+        fill: &impl Fill<P>,
+        mode: OverlayMode,
+        antialiased: bool,
+    ) {
+        for (i, &value) in self.values.iter().enumerate() {
+            let (x, y) = (
+                self.x + i as u32 % self.width,
+                self.y + i as u32 / self.width,
+            );
+            let alpha = (value * 255.0).round() as u8;
+
+            if !antialiased {
+                if value >= 0.5 {
+                    fill.plot(image, x, y, mode);
+                }
+            } else if alpha == u8::MAX {
+                fill.plot(image, x, y, mode);
+            } else if alpha > 0 {
+                fill.plot_with_alpha(image, x, y, mode, alpha);
+            }
+        }
+    }
+}
+
+/// Renders a shape given a function which returns its outline grown outwards by the given amount
+/// of pixels (or shrunk inwards if negative). The border of the shape is the region between its
+/// outline grown outwards and shrunk inwards according to the position of the border.
+#[allow(clippy::cast_precision_loss)]
+fn render_outlined<P: Pixel>(
+    image: &mut Image<P>,
+    outline: impl Fn(f32) -> Vec<Point>,
+    fill: Option<&impl Fill<P>>,
+    border: Option<&Border<P>>,
+    overlay: OverlayMode,
+    antialiased: bool,
+) {
+    let dimensions = image.dimensions();
+
+    if let Some(fill) = fill {
+        let region = Coverage::rasterize(&[outline(0.0)], FillRule::NonZero, dimensions);
+        region.plot(image, fill, overlay, antialiased);
+    }
+
+    if let Some((inner, outer, color)) = border.map(Border::bounds) {
+        let polygons = [outline(outer as f32), outline(-(inner as f32))];
+        let region = Coverage::rasterize(&polygons, FillRule::EvenOdd, dimensions);
+        region.plot(image, &SolidFill::new(color), overlay, antialiased);
+    }
+}
+
+/// Returns polygons which make up the outline of a stroke of the given width along the given
+/// polylines. Each polygon winds clockwise, so that they can be unioned with the non-zero rule.
+///
+/// If `round_joins` is `true`, consecutive segments are joined by circles.
+fn stroke_polygons(subpaths: &[Subpath], width: f32, round_joins: bool) -> Vec<Vec<Point>> {
+    let radius = width / 2.0;
+    let mut polygons = Vec::new();
+    let mut push = |mut polygon: Vec<Point>| {
+        if signed_area(&polygon) < 0.0 {
+            polygon.reverse();
+        }
+        polygons.push(polygon);
+    };
+
+    for (points, closed) in subpaths {
+        let wrap = if *closed { points.first() } else { None };
+        let segments = points.iter().zip(points.iter().skip(1).chain(wrap));
+
+        for (&(x1, y1), &(x2, y2)) in segments {
+            let length = distance((x1, y1), (x2, y2));
+            let (nx, ny) = ((y1 - y2) / length * radius, (x2 - x1) / length * radius);
+            push(vec![
+                (x1 + nx, y1 + ny),
+                (x2 + nx, y2 + ny),
+                (x2 - nx, y2 - ny),
+                (x1 - nx, y1 - ny),
+            ]);
+        }
+
+        if round_joins {
+            let joins = if *closed {
+                points.as_slice()
+            } else {
+                &points[1..points.len() - 1]
+            };
+            for &center in joins {
+                push(ellipse_points(center, (radius, radius)));
+            }
+        }
+    }
+
+    polygons
+}
+
+/// Rasterizes a border along the given polylines, where `region` is the coverage of the region
+/// enclosed by them. Inset and outset borders are positioned relative to this region.
+#[allow(clippy::cast_precision_loss)]
+fn stroke<P: Pixel>(
+    subpaths: &[Subpath],
+    border: &Border<P>,
+    round_joins: bool,
+    region: &Coverage,
+    dimensions: (u32, u32),
+) -> Coverage {
+    let thickness = border.thickness as f32;
+    // Inset and outset borders are strokes twice as thick, of which only the half inside or
+    // outside of the region is kept
+    let (width, inside) = match border.position {
+        BorderPosition::Center => (thickness, None),
+        BorderPosition::Inset => (thickness * 2.0, Some(true)),
+        BorderPosition::Outset => (thickness * 2.0, Some(false)),
+    };
+
+    let polygons = stroke_polygons(subpaths, width, round_joins);
+    let mut coverage = Coverage::rasterize(&polygons, FillRule::NonZero, dimensions);
+    if let Some(inside) = inside {
+        coverage.mask(region, inside);
+    }
+    coverage
+}
+
 /// A line.
 ///
 /// At its core, this method utilizes
@@ -195,12 +614,6 @@ impl<F: IntoFill> Line<F> {
         self
     }
 
-    #[allow(clippy::missing_const_for_fn)]
-    pub(crate) fn with_fill_color(mut self, color: F::Fill) -> Self {
-        self.color = color;
-        self
-    }
-
     /// Sets the overlay mode of the line.
     #[must_use]
     pub const fn with_mode(mut self, mode: OverlayMode) -> Self {
@@ -483,13 +896,14 @@ pub struct Polygon<F: IntoFill> {
     /// The border of the polygon. Either this or `fill` must be `Some`.
     pub border: Option<Border<F::Pixel>>,
     /// Whether the border should be rounded off by drawing circles at each vertex. This is only
-    /// applied if `border` is `Some`. Additionally, these circles will not antialias.
+    /// applied if `border` is `Some`. Additionally, these circles will only antialias if
+    /// `antialiased` is `true`.
     pub rounded: bool,
     /// The fill color of the polygon. Either this or `border` must be `Some`.
     pub fill: Option<F::Fill>,
     /// The overlay mode of the polygon. If `None`, the image's overlay mode will be used.
     pub overlay: Option<OverlayMode>,
-    /// Whether to antialias the polygon's edges.
+    /// Whether to antialias the polygon's edges, including its border.
     pub antialiased: bool,
 }
 
@@ -741,6 +1155,33 @@ impl<F: IntoFill> Polygon<F> {
             intersections.clear();
         });
     }
+
+    // Used when antialiased. Unlike `rasterize_fill`, vertices lie at the centers of pixels.
+    #[allow(clippy::cast_precision_loss)]
+    fn render_antialiased(&self, image: &mut Image<F::Pixel>, vertices: &[(u32, u32)]) {
+        let dimensions = image.dimensions();
+        let overlay = self.overlay.unwrap_or(image.overlay);
+
+        let mut points = vertices
+            .iter()
+            .map(|&(x, y)| (x as f32 + 0.5, y as f32 + 0.5))
+            .collect::<Vec<_>>();
+        points.dedup();
+        // The polygon is closed, so the last vertex is the first
+        points.pop();
+
+        let polygons = [points];
+        let region = Coverage::rasterize(&polygons, FillRule::EvenOdd, dimensions);
+        if let Some(ref fill) = self.fill {
+            region.plot(image, fill, overlay, true);
+        }
+
+        if let Some(ref border) = self.border {
+            let [points] = polygons;
+            let stroke = stroke(&[(points, true)], border, self.rounded, &region, dimensions);
+            stroke.plot(image, &SolidFill::new(border.color), overlay, true);
+        }
+    }
 }
 
 impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
@@ -753,24 +1194,13 @@ impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
         let image = &mut *image;
         let vertices = self.sanitize_vertices();
 
-        if let Some(ref fill) = self.fill {
-            self.rasterize_fill(image, &vertices);
+        if self.antialiased {
+            self.render_antialiased(image, &vertices);
+            return;
+        }
 
-            if self.border.is_none() && self.antialiased {
-                for edge in vertices.windows(2) {
-                    unsafe {
-                        // SAFETY: windows(2) ensures that there are at least 2 points
-                        let &from = edge.get_unchecked(0);
-                        let &to = edge.get_unchecked(1);
-                        image.draw(
-                            // SAFETY: color is overridden
-                            &Line::new(from, to, std::mem::zeroed::<F>())
-                                .with_fill_color(fill.clone())
-                                .with_antialiased(true),
-                        );
-                    }
-                }
-            }
+        if self.fill.is_some() {
+            self.rasterize_fill(image, &vertices);
         }
 
         if let Some(ref border) = self.border {
@@ -781,7 +1211,6 @@ impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
                     let &to = edge.get_unchecked(1);
                     image.draw(
                         &Line::new(from, to, border.color)
-                            .with_antialiased(self.antialiased)
                             .with_thickness(border.thickness)
                             .with_position(border.position),
                     );
@@ -820,6 +1249,14 @@ pub struct Rectangle<F: IntoFill> {
     pub fill: Option<F::Fill>,
     /// The overlay mode of the rectangle, or None to inherit from the overlay mode of the image.
     pub overlay: Option<OverlayMode>,
+    /// The angle the rectangle is rotated by about its center, in radians clockwise. Defaults to
+    /// `0.0`.
+    pub rotation: f32,
+    /// The radius of the rounded corners of the rectangle, in pixels. Defaults to `0`, which
+    /// results in sharp corners.
+    pub corner_radius: u32,
+    /// Whether to antialias the edges of the rectangle. Defaults to `false`.
+    pub antialiased: bool,
 }
 
 impl<F: IntoFill> Default for Rectangle<F> {
@@ -830,6 +1267,9 @@ impl<F: IntoFill> Default for Rectangle<F> {
             border: None,
             fill: None,
             overlay: None,
+            rotation: 0.0,
+            corner_radius: 0,
+            antialiased: false,
         }
     }
 }
@@ -938,6 +1378,75 @@ impl<F: IntoFill> Rectangle<F> {
         self.overlay = Some(mode);
         self
     }
+
+    /// Sets the angle to rotate the rectangle by about its center, in radians clockwise.
+    ///
+    /// For angles specified in degrees, the [`f32::to_radians`] method can be used for conversion.
+    #[must_use]
+    pub const fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets the radius of the rounded corners of the rectangle, in pixels.
+    #[must_use]
+    pub const fn with_corner_radius(mut self, radius: u32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Sets whether to antialias the edges of the rectangle. If set to `true` and no overlay mode
+    /// has been set, this will also set the overlay mode to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased && self.overlay.is_none() {
+            self.overlay = Some(OverlayMode::Merge);
+        }
+        self
+    }
+
+    /// Returns the outline of the rectangle, grown outwards by the given amount of pixels (or
+    /// shrunk inwards if negative).
+    #[allow(clippy::cast_precision_loss)]
+    fn outline(&self, grow: f32) -> Vec<Point> {
+        let (x, y) = self.position;
+        let (w, h) = self.size;
+        let (x1, y1) = (x as f32 - grow, y as f32 - grow);
+        let (x2, y2) = ((x + w) as f32 + grow, (y + h) as f32 + grow);
+        if x2 <= x1 || y2 <= y1 {
+            return Vec::new();
+        }
+
+        let mut points = if self.corner_radius > 0 {
+            let radius = (self.corner_radius as f32 + grow)
+                .max(0.0)
+                .min((x2 - x1).min(y2 - y1) / 2.0);
+            let corner = |cx: f32, cy: f32, start_angle: f32| {
+                arc_points((cx, cy), (radius, radius), start_angle, FRAC_PI_2)
+            };
+
+            corner(x2 - radius, y1 + radius, -FRAC_PI_2)
+                .chain(corner(x2 - radius, y2 - radius, 0.0))
+                .chain(corner(x1 + radius, y2 - radius, FRAC_PI_2))
+                .chain(corner(x1 + radius, y1 + radius, PI))
+                .collect()
+        } else {
+            vec![(x1, y1), (x2, y1), (x2, y2), (x1, y2)]
+        };
+
+        if self.rotation != 0.0 {
+            let (cx, cy) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+            let (sin, cos) = self.rotation.sin_cos();
+
+            for (x, y) in &mut points {
+                let (dx, dy) = (*x - cx, *y - cy);
+                *x = dx.mul_add(cos, -dy * sin) + cx;
+                *y = dx.mul_add(sin, dy * cos) + cy;
+            }
+        }
+        points
+    }
 }
 
 impl<F: IntoFill> Draw<F::Pixel> for Rectangle<F> {
@@ -951,6 +1460,20 @@ impl<F: IntoFill> Draw<F::Pixel> for Rectangle<F> {
             "rectangle must have a non-zero width and height, have you called .with_size() yet?"
         );
 
+        #[allow(clippy::float_cmp)]
+        if self.antialiased || self.rotation != 0.0 || self.corner_radius > 0 {
+            let overlay = self.overlay.unwrap_or(image.overlay);
+            render_outlined(
+                &mut image,
+                |grow| self.outline(grow),
+                self.fill.as_ref(),
+                self.border.as_ref(),
+                overlay,
+                self.antialiased,
+            );
+            return;
+        }
+
         let (x1, y1) = self.position;
         let (w, h) = self.size;
         // Exclusive bounds
@@ -1010,6 +1533,8 @@ pub struct Ellipse<F: IntoFill> {
     pub fill: Option<F::Fill>,
     // The overlay mode for the ellipse or None to inherit from the image's overlay mode.
     pub overlay: Option<OverlayMode>,
+    /// Whether to antialias the edges of the ellipse. Defaults to `false`.
+    pub antialiased: bool,
 }
 
 impl<F: IntoFill> Default for Ellipse<F> {
@@ -1020,6 +1545,7 @@ impl<F: IntoFill> Default for Ellipse<F> {
             border: None,
             fill: None,
             overlay: None,
+            antialiased: false,
         }
     }
 }
@@ -1128,6 +1654,33 @@ impl<F: IntoFill> Ellipse<F> {
         self
     }
 
+    /// Sets whether to antialias the edges of the ellipse. If set to `true` and no overlay mode
+    /// has been set, this will also set the overlay mode to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased && self.overlay.is_none() {
+            self.overlay = Some(OverlayMode::Merge);
+        }
+        self
+    }
+
+    /// Returns the outline of the ellipse, grown outwards by the given amount of pixels (or shrunk
+    /// inwards if negative).
+    #[allow(clippy::cast_precision_loss)]
+    fn outline(&self, grow: f32) -> Vec<Point> {
+        // The center lies at the center of a pixel, and the ellipse covers the pixels up to
+        // `radii` away from it
+        let (x, y) = self.position;
+        let (rx, ry) = self.radii;
+        let radii = (rx as f32 + 0.5 + grow, ry as f32 + 0.5 + grow);
+        if radii.0 <= 0.0 || radii.1 <= 0.0 {
+            return Vec::new();
+        }
+
+        ellipse_points((x as f32 + 0.5, y as f32 + 0.5), radii)
+    }
+
     // Used when there is no border
     #[allow(clippy::cast_possible_wrap)]
     fn rasterize_filled_circle(&self, image: &mut Image<F::Pixel>) {
@@ -1366,6 +1919,19 @@ impl<F: IntoFill> Draw<F::Pixel> for Ellipse<F> {
 
         let image = &mut *image;
 
+        if self.antialiased {
+            let overlay = self.overlay.unwrap_or(image.overlay);
+            render_outlined(
+                image,
+                |grow| self.outline(grow),
+                self.fill.as_ref(),
+                self.border.as_ref(),
+                overlay,
+                true,
+            );
+            return;
+        }
+
         if self.border.is_none() {
             if self.radii.0 == self.radii.1 {
                 self.rasterize_filled_circle(image);
@@ -1384,6 +1950,380 @@ impl<F: IntoFill> Draw<F::Pixel> for Ellipse<F> {
     }
 }
 
+/// A single command of a [`Path`].
+///
+/// Angles are measured in radians clockwise from the positive x-axis, since the y-axis points
+/// downwards.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathCommand {
+    /// Begins a new subpath at the given point.
+    MoveTo(f32, f32),
+    /// Draws a straight line from the current point to the given point.
+    LineTo(f32, f32),
+    /// Draws a quadratic Bézier curve from the current point to the given end point.
+    QuadTo {
+        /// The control point of the curve.
+        control: (f32, f32),
+        /// The end point of the curve.
+        end: (f32, f32),
+    },
+    /// Draws a cubic Bézier curve from the current point to the given end point.
+    CubicTo {
+        /// The first control point of the curve.
+        control1: (f32, f32),
+        /// The second control point of the curve.
+        control2: (f32, f32),
+        /// The end point of the curve.
+        end: (f32, f32),
+    },
+    /// Draws an arc of an ellipse. If there is a current point, a straight line is first drawn
+    /// from it to the start of the arc.
+    Arc {
+        /// The center of the ellipse.
+        center: (f32, f32),
+        /// The horizontal and vertical radii of the ellipse.
+        radii: (f32, f32),
+        /// The angle of the start of the arc.
+        start_angle: f32,
+        /// The angle the arc spans, which is clockwise if positive and counterclockwise if
+        /// negative.
+        sweep_angle: f32,
+    },
+    /// Closes the current subpath by drawing a straight line back to its start.
+    Close,
+}
+
+/// The number of line segments needed to approximate a curve whose second derivative has the
+/// given maximum magnitude over the unit interval.
+fn segment_count(curvature: f32) -> usize {
+    ((curvature / (8.0 * FLATTEN_TOLERANCE)).sqrt().ceil() as usize).clamp(1, 1024)
+}
+
+/// A vector path made up of straight lines, Bézier curves, and arcs, which can be filled and
+/// stroked.
+///
+/// Unlike other shapes, paths are positioned with floating-point coordinates. A path consists of
+/// one or more subpaths, each of which begins with [`PathCommand::MoveTo`]. When filling, all
+/// subpaths are implicitly closed and the regions inside of them are determined by the
+/// [`FillRule`] of the path.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// let mut image = Image::new(256, 256, Rgba::white());
+/// let heart = Path::new()
+///     .move_to(128.0, 80.0)
+///     .cubic_to(128.0, 40.0, 40.0, 40.0, 40.0, 100.0)
+///     .cubic_to(40.0, 150.0, 128.0, 200.0, 128.0, 220.0)
+///     .cubic_to(128.0, 200.0, 216.0, 150.0, 216.0, 100.0)
+///     .cubic_to(216.0, 40.0, 128.0, 40.0, 128.0, 80.0)
+///     .close()
+///     .with_fill(Rgba::new(220, 20, 60, 255))
+///     .with_border(Border::new(Rgba::black(), 4));
+///
+/// image.draw(&heart);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Path<F: IntoFill> {
+    /// The commands that make up the path, in order.
+    pub commands: Vec<PathCommand>,
+    /// The fill of the path, or None if there is no fill.
+    pub fill: Option<F::Fill>,
+    /// The rule used to determine which regions of the path are filled.
+    pub fill_rule: FillRule,
+    /// The border, or stroke, of the path, or None if there is no border.
+    ///
+    /// Joins between segments are rounded, and the ends of subpaths that are not closed are flat.
+    /// Inset and outset borders are positioned relative to the regions that would be filled.
+    pub border: Option<Border<F::Pixel>>,
+    /// The overlay mode of the path, or None to inherit from the overlay mode of the image.
+    pub overlay: Option<OverlayMode>,
+    /// Whether to antialias the path. Defaults to `false`.
+    pub antialiased: bool,
+}
+
+impl<F: IntoFill> Default for Path<F> {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            fill: None,
+            fill_rule: FillRule::default(),
+            border: None,
+            overlay: None,
+            antialiased: false,
+        }
+    }
+}
+
+impl<F: IntoFill> Path<F> {
+    /// Creates a new empty path.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new path from the given commands.
+    #[must_use]
+    pub fn from_commands(commands: impl IntoIterator<Item = PathCommand>) -> Self {
+        Self {
+            commands: commands.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a command to the path in place.
+    pub fn push_command(&mut self, command: PathCommand) {
+        self.commands.push(command);
+    }
+
+    /// Returns a slice of the commands in the path.
+    #[must_use]
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    /// Begins a new subpath at the given point.
+    #[must_use]
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.push_command(PathCommand::MoveTo(x, y));
+        self
+    }
+
+    /// Draws a straight line from the current point to the given point.
+    #[must_use]
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.push_command(PathCommand::LineTo(x, y));
+        self
+    }
+
+    /// Draws a quadratic Bézier curve from the current point to `(x, y)` with the control point
+    /// `(cx, cy)`.
+    #[must_use]
+    pub fn quad_to(mut self, cx: f32, cy: f32, x: f32, y: f32) -> Self {
+        self.push_command(PathCommand::QuadTo {
+            control: (cx, cy),
+            end: (x, y),
+        });
+        self
+    }
+
+    /// Draws a cubic Bézier curve from the current point to `(x, y)` with the control points
+    /// `(c1x, c1y)` and `(c2x, c2y)`.
+    #[must_use]
+    pub fn cubic_to(mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> Self {
+        self.push_command(PathCommand::CubicTo {
+            control1: (c1x, c1y),
+            control2: (c2x, c2y),
+            end: (x, y),
+        });
+        self
+    }
+
+    /// Draws an arc of the circle centered at `(cx, cy)` with the given radius, starting at
+    /// `start_angle` and spanning `sweep_angle`, both in radians. Positive sweep angles are
+    /// clockwise. If there is a current point, a straight line is first drawn from it to the start
+    /// of the arc.
+    ///
+    /// For arcs of ellipses, use [`PathCommand::Arc`] with [`Self::push_command`].
+    #[must_use]
+    pub fn arc(
+        mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+    ) -> Self {
+        self.push_command(PathCommand::Arc {
+            center: (cx, cy),
+            radii: (radius, radius),
+            start_angle,
+            sweep_angle,
+        });
+        self
+    }
+
+    /// Closes the current subpath by drawing a straight line back to its start.
+    #[must_use]
+    pub fn close(mut self) -> Self {
+        self.push_command(PathCommand::Close);
+        self
+    }
+
+    /// Sets the fill of the path.
+    #[must_use]
+    pub fn with_fill(mut self, fill: F) -> Self {
+        self.fill = Some(fill.into_fill());
+        self
+    }
+
+    /// Sets the rule used to determine which regions of the path are filled.
+    #[must_use]
+    pub const fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    /// Sets the border, or stroke, of the path.
+    #[must_use]
+    pub const fn with_border(mut self, border: Border<F::Pixel>) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Sets the overlay mode of the path.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Sets whether to antialias the path. If set to `true` and no overlay mode has been set, this
+    /// will also set the overlay mode to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased && self.overlay.is_none() {
+            self.overlay = Some(OverlayMode::Merge);
+        }
+        self
+    }
+
+    /// Approximates each subpath of the path with a polyline.
+    #[allow(clippy::many_single_char_names)]
+    fn flatten(&self) -> Vec<Subpath> {
+        let mut subpaths = Vec::new();
+        let mut current: Subpath = (Vec::new(), false);
+        // Starts a new subpath at the given point if there is no current subpath
+        let begin = |subpaths: &mut Vec<Subpath>, current: &mut Subpath, point: Point| {
+            if current.1 || current.0.is_empty() {
+                let start = if current.1 { current.0[0] } else { point };
+                subpaths.push(std::mem::replace(current, (vec![start], false)));
+            }
+        };
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(x, y) => {
+                    subpaths.push(std::mem::replace(&mut current, (vec![(x, y)], false)));
+                }
+                PathCommand::LineTo(x, y) => {
+                    begin(&mut subpaths, &mut current, (x, y));
+                    current.0.push((x, y));
+                }
+                PathCommand::QuadTo { control, end } => {
+                    begin(&mut subpaths, &mut current, control);
+                    let start = *current.0.last().unwrap_or(&control);
+                    let curvature = 2.0
+                        * distance(
+                            (start.0 + end.0, start.1 + end.1),
+                            (2.0 * control.0, 2.0 * control.1),
+                        );
+                    let count = segment_count(curvature);
+
+                    current.0.extend((1..=count).map(|i| {
+                        let t = i as f32 / count as f32;
+                        let (a, b, c) = ((1.0 - t).powi(2), 2.0 * t * (1.0 - t), t * t);
+                        (
+                            a * start.0 + b * control.0 + c * end.0,
+                            a * start.1 + b * control.1 + c * end.1,
+                        )
+                    }));
+                }
+                PathCommand::CubicTo {
+                    control1,
+                    control2,
+                    end,
+                } => {
+                    begin(&mut subpaths, &mut current, control1);
+                    let start = *current.0.last().unwrap_or(&control1);
+                    let second_difference = |p: Point, q: Point, r: Point| {
+                        distance((p.0 + r.0, p.1 + r.1), (2.0 * q.0, 2.0 * q.1))
+                    };
+                    let curvature = 6.0
+                        * second_difference(start, control1, control2)
+                            .max(second_difference(control1, control2, end));
+                    let count = segment_count(curvature);
+
+                    current.0.extend((1..=count).map(|i| {
+                        let t = i as f32 / count as f32;
+                        let u = 1.0 - t;
+                        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                        (
+                            a * start.0 + b * control1.0 + c * control2.0 + d * end.0,
+                            a * start.1 + b * control1.1 + c * control2.1 + d * end.1,
+                        )
+                    }));
+                }
+                PathCommand::Arc {
+                    center,
+                    radii,
+                    start_angle,
+                    sweep_angle,
+                } => {
+                    let mut points = arc_points(center, radii, start_angle, sweep_angle);
+                    if let Some(first) = points.next() {
+                        begin(&mut subpaths, &mut current, first);
+                        current.0.push(first);
+                    }
+                    current.0.extend(points);
+                }
+                PathCommand::Close => current.1 = !current.0.is_empty(),
+            }
+        }
+        subpaths.push(current);
+
+        // Remove subpaths without any segments along with repeated points
+        subpaths
+            .into_iter()
+            .map(|(mut points, closed)| {
+                points.dedup();
+                if closed && points.len() > 1 && points.first() == points.last() {
+                    points.pop();
+                }
+                (points, closed)
+            })
+            .filter(|(points, _)| points.len() > 1)
+            .collect()
+    }
+}
+
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+impl<F: IntoFill> Draw<F::Pixel> for Path<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        assert!(
+            self.fill.is_some() || self.border.is_some(),
+            "must provide one of either fill or border, try calling .with_fill()"
+        );
+
+        let image = &mut *image;
+        let dimensions = image.dimensions();
+        let overlay = self.overlay.unwrap_or(image.overlay);
+
+        let subpaths = self.flatten();
+        let polygons = subpaths
+            .iter()
+            .map(|(points, _)| points.clone())
+            .collect::<Vec<_>>();
+        let region = Coverage::rasterize(&polygons, self.fill_rule, dimensions);
+
+        if let Some(ref fill) = self.fill {
+            let fill = fill.clone().with_bounding_box(region.bounding_box());
+            region.plot(image, &fill, overlay, self.antialiased);
+        }
+
+        if let Some(ref border) = self.border {
+            let stroke = stroke(&subpaths, border, true, &region, dimensions);
+            stroke.plot(
+                image,
+                &SolidFill::new(border.color),
+                overlay,
+                self.antialiased,
+            );
+        }
+    }
+}
+
 /// Pastes or overlays an image on top of another image.
 #[derive(Clone)]
 pub struct Paste<'img, 'mask, P: Pixel> {
@@ -1492,3 +2432,148 @@ impl<'img, 'mask, P: Pixel> Draw<P> for Paste<'img, 'mask, P> {
         }
     }
 }
+
+/// Composites an image onto another image using a Porter-Duff [`CompositeOperator`], where the
+/// image being drawn is the source and the image being drawn on is the destination.
+///
+/// This is like [`Paste`] with [`OverlayMode::Composite`], except that it can also composite the
+/// pixels of the destination which are not covered by the source, see
+/// [`with_unbounded`][Self::with_unbounded].
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::<Rgba>::open("sample.png")?;
+/// let stencil = Image::open("stencil.png")?;
+///
+/// // Keep only the parts of the image covered by the stencil, clearing everything else
+/// image.draw(
+///     &Composite::new(&stencil, CompositeOperator::DestinationIn)
+///         .with_position(64, 64)
+///         .with_unbounded(true),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Composite<'img, 'mask, P: Pixel> {
+    /// The position of the source image. This is where its top-left corner is placed.
+    pub position: (u32, u32),
+    /// A reference to the source image.
+    pub image: &'img Image<P>,
+    /// A reference to an image that masks out pixels of the source image, which leaves the
+    /// corresponding pixels of the destination untouched. See [`Paste::mask`] for more
+    /// information.
+    pub mask: Option<&'mask Image<BitPixel>>,
+    /// The compositing operator to use.
+    pub operator: CompositeOperator,
+    /// Whether pixels of the destination which are not covered by the source are composited with
+    /// a fully transparent source. Defaults to `false`, which leaves them untouched.
+    pub unbounded: bool,
+}
+
+impl<'img, 'mask, P: Pixel> Composite<'img, 'mask, P> {
+    /// Creates a new composite of the given source image with the given operator, with the
+    /// position defaulting to `(0, 0)`.
+    #[must_use]
+    pub const fn new(image: &'img Image<P>, operator: CompositeOperator) -> Self {
+        Self {
+            position: (0, 0),
+            image,
+            mask: None,
+            operator,
+            unbounded: false,
+        }
+    }
+
+    /// Sets the position of the source image. This is where its top-left corner is placed.
+    #[must_use]
+    pub const fn with_position(mut self, x: u32, y: u32) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the mask image to use. Currently this is only limited to [`BitPixel`] images.
+    ///
+    /// # Panics
+    /// * The mask image has different dimensions than the source image.
+    #[must_use]
+    pub fn with_mask(mut self, mask: &'mask Image<BitPixel>) -> Self {
+        assert_eq!(
+            self.image.dimensions(),
+            mask.dimensions(),
+            "mask image with dimensions {:?} has different dimensions \
+            than source image with dimensions {:?}",
+            mask.dimensions(),
+            self.image.dimensions(),
+        );
+
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Sets the compositing operator to use.
+    #[must_use]
+    pub const fn with_operator(mut self, operator: CompositeOperator) -> Self {
+        self.operator = operator;
+        self
+    }
+
+    /// Sets whether pixels of the destination which are not covered by the source should be
+    /// composited with a fully transparent source. This matters for operators which modify the
+    /// destination where the source is transparent, such as
+    /// [`DestinationIn`][CompositeOperator::DestinationIn] or [`Clear`][CompositeOperator::Clear].
+    ///
+    /// Pixel types without an alpha channel cannot be transparent, so this has no effect on them.
+    #[must_use]
+    pub const fn with_unbounded(mut self, unbounded: bool) -> Self {
+        self.unbounded = unbounded;
+        self
+    }
+}
+
+impl<'img, 'mask, P: Pixel> Draw<P> for Composite<'img, 'mask, P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let image = &mut *image;
+        let (x1, y1) = self.position;
+        let (w, h) = self.image.dimensions();
+        let (x2, y2) = (x1.saturating_add(w), y1.saturating_add(h));
+        // The default pixel of pixel types with alpha is fully transparent
+        let unbounded =
+            self.unbounded && matches!(P::COLOR_TYPE, ColorType::LumaA | ColorType::Rgba);
+
+        // Only the pixels covered by the source are visited, unless the composite is unbounded
+        let (xs, ys) = if unbounded {
+            (0..image.width(), 0..image.height())
+        } else {
+            (
+                x1.min(image.width())..x2.min(image.width()),
+                y1.min(image.height())..y2.min(image.height()),
+            )
+        };
+
+        for y in ys {
+            for x in xs.clone() {
+                let source = if (x1..x2).contains(&x) && (y1..y2).contains(&y) {
+                    let (j, i) = (x - x1, y - y1);
+                    if !self
+                        .mask
+                        .and_then(|mask| mask.get_pixel(j, i).map(BitPixel::value))
+                        .unwrap_or(true)
+                    {
+                        continue;
+                    }
+                    *self.image.pixel(j, i)
+                } else if unbounded {
+                    P::default()
+                } else {
+                    continue;
+                };
+
+                let pixel = image.pixel_mut(x, y);
+                *pixel = pixel.composite(source, self.operator);
+            }
+        }
+    }
+}
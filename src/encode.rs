@@ -1,6 +1,8 @@
 //! Houses Encoder, Decoder, and frame iterator traits.
 
-use crate::{ColorType, DisposalMethod, Error, Frame, Image, ImageSequence, LoopCount, Pixel};
+use crate::{
+    ColorType, DisposalMethod, Error, Frame, Image, ImageSequence, LoopCount, Metadata, Pixel,
+};
 use std::ops::DerefMut;
 use std::{
     io::{Read, Write},
@@ -9,7 +11,7 @@ use std::{
 };
 
 mod sealed {
-    use super::{ColorType, DisposalMethod, Duration, Frame, Image, LoopCount, Pixel};
+    use super::{ColorType, DisposalMethod, Duration, Frame, Image, LoopCount, Metadata, Pixel};
 
     pub trait HasEncoderMetadata<C: Default, P: Pixel>: Sized {
         fn width(&self) -> u32;
@@ -23,6 +25,9 @@ mod sealed {
         fn palette(&self) -> Option<&[P::Color]> {
             None
         }
+        fn metadata(&self) -> Option<&Metadata> {
+            None
+        }
         fn config(self) -> C {
             C::default()
         }
@@ -80,6 +85,9 @@ impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a Image<P> {
     fn palette(&self) -> Option<&[P::Color]> {
         Image::palette(self)
     }
+    fn metadata(&self) -> Option<&Metadata> {
+        Some(Image::metadata(self))
+    }
 }
 
 impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a Frame<P> {
@@ -101,6 +109,9 @@ impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a Frame<P> {
     fn palette(&self) -> Option<&[P::Color]> {
         Image::palette(self.image())
     }
+    fn metadata(&self) -> Option<&Metadata> {
+        Some(Image::metadata(self.image()))
+    }
 }
 
 impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a ImageSequence<P> {
@@ -138,6 +149,11 @@ impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a ImageSequence<P>
         self.first_frame()
             .and_then(|frame| Image::palette(frame.image()))
     }
+
+    fn metadata(&self) -> Option<&Metadata> {
+        self.first_frame()
+            .map(|frame| Image::metadata(frame.image()))
+    }
 }
 
 /// Manually configured encoder metadata. This is used to provide fine-grained control over the
@@ -168,6 +184,8 @@ pub struct EncoderMetadata<P: Pixel> {
     pub bit_depth: u8,
     /// The palette of the image.
     pub palette: Option<Box<[P::Color]>>,
+    /// Auxiliary metadata to embed into the image, such as EXIF data or an ICC profile.
+    pub metadata: Metadata,
 }
 
 macro_rules! impl_from_metadata {
@@ -182,6 +200,7 @@ macro_rules! impl_from_metadata {
                         color_type: HasEncoderMetadata::<(), P>::color_type(&metadata),
                         bit_depth: HasEncoderMetadata::<(), P>::bit_depth(&metadata),
                         palette: HasEncoderMetadata::<(), P>::palette(&metadata).map(|p| p.to_vec().into_boxed_slice()),
+                        metadata: HasEncoderMetadata::<(), P>::metadata(&metadata).cloned().unwrap_or_default(),
                     }
                 }
             }
@@ -210,6 +229,9 @@ impl<C: Default, P: Pixel> HasEncoderMetadata<C, P> for EncoderMetadata<P> {
     fn palette(&self) -> Option<&[P::Color]> {
         self.palette.as_deref()
     }
+    fn metadata(&self) -> Option<&Metadata> {
+        Some(&self.metadata)
+    }
 }
 
 impl<P: Pixel> EncoderMetadata<P> {
@@ -232,6 +254,7 @@ impl<P: Pixel> EncoderMetadata<P> {
             color_type: P::COLOR_TYPE,
             bit_depth: P::BIT_DEPTH,
             palette: None,
+            metadata: Metadata::new(),
         }
     }
 
@@ -302,6 +325,13 @@ impl<P: Pixel> EncoderMetadata<P> {
         self.palette = Some(palette.to_vec().into_boxed_slice());
         self
     }
+
+    /// Sets the metadata to embed into the image.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
 }
 
 /// An [`EncoderMetadata`] with additional configuration parameters for the specific encoder.
@@ -332,6 +362,9 @@ impl<C: Default, P: Pixel> HasEncoderMetadata<C, P> for EncoderMetadataWithConfi
     fn palette(&self) -> Option<&[P::Color]> {
         HasEncoderMetadata::<C, _>::palette(&self.metadata)
     }
+    fn metadata(&self) -> Option<&Metadata> {
+        HasEncoderMetadata::<C, _>::metadata(&self.metadata)
+    }
     fn config(self) -> C {
         self.config
     }
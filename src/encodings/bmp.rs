@@ -0,0 +1,774 @@
+//! A native, dependency-free implementation of the BMP (Windows bitmap) format.
+
+use super::ColorType;
+use crate::{
+    encode::{self, Decoder, Encoder},
+    pixel::assume_pixel_from_palette,
+    Dynamic, Error, Image, ImageFormat, Metadata, OverlayMode, Pixel, Result, Rgb,
+    SingleFrameIterator,
+};
+
+use std::{
+    io::{Read, Write},
+    marker::PhantomData,
+    num::NonZeroU32,
+};
+
+const FILE_HEADER_SIZE: u32 = 14;
+const INFO_HEADER_SIZE: u32 = 40;
+const V4_HEADER_SIZE: u32 = 108;
+
+const BI_RGB: u32 = 0;
+const BI_RLE8: u32 = 1;
+const BI_RLE4: u32 = 2;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+/// Pixels per meter, equivalent to 72 DPI.
+const DEFAULT_RESOLUTION: i32 = 2835;
+
+macro_rules! malformed {
+    ($($t:tt)+) => {{
+        Error::DecodingError(format!("malformed bmp: {}", format_args!($($t)+)))
+    }};
+}
+
+/// BMP encoder options.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BmpEncoderOptions {
+    /// Whether to compress 8-bit paletted and grayscale images with run-length encoding (RLE8).
+    pub rle: bool,
+}
+
+impl BmpEncoderOptions {
+    /// Creates a new set of options with the default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { rle: false }
+    }
+
+    /// Sets whether to compress 8-bit paletted and grayscale images with run-length encoding.
+    /// This has no effect on images encoded with any other bit depth.
+    #[must_use]
+    pub const fn with_rle(mut self, rle: bool) -> Self {
+        self.rle = rle;
+        self
+    }
+}
+
+/// The layout the pixel data will be written in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum BmpLayout {
+    /// 1-bit black and white, used for [`crate::BitPixel`].
+    Bilevel,
+    /// 8-bit indices into the given palette.
+    Indexed,
+    /// 24-bit BGR.
+    Bgr,
+    /// 32-bit BGRA, written with bitfields and a V4 header so that alpha is preserved.
+    Bgra,
+}
+
+impl BmpLayout {
+    const fn bits_per_pixel(self) -> u16 {
+        match self {
+            Self::Bilevel => 1,
+            Self::Indexed => 8,
+            Self::Bgr => 24,
+            Self::Bgra => 32,
+        }
+    }
+
+    const fn header_size(self) -> u32 {
+        match self {
+            Self::Bgra => V4_HEADER_SIZE,
+            _ => INFO_HEADER_SIZE,
+        }
+    }
+}
+
+/// A native BMP encoder.
+///
+/// Images are encoded as follows:
+/// * 1-bit images (i.e. [`crate::BitPixel`]) are encoded as 1-bit monochrome bitmaps.
+/// * Grayscale and [`crate::PalettedRgb`] images are encoded as 8-bit paletted bitmaps,
+///   optionally compressed with RLE8 (see [`BmpEncoderOptions::with_rle`]).
+/// * RGB images are encoded as 24-bit bitmaps.
+/// * Images with an alpha channel are encoded as 32-bit bitmaps with a `BITMAPV4HEADER`.
+///
+/// BMP does not support image sequences, so only the first frame added will be encoded.
+pub struct BmpEncoder<P: Pixel, W: Write> {
+    layout: BmpLayout,
+    palette: Vec<Rgb>,
+    rle: bool,
+    writer: Option<W>,
+    _marker: PhantomData<P>,
+}
+
+impl<P: Pixel, W: Write> BmpEncoder<P, W> {
+    fn encode_rows(&self, image: &Image<P>) -> Vec<u8> {
+        let width = image.width() as usize;
+        let stride = row_stride(image.width(), self.layout.bits_per_pixel());
+        let mut data = Vec::with_capacity(stride * image.height() as usize);
+
+        // BMP rows are stored bottom-up
+        for row in image.data.chunks_exact(width).rev() {
+            let start = data.len();
+
+            match self.layout {
+                BmpLayout::Bilevel => {
+                    for byte in row.chunks(8) {
+                        data.push(byte.iter().enumerate().fold(0, |acc, (i, p)| {
+                            acc | (u8::from(p.as_rgb().r > 127) << (7 - i))
+                        }));
+                    }
+                }
+                BmpLayout::Indexed => data.extend(row.iter().map(|p| self.index_of(p))),
+                BmpLayout::Bgr => {
+                    for Rgb { r, g, b } in row.iter().map(Pixel::as_rgb) {
+                        data.extend([b, g, r]);
+                    }
+                }
+                BmpLayout::Bgra => {
+                    for p in row.iter().map(Pixel::as_rgba) {
+                        data.extend([p.b, p.g, p.r, p.a]);
+                    }
+                }
+            }
+            data.resize(start + stride, 0);
+        }
+
+        data
+    }
+
+    fn encode_rle8(&self, image: &Image<P>) -> Vec<u8> {
+        let width = image.width() as usize;
+        let mut data = Vec::new();
+
+        for row in image.data.chunks_exact(width).rev() {
+            let indices = row.iter().map(|p| self.index_of(p)).collect::<Vec<_>>();
+            let mut i = 0;
+
+            while i < indices.len() {
+                let value = indices[i];
+                let run = indices[i..]
+                    .iter()
+                    .take(255)
+                    .take_while(|&&v| v == value)
+                    .count();
+
+                data.extend([run as u8, value]);
+                i += run;
+            }
+            // End of line
+            data.extend([0, 0]);
+        }
+        // End of bitmap
+        data.extend([0, 1]);
+
+        data
+    }
+
+    fn index_of(&self, pixel: &P) -> u8 {
+        if P::COLOR_TYPE.is_paletted() {
+            pixel.as_bytes().as_ref()[0]
+        } else {
+            pixel.as_rgb().r
+        }
+    }
+}
+
+fn row_stride(width: u32, bits_per_pixel: u16) -> usize {
+    ((width as usize * bits_per_pixel as usize + 31) >> 5) << 2
+}
+
+/// Allocates a zeroed buffer of `width * height * channels` bytes, returning an error instead of
+/// aborting if the dimensions are too large to allocate.
+fn allocate(width: usize, height: usize, channels: usize) -> Result<Vec<u8>> {
+    let len = width
+        .checked_mul(height)
+        .and_then(|len| len.checked_mul(channels))
+        .ok_or_else(|| malformed!("image dimensions are too large"))?;
+
+    let mut buffer = Vec::new();
+    buffer
+        .try_reserve_exact(len)
+        .map_err(|_| malformed!("image dimensions are too large"))?;
+    buffer.resize(len, 0);
+    Ok(buffer)
+}
+
+/// Ensures that the given pixel data holds `height` rows of `stride` bytes, where the last row only
+/// needs `last_row` bytes. This rejects dimensions that the data cannot cover before allocating.
+fn check_length(data: &[u8], stride: usize, height: usize, last_row: usize) -> Result<()> {
+    let required = stride
+        .checked_mul(height.saturating_sub(1))
+        .and_then(|len| len.checked_add(last_row));
+
+    match required {
+        Some(required) if height == 0 || required <= data.len() => Ok(()),
+        _ => Err(malformed!("pixel data is truncated")),
+    }
+}
+
+impl<P: Pixel, W: Write> Encoder<P, W> for BmpEncoder<P, W> {
+    type Config = BmpEncoderOptions;
+
+    fn new(dest: W, metadata: impl encode::HasEncoderMetadata<Self::Config, P>) -> Result<Self> {
+        let (layout, palette) = match (metadata.color_type(), metadata.bit_depth()) {
+            (ColorType::Luma, 1) => (BmpLayout::Bilevel, vec![Rgb::black(), Rgb::white()]),
+            (ColorType::Luma, _) => (
+                BmpLayout::Indexed,
+                (0..=255).map(|l| Rgb::new(l, l, l)).collect(),
+            ),
+            (ColorType::PaletteRgb, _) => {
+                let palette = metadata
+                    .palette()
+                    .expect("no palette for paletted image?")
+                    .iter()
+                    .map(Pixel::as_rgb)
+                    .collect::<Vec<_>>();
+
+                if palette.len() > 256 {
+                    return Err(Error::EncodingError(
+                        "bmp palettes can hold at most 256 colors".to_string(),
+                    ));
+                }
+                (BmpLayout::Indexed, palette)
+            }
+            (ColorType::Rgb, _) => (BmpLayout::Bgr, Vec::new()),
+            // BMP palettes cannot hold alpha values, so these are flattened into true color
+            (ColorType::LumaA | ColorType::Rgba | ColorType::PaletteRgba, _) => {
+                (BmpLayout::Bgra, Vec::new())
+            }
+            (ColorType::Dynamic, _) => return Err(Error::UnsupportedColorType),
+        };
+
+        Ok(Self {
+            layout,
+            palette,
+            rle: metadata.config().rle,
+            writer: Some(dest),
+            _marker: PhantomData,
+        })
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn add_frame(&mut self, frame: &impl encode::FrameLike<P>) -> Result<()> {
+        // BMP cannot hold more than one frame, so any subsequent frames are ignored
+        let mut writer = match self.writer.take() {
+            Some(writer) => writer,
+            None => return Ok(()),
+        };
+        let image = frame.image();
+        let rle = self.rle && self.layout == BmpLayout::Indexed;
+        let data = if rle {
+            self.encode_rle8(image)
+        } else {
+            self.encode_rows(image)
+        };
+
+        let header_size = self.layout.header_size();
+        let offset = FILE_HEADER_SIZE + header_size + self.palette.len() as u32 * 4;
+        let mut out = Vec::with_capacity(offset as usize + data.len());
+
+        // BITMAPFILEHEADER
+        out.extend_from_slice(b"BM");
+        out.extend((offset + data.len() as u32).to_le_bytes());
+        out.extend([0; 4]);
+        out.extend(offset.to_le_bytes());
+
+        // BITMAPINFOHEADER
+        out.extend(header_size.to_le_bytes());
+        out.extend((image.width() as i32).to_le_bytes());
+        out.extend((image.height() as i32).to_le_bytes());
+        out.extend(1_u16.to_le_bytes());
+        out.extend(self.layout.bits_per_pixel().to_le_bytes());
+        out.extend(
+            match self.layout {
+                BmpLayout::Bgra => BI_BITFIELDS,
+                _ if rle => BI_RLE8,
+                _ => BI_RGB,
+            }
+            .to_le_bytes(),
+        );
+        out.extend((data.len() as u32).to_le_bytes());
+        out.extend(DEFAULT_RESOLUTION.to_le_bytes());
+        out.extend(DEFAULT_RESOLUTION.to_le_bytes());
+        out.extend((self.palette.len() as u32).to_le_bytes());
+        out.extend(0_u32.to_le_bytes());
+
+        if self.layout == BmpLayout::Bgra {
+            // BITMAPV4HEADER extension: channel masks, LCS_sRGB and unused endpoints/gamma
+            for mask in [0x00FF_0000_u32, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+                out.extend(mask.to_le_bytes());
+            }
+            out.extend(b"BGRs");
+            out.extend([0; 48]);
+        }
+
+        for Rgb { r, g, b } in &self.palette {
+            out.extend([*b, *g, *r, 0]);
+        }
+        out.extend(data);
+
+        writer.write_all(&out)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A channel in a bitfield-encoded pixel.
+#[derive(Copy, Clone)]
+struct Bitfield {
+    shift: u32,
+    max: u32,
+    mask: u32,
+}
+
+impl Bitfield {
+    const fn new(mask: u32) -> Self {
+        Self {
+            shift: mask.trailing_zeros() % 32,
+            max: if mask == 0 {
+                0
+            } else {
+                mask >> mask.trailing_zeros()
+            },
+            mask,
+        }
+    }
+
+    #[allow(clippy::cast_lossless)]
+    const fn read(self, value: u32) -> u8 {
+        if self.max == 0 {
+            return u8::MAX;
+        }
+        (((value & self.mask) >> self.shift) as u64 * 255 / self.max as u64) as u8
+    }
+}
+
+/// Parsed BMP headers.
+struct BmpInfo {
+    width: u32,
+    height: u32,
+    top_down: bool,
+    bits_per_pixel: u16,
+    compression: u32,
+    masks: [u32; 4],
+    palette: Vec<Rgb>,
+    data_offset: usize,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| malformed!("unexpected end of header"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| malformed!("unexpected end of header"))
+}
+
+impl BmpInfo {
+    #[allow(clippy::cast_possible_wrap)]
+    fn parse(data: &[u8]) -> Result<Self> {
+        if !data.starts_with(b"BM") {
+            return Err(malformed!("invalid signature"));
+        }
+        let data_offset = read_u32(data, 10)? as usize;
+        let header_size = read_u32(data, 14)?;
+        let header = FILE_HEADER_SIZE as usize;
+
+        let (width, height, bits_per_pixel, compression, colors_used, palette_entry_size) =
+            if header_size == 12 {
+                // BITMAPCOREHEADER
+                (
+                    i32::from(read_u16(data, header + 4)?),
+                    i32::from(read_u16(data, header + 6)?),
+                    read_u16(data, header + 10)?,
+                    BI_RGB,
+                    0,
+                    3,
+                )
+            } else if header_size >= INFO_HEADER_SIZE {
+                (
+                    read_u32(data, header + 4)? as i32,
+                    read_u32(data, header + 8)? as i32,
+                    read_u16(data, header + 14)?,
+                    read_u32(data, header + 16)?,
+                    read_u32(data, header + 32)?,
+                    4,
+                )
+            } else {
+                return Err(malformed!("unsupported header size {header_size}"));
+            };
+
+        if width <= 0 || height == 0 {
+            return Err(malformed!("invalid dimensions {width}x{height}"));
+        }
+
+        let mut palette_offset = header + header_size as usize;
+        let masks = match compression {
+            BI_RGB | BI_RLE8 | BI_RLE4 => match bits_per_pixel {
+                16 => [0x7C00, 0x03E0, 0x001F, 0],
+                24 | 32 => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+                _ => [0; 4],
+            },
+            BI_BITFIELDS | BI_ALPHABITFIELDS => {
+                let has_alpha_mask = compression == BI_ALPHABITFIELDS || header_size >= 56;
+                // Masks always immediately follow the BITMAPINFOHEADER fields, but are only
+                // considered part of the header in V2 headers and above
+                let masks_offset = header + INFO_HEADER_SIZE as usize;
+                if header_size == INFO_HEADER_SIZE {
+                    palette_offset += if compression == BI_ALPHABITFIELDS {
+                        16
+                    } else {
+                        12
+                    };
+                }
+
+                [
+                    read_u32(data, masks_offset)?,
+                    read_u32(data, masks_offset + 4)?,
+                    read_u32(data, masks_offset + 8)?,
+                    if has_alpha_mask {
+                        read_u32(data, masks_offset + 12)?
+                    } else {
+                        0
+                    },
+                ]
+            }
+            _ => {
+                return Err(Error::DecodingError(format!(
+                    "unsupported bmp compression method {compression}"
+                )))
+            }
+        };
+
+        match (bits_per_pixel, compression) {
+            (1 | 4 | 8 | 16 | 24 | 32, BI_RGB)
+            | (8, BI_RLE8)
+            | (4, BI_RLE4)
+            | (16 | 32, BI_BITFIELDS | BI_ALPHABITFIELDS) => (),
+            _ => {
+                return Err(Error::DecodingError(format!(
+                    "unsupported bmp bit depth {bits_per_pixel} with compression method \
+                     {compression}"
+                )))
+            }
+        }
+
+        let palette = if bits_per_pixel <= 8 {
+            let len = match colors_used {
+                0 => 1 << bits_per_pixel,
+                n => (n as usize).min(256),
+            };
+
+            (0..len)
+                .map(|i| {
+                    let offset = palette_offset + i * palette_entry_size;
+                    data.get(offset..offset + 3)
+                        .map(|b| Rgb::new(b[2], b[1], b[0]))
+                        .ok_or_else(|| malformed!("palette is truncated"))
+                })
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            width: width.unsigned_abs(),
+            height: height.unsigned_abs(),
+            top_down: height < 0,
+            bits_per_pixel,
+            compression,
+            masks,
+            palette,
+            data_offset,
+        })
+    }
+
+    const fn is_paletted(&self) -> bool {
+        self.bits_per_pixel <= 8
+    }
+
+    const fn has_alpha(&self) -> bool {
+        self.masks[3] != 0
+    }
+
+    /// Returns the destination row of the given stored row.
+    const fn row(&self, stored: usize) -> usize {
+        if self.top_down {
+            stored
+        } else {
+            self.height as usize - 1 - stored
+        }
+    }
+
+    /// Reads palette indices in top-down order from an uncompressed or run-length encoded bitmap.
+    fn read_indices(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let stride = row_stride(self.width, self.bits_per_pixel);
+        if !matches!(self.compression, BI_RLE8 | BI_RLE4) {
+            check_length(data, stride, height, stride)?;
+        }
+        let mut indices = allocate(width, height, 1)?;
+
+        match self.compression {
+            BI_RLE8 | BI_RLE4 => self.read_rle(data, &mut indices)?,
+            _ => {
+                let bits = self.bits_per_pixel as usize;
+                let per_byte = 8 / bits;
+                let mask = ((1_u16 << bits) - 1) as u8;
+
+                for stored in 0..height {
+                    let row = data
+                        .get(stored * stride..(stored + 1) * stride)
+                        .ok_or_else(|| malformed!("pixel data is truncated"))?;
+                    let dest = &mut indices[self.row(stored) * width..][..width];
+
+                    for (x, index) in dest.iter_mut().enumerate() {
+                        let byte = row[x / per_byte];
+                        let shift = 8 - bits * (x % per_byte + 1);
+                        *index = (byte >> shift) & mask;
+                    }
+                }
+            }
+        }
+
+        Ok(indices)
+    }
+
+    fn read_rle(&self, data: &[u8], indices: &mut [u8]) -> Result<()> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let is_rle4 = self.compression == BI_RLE4;
+        let (mut x, mut y, mut i) = (0_usize, 0_usize, 0_usize);
+
+        let mut put = |x: usize, y: usize, value: u8| {
+            if x < width && y < height {
+                indices[self.row(y) * width + x] = value;
+            }
+        };
+        let next = |i: &mut usize| -> Result<u8> {
+            let byte = *data
+                .get(*i)
+                .ok_or_else(|| malformed!("run-length encoded data is truncated"))?;
+            *i += 1;
+            Ok(byte)
+        };
+
+        loop {
+            let count = next(&mut i)? as usize;
+            let value = next(&mut i)?;
+
+            if count > 0 {
+                for n in 0..count {
+                    let index = match (is_rle4, n % 2) {
+                        (false, _) => value,
+                        (true, 0) => value >> 4,
+                        (true, _) => value & 0x0F,
+                    };
+                    put(x, y, index);
+                    x += 1;
+                }
+                continue;
+            }
+
+            match value {
+                // End of line
+                0 => {
+                    x = 0;
+                    y += 1;
+                }
+                // End of bitmap
+                1 => break,
+                // Delta
+                2 => {
+                    x += next(&mut i)? as usize;
+                    y += next(&mut i)? as usize;
+                }
+                // Absolute mode
+                len => {
+                    let len = len as usize;
+                    let bytes = if is_rle4 { (len + 1) >> 1 } else { len };
+
+                    for n in 0..len {
+                        let index = if is_rle4 {
+                            let byte = *data.get(i + n / 2).ok_or_else(|| {
+                                malformed!("run-length encoded data is truncated")
+                            })?;
+                            if n % 2 == 0 {
+                                byte >> 4
+                            } else {
+                                byte & 0x0F
+                            }
+                        } else {
+                            *data
+                                .get(i + n)
+                                .ok_or_else(|| malformed!("run-length encoded data is truncated"))?
+                        };
+                        put(x, y, index);
+                        x += 1;
+                    }
+                    // Absolute runs are padded to a 16-bit boundary
+                    i += bytes + bytes % 2;
+                }
+            }
+
+            if y >= height {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads true color pixel data in top-down order as either RGB or RGBA bytes.
+    fn read_true_color(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let channels = if self.has_alpha() { 4 } else { 3 };
+        let bytes_per_pixel = self.bits_per_pixel as usize / 8;
+        let stride = row_stride(self.width, self.bits_per_pixel);
+        let [r, g, b, a] = self.masks.map(Bitfield::new);
+
+        let row_len = width
+            .checked_mul(bytes_per_pixel)
+            .ok_or_else(|| malformed!("image dimensions are too large"))?;
+        check_length(data, stride, height, row_len)?;
+        let mut out = allocate(width, height, channels)?;
+        for stored in 0..height {
+            let row = data
+                .get(stored * stride..stored * stride + row_len)
+                .ok_or_else(|| malformed!("pixel data is truncated"))?;
+            let dest = &mut out[self.row(stored) * width * channels..][..width * channels];
+
+            for (pixel, dest) in row
+                .chunks_exact(bytes_per_pixel)
+                .zip(dest.chunks_exact_mut(channels))
+            {
+                let value = pixel
+                    .iter()
+                    .rev()
+                    .fold(0_u32, |acc, &byte| (acc << 8) | u32::from(byte));
+
+                dest[0] = r.read(value);
+                dest[1] = g.read(value);
+                dest[2] = b.read(value);
+                if channels == 4 {
+                    dest[3] = a.read(value);
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// A native BMP decoder.
+///
+/// Supports 1, 4, and 8-bit paletted bitmaps (optionally RLE4/RLE8 compressed), and 16, 24, and
+/// 32-bit true color bitmaps (optionally with bitfields). 1, 4, and 8-bit bitmaps are decoded as
+/// paletted images with an RGB palette, and 32-bit bitmaps with an alpha mask are decoded as RGBA.
+pub struct BmpDecoder<P: Pixel, R: Read> {
+    _marker: PhantomData<(P, R)>,
+}
+
+impl<P: Pixel, R: Read> BmpDecoder<P, R> {
+    /// Creates a new decoder with the default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<P: Pixel, R: Read> Default for BmpDecoder<P, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Pixel, R: Read> Decoder<P, R> for BmpDecoder<P, R> {
+    type Sequence = SingleFrameIterator<P>;
+
+    fn decode(&mut self, mut stream: R) -> Result<Image<P>> {
+        let mut buffer = Vec::new();
+        stream.read_to_end(&mut buffer)?;
+
+        let info = BmpInfo::parse(&buffer)?;
+        let pixel_data = buffer
+            .get(info.data_offset..)
+            .ok_or_else(|| malformed!("pixel data offset is out of bounds"))?;
+
+        let (data, palette) = if info.is_paletted() {
+            let palette = info
+                .palette
+                .iter()
+                .map(|&p| P::Color::from_dynamic(Dynamic::Rgb(p)))
+                .collect::<Box<[_]>>();
+            let indices = info.read_indices(pixel_data)?;
+
+            if indices.iter().any(|&i| i as usize >= palette.len()) {
+                return Err(Error::InvalidPaletteIndex);
+            }
+
+            let data = if P::COLOR_TYPE.is_paletted() {
+                indices
+                    .into_iter()
+                    // SAFETY: indices were checked against the palette above
+                    .map(|idx| unsafe { assume_pixel_from_palette(&palette, idx) })
+                    .collect::<Result<Vec<_>>>()?
+            } else {
+                indices
+                    .into_iter()
+                    .map(|idx| {
+                        P::from_raw_parts_paletted(
+                            ColorType::PaletteRgb,
+                            8,
+                            &[idx],
+                            Some(&palette[..]),
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            };
+
+            (data, Some(palette))
+        } else {
+            let color_type = if info.has_alpha() {
+                ColorType::Rgba
+            } else {
+                ColorType::Rgb
+            };
+            let data = info
+                .read_true_color(pixel_data)?
+                .chunks_exact(color_type.channels())
+                .map(|chunk| P::from_raw_parts(color_type, 8, chunk))
+                .collect::<Result<Vec<_>>>()?;
+
+            (data, None)
+        };
+
+        Ok(Image {
+            width: NonZeroU32::new(info.width).unwrap(),
+            height: NonZeroU32::new(info.height).unwrap(),
+            data,
+            format: ImageFormat::Bmp,
+            overlay: OverlayMode::default(),
+            palette,
+            metadata: Metadata::new(),
+        })
+    }
+
+    fn decode_sequence(&mut self, stream: R) -> Result<Self::Sequence> {
+        let image = self.decode(stream)?;
+
+        Ok(SingleFrameIterator::new(image))
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
-    encode, encodings::ColorType, pixel::assume_pixel_from_palette, Decoder, DisposalMethod,
-    Dynamic, Encoder, Error, Frame, FrameIterator, Image, ImageFormat, LoopCount, OverlayMode,
-    Pixel, Rgba,
+    dither::{self, Dither},
+    encode,
+    encodings::ColorType,
+    pixel::assume_pixel_from_palette,
+    Decoder, DisposalMethod, Dynamic, Encoder, Error, Frame, FrameIterator, Image, ImageFormat,
+    LoopCount, Metadata, OverlayMode, Pixel, Rgba,
 };
 use std::{
     io::{Read, Write},
@@ -14,6 +17,7 @@ use std::{
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct GifEncoderOptions {
     speed: u8,
+    dither: Dither,
 }
 
 impl Default for GifEncoderOptions {
@@ -26,7 +30,10 @@ impl GifEncoderOptions {
     /// Creates a new encoder with default settings.
     #[must_use]
     pub const fn new() -> Self {
-        Self { speed: 10 }
+        Self {
+            speed: 10,
+            dither: Dither::None,
+        }
     }
 
     /// Sets the speed of the encoder. Higher speeds come at the cost of lower image quality.
@@ -41,6 +48,18 @@ impl GifEncoderOptions {
         self.speed = speed;
         self
     }
+
+    /// Sets the dithering method used when frames have more than 256 colors and must be
+    /// quantized. Dithering reduces banding in gradients at the cost of adding noise and
+    /// increasing the size of the encoded image. Defaults to [`Dither::None`].
+    ///
+    /// Ordered methods (e.g. [`Dither::Bayer4`]) are usually preferable for animations, since
+    /// error diffusion patterns change from frame to frame and cause flickering.
+    #[must_use]
+    pub const fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
 }
 
 /// A GIF encoder interface over [`gif::Encoder`].
@@ -74,12 +93,14 @@ impl<P: Pixel, W: Write> GifEncoder<P, W> {
             ($data:expr) => {{
                 let pixels = $data;
 
-                gif::Frame::from_rgb_speed(
+                let mut frame = gif::Frame::from_rgb_speed(
                     image.width() as u16,
                     image.height() as u16,
                     &pixels,
                     self.options.speed as i32,
-                )
+                );
+                self.dither_frame(&mut frame, &pixels, 3);
+                frame
             }};
         }
 
@@ -87,12 +108,14 @@ impl<P: Pixel, W: Write> GifEncoder<P, W> {
             ($data:expr) => {{
                 let mut pixels = $data;
 
-                gif::Frame::from_rgba_speed(
+                let mut frame = gif::Frame::from_rgba_speed(
                     image.width() as u16,
                     image.height() as u16,
                     &mut pixels,
                     self.options.speed as i32,
-                )
+                );
+                self.dither_frame(&mut frame, &pixels, 4);
+                frame
             }};
         }
 
@@ -147,6 +170,58 @@ impl<P: Pixel, W: Write> GifEncoder<P, W> {
             _ => return Err(Error::UnsupportedColorType),
         })
     }
+
+    /// Remaps the pixels of a quantized frame to its palette with the configured dithering
+    /// method. Frames that were not quantized, i.e. had at most 256 colors, are left untouched.
+    fn dither_frame(&self, frame: &mut gif::Frame, pixels: &[u8], channels: usize) {
+        let dither = self.options.dither;
+        let palette = match frame.palette {
+            Some(ref palette) if dither != Dither::None => palette,
+            _ => return,
+        };
+        let transparent = frame.transparent.map(usize::from);
+        let is_transparent = |pixel: &[u8]| channels == 4 && pixel[3] == 0;
+
+        let exact = pixels
+            .chunks_exact(channels)
+            .zip(frame.buffer.iter())
+            .all(|(pixel, &i)| {
+                is_transparent(pixel) || pixel[..3] == palette[i as usize * 3..i as usize * 3 + 3]
+            });
+        if exact {
+            return;
+        }
+
+        // Never map opaque pixels to the transparent color
+        let (indices, colors): (Vec<_>, Vec<_>) = palette
+            .chunks_exact(3)
+            .enumerate()
+            .filter(|&(i, _)| Some(i) != transparent)
+            .map(|(i, color)| (i, [0, 1, 2].map(|c| f32::from(color[c]))))
+            .unzip();
+        if colors.is_empty() {
+            return;
+        }
+
+        let mapped = dither::dither(
+            &pixels
+                .chunks_exact(channels)
+                .map(|pixel| [0, 1, 2].map(|c| f32::from(pixel[c])))
+                .collect::<Vec<_>>(),
+            frame.width as usize,
+            &colors,
+            dither,
+        );
+        frame.buffer = pixels
+            .chunks_exact(channels)
+            .zip(mapped)
+            .map(|(pixel, i)| match transparent {
+                Some(t) if is_transparent(pixel) => t as u8,
+                _ => indices[i] as u8,
+            })
+            .collect::<Vec<_>>()
+            .into();
+    }
 }
 
 impl<P: Pixel, W: Write> Encoder<P, W> for GifEncoder<P, W> {
@@ -276,6 +351,7 @@ fn read_frame<P: Pixel, R: Read>(
             palette: P::COLOR_TYPE
                 .is_paletted()
                 .then(|| palette.into_boxed_slice()),
+            metadata: Metadata::new(),
         },
     )))
 }
@@ -1,7 +1,7 @@
 use super::ColorType;
 use crate::{
     encode::{self, Decoder, Encoder},
-    Error, Image, ImageFormat, OverlayMode, Pixel, Result, SingleFrameIterator,
+    Error, Image, ImageFormat, Metadata, OverlayMode, Pixel, Result, SingleFrameIterator,
 };
 
 use jpeg_decoder::PixelFormat as DecoderPixelFormat;
@@ -57,9 +57,14 @@ impl JpegEncoderOptions {
     }
 }
 
+/// The identifier that prefixes EXIF data in an APP1 segment.
+const EXIF_IDENTIFIER: &[u8] = b"Exif\0\0";
+/// The identifier that prefixes an XMP packet in an APP1 segment.
+const XMP_IDENTIFIER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
 enum JpegSpecialCase {
     L1,
-    LA1,
+    LumaA,
     None,
 }
 
@@ -68,6 +73,7 @@ pub struct JpegEncoder<P: Pixel, W: Write> {
     native_color_type: ColorType,
     color_type: EncoderColorType,
     special_case: JpegSpecialCase,
+    metadata: Option<Metadata>,
     quality: u8,
     writer: Option<W>,
     _marker: PhantomData<P>,
@@ -87,7 +93,8 @@ impl<P: Pixel, W: Write> Encoder<P, W> for JpegEncoder<P, W> {
         };
         let special_case = match sample {
             (ColorType::Luma, 1) => JpegSpecialCase::L1,
-            (ColorType::LumaA, 1) => JpegSpecialCase::LA1,
+            // JPEG has no alpha channel, so it is stripped from each pixel
+            (ColorType::LumaA, _) => JpegSpecialCase::LumaA,
             _ => JpegSpecialCase::None,
         };
 
@@ -95,6 +102,7 @@ impl<P: Pixel, W: Write> Encoder<P, W> for JpegEncoder<P, W> {
             native_color_type: metadata.color_type(),
             color_type,
             special_case,
+            metadata: metadata.metadata().cloned(),
             quality: metadata.config().quality,
             writer: Some(dest),
             _marker: PhantomData,
@@ -115,16 +123,27 @@ impl<P: Pixel, W: Write> Encoder<P, W> for JpegEncoder<P, W> {
             JpegSpecialCase::L1 => data
                 .iter_mut()
                 .for_each(|p| *p = if *p > 0 { 255 } else { 0 }),
-            JpegSpecialCase::LA1 => data = data.into_iter().step_by(2).collect(),
+            JpegSpecialCase::LumaA => data = data.into_iter().step_by(2).collect(),
             JpegSpecialCase::None => (),
         }
 
-        let encoder = jpeg_encoder::Encoder::new(
+        let mut encoder = jpeg_encoder::Encoder::new(
             self.writer
                 .take()
                 .expect("jpeg cannot encode multiple frames"),
             self.quality,
         );
+        if let Some(metadata) = &self.metadata {
+            if let Some(exif) = &metadata.exif {
+                encoder.add_app_segment(1, &[EXIF_IDENTIFIER, exif].concat())?;
+            }
+            if let Some(xmp) = &metadata.xmp {
+                encoder.add_app_segment(1, &[XMP_IDENTIFIER, xmp].concat())?;
+            }
+            if let Some(icc_profile) = &metadata.icc_profile {
+                encoder.add_icc_profile(icc_profile)?;
+            }
+        }
         encoder.encode(
             &data,
             frame.image().width() as u16,
@@ -177,6 +196,12 @@ impl<P: Pixel, R: Read> Decoder<P, R> for JpegDecoder<P, R> {
             .chunks_exact(info.pixel_format.pixel_bytes())
             .map(|chunk| {
                 if color_type == ColorType::Luma {
+                    // 16-bit samples are decoded in native endianness, while pixels expect them
+                    // to be big-endian
+                    if bit_depth == 16 {
+                        let chunk = u16::from_ne_bytes([chunk[0], chunk[1]]).to_be_bytes();
+                        return P::from_raw_parts(ColorType::Luma, bit_depth, &chunk);
+                    }
                     return P::from_raw_parts(ColorType::Luma, bit_depth, chunk);
                 }
 
@@ -206,6 +231,12 @@ impl<P: Pixel, R: Read> Decoder<P, R> for JpegDecoder<P, R> {
             format: ImageFormat::Jpeg,
             overlay: OverlayMode::default(),
             palette: None,
+            metadata: Metadata {
+                exif: decoder.exif_data().map(<[u8]>::to_vec),
+                xmp: decoder.xmp_data().map(<[u8]>::to_vec),
+                icc_profile: decoder.icc_profile(),
+                text: Vec::new(),
+            },
         })
     }
 
@@ -1,11 +1,15 @@
 //! Contains encoder and decoder implementations for various image formats.
 
+#[cfg(feature = "bmp")]
+pub mod bmp;
 #[cfg(feature = "gif")]
 pub mod gif;
 #[cfg(feature = "jpeg")]
 pub mod jpeg;
 #[cfg(feature = "png")]
 pub mod png;
+#[cfg(feature = "tiff")]
+pub mod tiff;
 #[cfg(feature = "webp")]
 pub mod webp;
 
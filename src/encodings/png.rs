@@ -2,13 +2,14 @@ use super::ColorType;
 use crate::{
     encode::{self, Decoder, Encoder, FrameIterator},
     pixel::assume_pixel_from_palette,
-    DisposalMethod, Dynamic, Frame, Image, ImageFormat, LoopCount, OverlayMode, Pixel, Rgb, Rgba,
+    DisposalMethod, Dynamic, Error, Frame, Image, ImageFormat, LoopCount, Metadata, OverlayMode,
+    Pixel, Rgb, Rgba,
 };
 
 pub use png::{AdaptiveFilterType, Compression, FilterType};
 use std::{
     borrow::Cow,
-    io::{Read, Write},
+    io::{self, Read, Write},
     marker::PhantomData,
     num::NonZeroU32,
     time::Duration,
@@ -43,6 +44,9 @@ const fn get_png_color_type(src: ColorType) -> png::ColorType {
     }
 }
 
+/// The `iTXt` keyword that XMP packets are stored under.
+const XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+
 /// PNG configuration options for [`PngEncoder`].
 #[derive(Copy, Clone, Debug, Default)]
 pub struct PngEncoderOptions {
@@ -106,9 +110,30 @@ impl<P: Pixel, W: Write> Encoder<P, W> for PngEncoder<P, W> {
         dest: W,
         metadata: impl encode::HasEncoderMetadata<Self::Config, P>,
     ) -> crate::Result<Self> {
-        let mut encoder = png::Encoder::new(dest, metadata.width(), metadata.height());
+        let mut info = png::Info::with_size(metadata.width(), metadata.height());
+        let image_metadata = metadata.metadata().cloned().unwrap_or_default();
+        info.exif_metadata = image_metadata.exif.map(Cow::Owned);
+        info.icc_profile = image_metadata.icc_profile.map(Cow::Owned);
+
+        let mut encoder = png::Encoder::with_info(dest, info)?;
+        if let Some(xmp) = image_metadata.xmp {
+            let xmp = String::from_utf8(xmp)
+                .map_err(|_| Error::EncodingError("XMP packet is not valid UTF-8".to_string()))?;
+            encoder.add_itxt_chunk(XMP_KEYWORD.to_string(), xmp)?;
+        }
+        for (keyword, text) in image_metadata.text {
+            // tEXt chunks can only hold Latin-1 text
+            if text.chars().all(|c| c <= '\u{ff}') {
+                encoder.add_text_chunk(keyword, text)?;
+            } else {
+                encoder.add_itxt_chunk(keyword, text)?;
+            }
+        }
+
         encoder.set_color(get_png_color_type(metadata.color_type()));
-        encoder.set_depth(png::BitDepth::from_u8(metadata.bit_depth()).unwrap());
+        encoder.set_depth(
+            png::BitDepth::from_u8(metadata.bit_depth()).ok_or(Error::UnsupportedColorType)?,
+        );
 
         match metadata.color_type() {
             ColorType::PaletteRgb => {
@@ -193,12 +218,87 @@ impl<P: Pixel, R: Read> PngDecoder<P, R> {
         }
     }
 
-    fn prepare(stream: R) -> crate::Result<png::Reader<R>> {
-        let decoder = png::Decoder::new(stream);
-        decoder.read_info().map_err(Into::into)
+    fn read_metadata(info: &png::Info, exif: Option<Vec<u8>>) -> crate::Result<Metadata> {
+        let mut metadata = Metadata {
+            exif,
+            icc_profile: info.icc_profile.as_deref().map(<[u8]>::to_vec),
+            ..Metadata::new()
+        };
+
+        for chunk in &info.uncompressed_latin1_text {
+            metadata
+                .text
+                .push((chunk.keyword.clone(), chunk.text.clone()));
+        }
+        for chunk in &info.compressed_latin1_text {
+            metadata
+                .text
+                .push((chunk.keyword.clone(), chunk.get_text()?));
+        }
+        for chunk in &info.utf8_text {
+            let text = chunk.get_text()?;
+
+            if chunk.keyword == XMP_KEYWORD {
+                metadata.xmp = Some(text.into_bytes());
+            } else {
+                metadata.text.push((chunk.keyword.clone(), text));
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    fn prepare(mut stream: R) -> crate::Result<(PngReader<R>, Option<Vec<u8>>)> {
+        let (prefix, exif) = read_exif_chunk(&mut stream)?;
+        let decoder = png::Decoder::new(io::Cursor::new(prefix).chain(stream));
+
+        Ok((decoder.read_info()?, exif))
     }
 }
 
+type PngReader<R> = png::Reader<io::Chain<io::Cursor<Vec<u8>>, R>>;
+
+/// Reads the signature and every chunk preceding the image data from the stream, returning the
+/// bytes read alongside the payload of the `eXIf` chunk, if any. This is needed since
+/// [`png::Decoder`] does not expose `eXIf` chunks itself.
+///
+/// Malformed input is not rejected here, the bytes read so far are simply handed back so that
+/// [`png::Decoder`] can report the error instead.
+fn read_exif_chunk<R: Read>(stream: &mut R) -> crate::Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let mut prefix = Vec::new();
+    let mut exif = None;
+
+    if stream.by_ref().take(8).read_to_end(&mut prefix)? < 8 {
+        return Ok((prefix, exif));
+    }
+
+    loop {
+        let start = prefix.len();
+        if stream.by_ref().take(8).read_to_end(&mut prefix)? < 8 {
+            break;
+        }
+
+        let length = u32::from_be_bytes(prefix[start..start + 4].try_into().unwrap());
+        let kind = &prefix[start + 4..start + 8];
+        if kind == b"IDAT" || kind == b"IEND" {
+            break;
+        }
+        let is_exif = kind == b"eXIf";
+
+        // chunk data followed by its CRC
+        let size = u64::from(length) + 4;
+        if (stream.by_ref().take(size).read_to_end(&mut prefix)? as u64) < size {
+            break;
+        }
+        if is_exif && exif.is_none() {
+            let data = start + 8;
+            exif = Some(prefix[data..data + length as usize].to_vec());
+        }
+    }
+
+    Ok((prefix, exif))
+}
+
 #[allow(clippy::type_complexity)]
 fn read_data<P: Pixel>(
     buffer: &[u8],
@@ -262,7 +362,7 @@ impl<P: Pixel, R: Read> Decoder<P, R> for PngDecoder<P, R> {
     type Sequence = ApngFrameIterator<P, R>;
 
     fn decode(&mut self, stream: R) -> crate::Result<Image<P>> {
-        let mut reader = Self::prepare(stream)?;
+        let (mut reader, exif) = Self::prepare(stream)?;
 
         // Here we are decoding a single image, so only capture the first frame:
         let buffer = &mut vec![0; reader.output_buffer_size()];
@@ -278,15 +378,17 @@ impl<P: Pixel, R: Read> Decoder<P, R> for PngDecoder<P, R> {
             format: ImageFormat::Png,
             overlay: OverlayMode::default(),
             palette,
+            metadata: Self::read_metadata(info, exif)?,
         })
     }
 
     fn decode_sequence(&mut self, stream: R) -> crate::Result<Self::Sequence> {
-        let reader = Self::prepare(stream)?;
+        let (reader, exif) = Self::prepare(stream)?;
 
         Ok(ApngFrameIterator {
             seq: 0,
             reader,
+            exif,
             _marker: PhantomData,
         })
     }
@@ -300,7 +402,8 @@ impl<P: Pixel, R: Read> Default for PngDecoder<P, R> {
 
 pub struct ApngFrameIterator<P: Pixel, R: Read> {
     seq: u32,
-    reader: png::Reader<R>,
+    reader: PngReader<R>,
+    exif: Option<Vec<u8>>,
     _marker: PhantomData<P>,
 }
 
@@ -345,6 +448,16 @@ impl<P: Pixel, R: Read> Iterator for ApngFrameIterator<P, R> {
             Err(e) => return Some(Err(e)),
         };
 
+        // Metadata applies to the image as a whole, so it is only attached to the first frame
+        let metadata = if self.seq == 0 {
+            let exif = self.exif.take();
+            match PngDecoder::<P, R>::read_metadata(self.info(), exif) {
+                Ok(metadata) => metadata,
+                Err(e) => return Some(Err(e)),
+            }
+        } else {
+            Metadata::new()
+        };
         let inner = Image {
             width: NonZeroU32::new(output_info.width).unwrap(),
             height: NonZeroU32::new(output_info.height).unwrap(),
@@ -352,6 +465,7 @@ impl<P: Pixel, R: Read> Iterator for ApngFrameIterator<P, R> {
             format: ImageFormat::Png,
             overlay: OverlayMode::default(),
             palette,
+            metadata,
         };
 
         self.seq += 1;
@@ -0,0 +1,439 @@
+use super::ColorType;
+use crate::{
+    encode::{self, Decoder, Encoder, FrameIterator},
+    Error, Frame, Image, ImageFormat, LoopCount, Metadata, OverlayMode, Pixel, Result,
+};
+
+pub use tiff::encoder::DeflateLevel;
+use tiff::{
+    decoder::DecodingResult,
+    encoder::{colortype, TiffEncoder as Writer},
+    ColorType as TiffColorType,
+};
+
+use std::{
+    io::{Cursor, Read, Write},
+    marker::PhantomData,
+    num::NonZeroU32,
+};
+
+/// The compression method used to compress TIFF image data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Store image data uncompressed.
+    Uncompressed,
+    /// Compress image data with PackBits, a simple run-length encoding.
+    PackBits,
+    /// Compress image data with LZW.
+    Lzw,
+    /// Compress image data with Deflate (zlib) at the given compression level.
+    Deflate(DeflateLevel),
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self::Uncompressed
+    }
+}
+
+impl From<Compression> for tiff::encoder::Compression {
+    fn from(value: Compression) -> Self {
+        match value {
+            Compression::Uncompressed => Self::Uncompressed,
+            Compression::PackBits => Self::Packbits,
+            Compression::Lzw => Self::Lzw,
+            Compression::Deflate(level) => Self::Deflate(level),
+        }
+    }
+}
+
+/// TIFF configuration options for [`TiffEncoder`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TiffEncoderOptions {
+    /// The compression method to use.
+    pub compression: Compression,
+}
+
+impl TiffEncoderOptions {
+    /// Creates a new set of options with the default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            compression: Compression::Uncompressed,
+        }
+    }
+
+    /// Sets the compression method to use.
+    #[must_use]
+    pub const fn with_compression(mut self, value: Compression) -> Self {
+        self.compression = value;
+        self
+    }
+}
+
+/// The sample layout a page will be written in.
+#[derive(Copy, Clone)]
+enum TiffLayout {
+    Gray8,
+    Rgb8,
+    Rgba8,
+    Gray16,
+    Rgb16,
+    Rgba16,
+}
+
+/// A single page of a TIFF file, buffered until the encoder is finished.
+struct Page {
+    width: u32,
+    height: u32,
+    layout: TiffLayout,
+    /// The sample data. 16-bit samples are stored as big-endian bytes.
+    data: Vec<u8>,
+}
+
+/// A TIFF encoder interface around [`tiff::encoder::TiffEncoder`].
+///
+/// Every frame added to the encoder is written as its own page (image file directory), so image
+/// sequences are encoded as multi-page TIFFs.
+///
+/// # Note
+/// Because TIFF files reference data by offset, the encoded pages are buffered in memory and only
+/// written to the destination when [`TiffEncoder::finish`] is called.
+pub struct TiffEncoder<P: Pixel, W: Write> {
+    writer: W,
+    layout: TiffLayout,
+    compression: Compression,
+    pages: Vec<Page>,
+    _marker: PhantomData<P>,
+}
+
+impl<P: Pixel, W: Write> Encoder<P, W> for TiffEncoder<P, W> {
+    type Config = TiffEncoderOptions;
+
+    fn new(dest: W, metadata: impl encode::HasEncoderMetadata<Self::Config, P>) -> Result<Self> {
+        // TIFF palettes and gray-alpha images are not supported by the underlying encoder, so
+        // these are flattened into true color.
+        let layout = match (metadata.color_type(), metadata.bit_depth()) {
+            (ColorType::Luma, 16) => TiffLayout::Gray16,
+            (ColorType::Rgb, 16) => TiffLayout::Rgb16,
+            (ColorType::Rgba, 16) => TiffLayout::Rgba16,
+            (ColorType::Luma, _) => TiffLayout::Gray8,
+            (ColorType::Rgb | ColorType::PaletteRgb, _) => TiffLayout::Rgb8,
+            (ColorType::LumaA | ColorType::Rgba | ColorType::PaletteRgba, _) => TiffLayout::Rgba8,
+            (ColorType::Dynamic, _) => return Err(Error::UnsupportedColorType),
+        };
+
+        Ok(Self {
+            writer: dest,
+            layout,
+            compression: metadata.config().compression,
+            pages: Vec::new(),
+            _marker: PhantomData,
+        })
+    }
+
+    fn add_frame(&mut self, frame: &impl encode::FrameLike<P>) -> Result<()> {
+        let image = frame.image();
+        let pixels = image.data.iter();
+        let data = match self.layout {
+            TiffLayout::Gray8 => pixels.map(|p| p.as_rgb().r).collect(),
+            TiffLayout::Rgb8 => pixels.flat_map(|p| p.as_rgb().as_bytes()).collect(),
+            TiffLayout::Rgba8 => pixels.flat_map(|p| p.as_rgba().as_bytes()).collect(),
+            TiffLayout::Gray16 | TiffLayout::Rgb16 | TiffLayout::Rgba16 => {
+                pixels.flat_map(P::as_bytes).collect()
+            }
+        };
+
+        self.pages.push(Page {
+            width: image.width(),
+            height: image.height(),
+            layout: self.layout,
+            data,
+        });
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        let buffer = write_pages(self.pages, self.compression).map_err(encoding_error)?;
+
+        self.writer.write_all(&buffer)?;
+        Ok(())
+    }
+}
+
+fn write_pages(pages: Vec<Page>, compression: Compression) -> tiff::TiffResult<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut encoder = Writer::new(Cursor::new(&mut buffer))?.with_compression(compression.into());
+
+    for Page {
+        width,
+        height,
+        layout,
+        data,
+    } in pages
+    {
+        match layout {
+            TiffLayout::Gray8 => encoder.write_image::<colortype::Gray8>(width, height, &data),
+            TiffLayout::Rgb8 => encoder.write_image::<colortype::RGB8>(width, height, &data),
+            TiffLayout::Rgba8 => encoder.write_image::<colortype::RGBA8>(width, height, &data),
+            TiffLayout::Gray16 => {
+                encoder.write_image::<colortype::Gray16>(width, height, &samples16(&data))
+            }
+            TiffLayout::Rgb16 => {
+                encoder.write_image::<colortype::RGB16>(width, height, &samples16(&data))
+            }
+            TiffLayout::Rgba16 => {
+                encoder.write_image::<colortype::RGBA16>(width, height, &samples16(&data))
+            }
+        }?;
+    }
+
+    Ok(buffer)
+}
+
+fn samples16(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2)
+        .map(|n| u16::from_be_bytes([n[0], n[1]]))
+        .collect()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn encoding_error(err: tiff::TiffError) -> Error {
+    match err {
+        tiff::TiffError::IoError(err) => Error::IoError(err),
+        err => Error::EncodingError(err.to_string()),
+    }
+}
+
+/// A TIFF decoder interface around [`tiff::decoder::Decoder`].
+///
+/// Supports baseline TIFF images that are uncompressed or compressed with PackBits, LZW, or
+/// Deflate, stored in either strips or tiles. Multi-page TIFFs can be decoded with
+/// [`Decoder::decode_sequence`], where each page is represented as a frame.
+///
+/// # Note
+/// Paletted TIFFs are currently not supported.
+pub struct TiffDecoder<P: Pixel, R: Read> {
+    _marker: PhantomData<(P, R)>,
+}
+
+impl<P: Pixel, R: Read> TiffDecoder<P, R> {
+    /// Creates a new decoder with the default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    fn prepare(mut stream: R) -> Result<tiff::decoder::Decoder<Cursor<Vec<u8>>>> {
+        // The underlying decoder requires the stream to be seekable
+        let mut buffer = Vec::new();
+        stream.read_to_end(&mut buffer)?;
+
+        tiff::decoder::Decoder::new(Cursor::new(buffer)).map_err(Into::into)
+    }
+}
+
+impl<P: Pixel, R: Read> Default for TiffDecoder<P, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Unpacks rows of sub-byte samples into one byte per sample, scaled to 8 bits.
+///
+/// Samples are packed most significant bit first and may span two bytes (e.g. 3-bit samples),
+/// but every row starts on a byte boundary.
+fn unpack_samples(data: &[u8], width: usize, samples: usize, bit_depth: u8) -> Vec<u8> {
+    let bits = bit_depth as usize;
+    let per_row = width * samples;
+    let stride = (per_row * bits + 7) >> 3;
+    let max = (1_u16 << bits) - 1;
+
+    data.chunks(stride)
+        .flat_map(|row| {
+            (0..per_row).map(move |i| {
+                let start = i * bits;
+                let value = (start..start + bits).fold(0_u16, |value, bit| {
+                    let byte = row.get(bit / 8).copied().unwrap_or_default();
+
+                    value << 1 | u16::from(byte >> (7 - bit % 8) & 1)
+                });
+
+                (value * 255 / max) as u8
+            })
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_lossless)]
+fn read_page<P: Pixel>(decoder: &mut tiff::decoder::Decoder<Cursor<Vec<u8>>>) -> Result<Image<P>> {
+    let (width, height) = decoder.dimensions()?;
+    let tiff_color_type = decoder.colortype()?;
+
+    let (color_type, bit_depth) = match tiff_color_type {
+        TiffColorType::Gray(depth) => (ColorType::Luma, depth),
+        TiffColorType::GrayA(depth)
+        | TiffColorType::Multiband {
+            bit_depth: depth,
+            num_samples: 2,
+        } => (ColorType::LumaA, depth),
+        TiffColorType::RGB(depth) => (ColorType::Rgb, depth),
+        TiffColorType::RGBA(depth) => (ColorType::Rgba, depth),
+        TiffColorType::CMYK(8) => (ColorType::Rgb, 8),
+        other => {
+            return Err(Error::DecodingError(format!(
+                "unsupported tiff color type {other:?}"
+            )))
+        }
+    };
+    let samples = match tiff_color_type {
+        TiffColorType::CMYK(_) => 4,
+        _ => color_type.channels(),
+    };
+
+    let result = decoder.read_image()?;
+    // Preserve 16-bit samples when decoding into 16-bit pixels
+    if let (DecodingResult::U16(samples), 16) = (&result, P::BIT_DEPTH) {
+        if !matches!(tiff_color_type, TiffColorType::CMYK(_)) {
+            let data = samples
+                .iter()
+                .flat_map(|n| n.to_be_bytes())
+                .collect::<Vec<_>>();
+
+            return into_image(width, height, color_type, 16, &data);
+        }
+    }
+
+    let mut data = match result {
+        DecodingResult::U8(data) if bit_depth < 8 => {
+            unpack_samples(&data, width as usize, samples, bit_depth)
+        }
+        DecodingResult::U8(data) => data,
+        DecodingResult::U16(data) => data.into_iter().map(|n| (n >> 8) as u8).collect(),
+        DecodingResult::F32(data) => data
+            .into_iter()
+            .map(|n| (n.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect(),
+        _ => {
+            return Err(Error::DecodingError(format!(
+                "unsupported tiff sample format for color type {tiff_color_type:?}"
+            )))
+        }
+    };
+
+    if let TiffColorType::CMYK(_) = tiff_color_type {
+        data = data
+            .chunks_exact(4)
+            .flat_map(|chunk| {
+                let k = 255 - chunk[3] as u16;
+                chunk[..3]
+                    .iter()
+                    .map(move |&n| ((255 - n as u16) * k / 255) as u8)
+            })
+            .collect();
+    }
+
+    // Preserve bilevel images when decoding into single-bit pixels
+    let bit_depth = if tiff_color_type == TiffColorType::Gray(1) && P::BIT_DEPTH == 1 {
+        data.iter_mut().for_each(|n| *n = u8::from(*n > 127));
+        1
+    } else {
+        8
+    };
+
+    into_image(width, height, color_type, bit_depth, &data)
+}
+
+fn into_image<P: Pixel>(
+    width: u32,
+    height: u32,
+    color_type: ColorType,
+    bit_depth: u8,
+    data: &[u8],
+) -> Result<Image<P>> {
+    let data = data
+        .chunks_exact(color_type.channels() * ((bit_depth as usize + 7) >> 3))
+        .map(|chunk| P::from_raw_parts(color_type, bit_depth, chunk))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Image {
+        width: NonZeroU32::new(width).unwrap(),
+        height: NonZeroU32::new(height).unwrap(),
+        data,
+        format: ImageFormat::Tiff,
+        overlay: OverlayMode::default(),
+        palette: None,
+        metadata: Metadata::new(),
+    })
+}
+
+impl<P: Pixel, R: Read> Decoder<P, R> for TiffDecoder<P, R> {
+    type Sequence = TiffFrameIterator<P>;
+
+    fn decode(&mut self, stream: R) -> Result<Image<P>> {
+        // Here we are decoding a single image, so only capture the first page:
+        read_page(&mut Self::prepare(stream)?)
+    }
+
+    fn decode_sequence(&mut self, stream: R) -> Result<Self::Sequence> {
+        let mut decoder = Self::prepare(stream)?;
+
+        let mut len = 1;
+        while decoder.more_images() {
+            decoder.next_image()?;
+            len += 1;
+        }
+        decoder.seek_to_image(0)?;
+
+        Ok(TiffFrameIterator {
+            seq: 0,
+            len,
+            decoder,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// Iterates over the pages of a multi-page TIFF as frames.
+pub struct TiffFrameIterator<P: Pixel> {
+    seq: u32,
+    len: u32,
+    decoder: tiff::decoder::Decoder<Cursor<Vec<u8>>>,
+    _marker: PhantomData<P>,
+}
+
+impl<P: Pixel> FrameIterator<P> for TiffFrameIterator<P> {
+    fn len(&self) -> u32 {
+        self.len
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        LoopCount::Exactly(1)
+    }
+}
+
+impl<P: Pixel> Iterator for TiffFrameIterator<P> {
+    type Item = Result<Frame<P>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.seq >= self.len {
+            return None;
+        }
+        if self.seq > 0 {
+            if let Err(err) = self.decoder.next_image() {
+                return Some(Err(err.into()));
+            }
+        }
+
+        self.seq += 1;
+        Some(read_page(&mut self.decoder).map(Frame::from_image))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.len - self.seq) as usize;
+
+        (remaining, Some(remaining))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     encode, ColorType, Decoder, DisposalMethod, Encoder, Error, Frame, FrameIterator, Image,
-    ImageFormat, LoopCount, OverlayMode, Pixel,
+    ImageFormat, LoopCount, Metadata, OverlayMode, Pixel,
 };
 use libwebp_sys as libwebp;
 use std::{
@@ -65,6 +65,72 @@ unsafe fn free_mux(
     libwebp::WebPMuxDelete(mux);
 }
 
+/// The FourCC code of the chunk that holds EXIF data, as a C string.
+const EXIF_FOURCC: &[u8; 5] = b"EXIF\0";
+/// The FourCC code of the chunk that holds an XMP packet, as a C string.
+const XMP_FOURCC: &[u8; 5] = b"XMP \0";
+/// The FourCC code of the chunk that holds an ICC profile, as a C string.
+const ICCP_FOURCC: &[u8; 5] = b"ICCP\0";
+
+/// Reads the EXIF, XMP, and ICCP chunks of the given WebP data.
+unsafe fn read_metadata(data: &[u8]) -> Metadata {
+    let data = libwebp::WebPData {
+        bytes: data.as_ptr(),
+        size: data.len() as _,
+    };
+    let mux = libwebp::WebPMuxCreate(addr_of!(data), 0);
+    if mux.is_null() {
+        return Metadata::new();
+    }
+
+    let chunk = |fourcc: &[u8; 5]| {
+        let mut chunk = std::mem::zeroed::<libwebp::WebPData>();
+        (libwebp::WebPMuxGetChunk(mux, fourcc.as_ptr().cast(), addr_of_mut!(chunk))
+            == libwebp::WEBP_MUX_OK)
+            .then(|| std::slice::from_raw_parts(chunk.bytes, chunk.size).to_vec())
+    };
+    let mut exif = chunk(EXIF_FOURCC);
+    // Some encoders include the JPEG identifier in the EXIF chunk, which we don't want
+    if let Some(stripped) = exif.as_deref().and_then(|e| e.strip_prefix(b"Exif\0\0")) {
+        exif = Some(stripped.to_vec());
+    }
+
+    let metadata = Metadata {
+        exif,
+        xmp: chunk(XMP_FOURCC),
+        icc_profile: chunk(ICCP_FOURCC),
+        text: Vec::new(),
+    };
+    libwebp::WebPMuxDelete(mux);
+    metadata
+}
+
+/// Writes the EXIF, XMP, and ICCP chunks of the given metadata into the mux.
+unsafe fn write_metadata(mux: *mut libwebp::WebPMux, metadata: &Metadata) -> crate::Result<()> {
+    for (fourcc, chunk) in [
+        (EXIF_FOURCC, &metadata.exif),
+        (XMP_FOURCC, &metadata.xmp),
+        (ICCP_FOURCC, &metadata.icc_profile),
+    ]
+    .into_iter()
+    .filter_map(|(fourcc, chunk)| Some((fourcc, chunk.as_ref()?)))
+    {
+        let data = libwebp::WebPData {
+            bytes: chunk.as_ptr(),
+            size: chunk.len() as _,
+        };
+
+        if libwebp::WebPMuxSetChunk(mux, fourcc.as_ptr().cast(), addr_of!(data), 1)
+            != libwebp::WEBP_MUX_OK
+        {
+            return Err(Error::EncodingError(
+                "WebP mux could not write metadata".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
 #[inline]
 unsafe fn free_picture(mut picture: libwebp::WebPPicture) {
     libwebp::WebPPictureFree(addr_of_mut!(picture));
@@ -156,6 +222,7 @@ extern "C" fn _wrapped(
 /// * [`WebPMuxEncoder`] for encoding WebP animations instead of just static images.
 pub struct WebPStaticEncoder<P: Pixel, W: Write> {
     options: WebPEncoderOptions,
+    metadata: Metadata,
     writer: W,
     _marker: PhantomData<P>,
 }
@@ -168,6 +235,7 @@ impl<P: Pixel, W: Write> Encoder<P, W> for WebPStaticEncoder<P, W> {
         metadata: impl encode::HasEncoderMetadata<Self::Config, P>,
     ) -> crate::Result<Self> {
         Ok(Self {
+            metadata: metadata.metadata().cloned().unwrap_or_default(),
             options: metadata.config(),
             writer: dest,
             _marker: PhantomData,
@@ -176,6 +244,9 @@ impl<P: Pixel, W: Write> Encoder<P, W> for WebPStaticEncoder<P, W> {
 
     fn add_frame(&mut self, frame: &impl encode::FrameLike<P>) -> crate::Result<()> {
         let mut data = encode_image(&self.options, frame.image())?;
+        if !self.metadata.is_empty() {
+            data = unsafe { embed_metadata(data, &self.metadata)? };
+        }
         unsafe {
             let result = self
                 .writer
@@ -192,12 +263,36 @@ impl<P: Pixel, W: Write> Encoder<P, W> for WebPStaticEncoder<P, W> {
     }
 }
 
+/// Wraps the encoded image data in a mux to embed metadata into it. This takes ownership of and
+/// frees the original data.
+unsafe fn embed_metadata(
+    mut data: libwebp::WebPData,
+    metadata: &Metadata,
+) -> crate::Result<libwebp::WebPData> {
+    let mux = libwebp::WebPMuxCreate(addr_of!(data), 1);
+    libwebp::WebPDataClear(addr_of_mut!(data));
+    if mux.is_null() {
+        return Err(Error::EncodingError("WebP mux bad data".to_string()));
+    }
+
+    let mut output = std::mem::zeroed::<libwebp::WebPData>();
+    let result = write_metadata(mux, metadata).and_then(|()| {
+        match libwebp::WebPMuxAssemble(mux, addr_of_mut!(output)) {
+            libwebp::WEBP_MUX_OK => Ok(output),
+            err => Err(Error::EncodingError(format!("WebP mux error {err}"))),
+        }
+    });
+    libwebp::WebPMuxDelete(mux);
+    result
+}
+
 /// An interface for encoding WebP animations.
 ///
 /// # See Also
 /// * [`WebPStaticEncoder`] for encoding static WebP images instead of animations.
 pub struct WebPMuxEncoder<P: Pixel, W: Write> {
     options: WebPEncoderOptions,
+    metadata: Metadata,
     writer: W,
     mux: *mut libwebp::WebPMux,
     encoded_frames: Vec<libwebp::WebPData>, // drop later
@@ -240,6 +335,7 @@ impl<P: Pixel, W: Write> Encoder<P, W> for WebPMuxEncoder<P, W> {
         };
 
         Ok(Self {
+            metadata: metadata.metadata().cloned().unwrap_or_default(),
             options: metadata.config(),
             writer: dest,
             mux,
@@ -278,6 +374,13 @@ impl<P: Pixel, W: Write> Encoder<P, W> for WebPMuxEncoder<P, W> {
     }
 
     fn finish(mut self) -> crate::Result<()> {
+        if let Err(e) = unsafe { write_metadata(self.mux, &self.metadata) } {
+            unsafe {
+                self.free();
+            }
+            return Err(e);
+        }
+
         let mut final_image = unsafe { std::mem::zeroed::<libwebp::WebPData>() };
         let mux_error = unsafe { libwebp::WebPMuxAssemble(self.mux, addr_of_mut!(final_image)) };
         {
@@ -381,6 +484,7 @@ impl<P: Pixel, R: Read> Decoder<P, R> for WebPDecoder<P, R> {
                 format: ImageFormat::WebP,
                 overlay: OverlayMode::default(),
                 palette: None,
+                metadata: read_metadata(&data),
             })
         }
     }
@@ -398,6 +502,7 @@ impl<P: Pixel, R: Read> Decoder<P, R> for WebPDecoder<P, R> {
                 _marker: PhantomData,
                 demuxer,
                 demux_iter: std::ptr::null_mut(),
+                metadata: Some(read_metadata(&bytes)),
             })
         }
     }
@@ -407,6 +512,7 @@ pub struct WebPSequenceDecoder<P: Pixel> {
     _marker: PhantomData<P>,
     demuxer: *const libwebp::WebPDemuxer,
     demux_iter: *mut libwebp::WebPIterator,
+    metadata: Option<Metadata>,
 }
 
 impl<P: Pixel> FrameIterator<P> for WebPSequenceDecoder<P> {
@@ -465,6 +571,9 @@ impl<P: Pixel> Iterator for WebPSequenceDecoder<P> {
                 format: ImageFormat::WebP,
                 overlay: OverlayMode::default(),
                 palette: None,
+                // Metadata applies to the image as a whole, so it is only attached to the first
+                // frame
+                metadata: self.metadata.take().unwrap_or_default(),
             })
             .with_delay(Duration::from_millis((*self.demux_iter).duration as _))
             .with_disposal(match (*self.demux_iter).dispose_method {
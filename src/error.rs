@@ -1,5 +1,6 @@
 //! Common error types.
 
+use crate::ImageFormat;
 use std::ffi::OsString;
 use std::fmt;
 
@@ -33,6 +34,10 @@ pub enum Error {
     /// an error message.
     DecodingError(String),
 
+    /// An ICC color profile could not be parsed, either because it is malformed or because it is
+    /// not a matrix/TRC based RGB or grayscale profile.
+    InvalidIccProfile(String),
+
     /// An error occured while trying to render or rasterize a font.
     #[cfg(feature = "text")]
     FontError(&'static str),
@@ -40,6 +45,14 @@ pub enum Error {
     /// No encoding format could be inferred for the given image.
     UnknownEncodingFormat,
 
+    /// No encoder or decoder implementation is available for the given image format. This is
+    /// either because the format is [`ImageFormat::Unknown`], or because the cargo feature for
+    /// the format is not enabled.
+    ///
+    /// See [`ImageFormat::can_decode`] and [`ImageFormat::can_encode`] to check for this
+    /// beforehand.
+    UnsupportedFormat(ImageFormat),
+
     /// An image received data incompatible with the image's dimensions.
     IncompatibleImageData {
         width: u32,
@@ -83,9 +96,19 @@ impl fmt::Display for Error {
             }
             Self::EncodingError(msg) => write!(f, "Encoding error: {msg}"),
             Self::DecodingError(msg) => write!(f, "Decoding error: {msg}"),
+            Self::InvalidIccProfile(msg) => write!(f, "Invalid ICC profile: {msg}"),
             #[cfg(feature = "text")]
             Self::FontError(msg) => write!(f, "Font error: {msg}"),
             Self::UnknownEncodingFormat => write!(f, "Could not infer encoding format"),
+            Self::UnsupportedFormat(ImageFormat::Unknown) => write!(
+                f,
+                "No encoder or decoder implementation exists for an unknown image format"
+            ),
+            Self::UnsupportedFormat(format) => write!(
+                f,
+                "No encoder or decoder implementation is available for the {format} format. \
+                Did you forget to enable the `{format}` feature?"
+            ),
             Self::UnsupportedColorType => write!(
                 f,
                 "Unsupported color type. Try using the `Dynamic` pixel type instead."
@@ -188,3 +211,13 @@ impl From<gif::DecodingError> for Error {
         }
     }
 }
+
+#[cfg(feature = "tiff")]
+impl From<tiff::TiffError> for Error {
+    fn from(err: tiff::TiffError) -> Self {
+        match err {
+            tiff::TiffError::IoError(err) => Self::IoError(err),
+            err => Self::DecodingError(err.to_string()),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 //! Filters that can be applied on images.
 
-use crate::{BitPixel, FromVector, Image, IntoVector, Pixel, Vector};
+use crate::{BitPixel, ColorType, EdgeMode, FromVector, Image, IntoVector, Pixel, Vector};
 use num_traits::{
-    AsPrimitive, Bounded, ConstOne, ConstZero, Float, FromPrimitive, Num, SaturatingAdd, Zero,
+    AsPrimitive, Bounded, ConstOne, ConstZero, Float, FromPrimitive, Num, PrimInt, SaturatingAdd,
+    Unsigned, Zero,
 };
 use std::{
     marker::PhantomData,
@@ -29,8 +30,53 @@ pub trait Filter {
         y: u32,
         pixel: Self::Input,
     ) -> Self::Output;
+
+    /// Applies the filter to every pixel of the given image, returning the pixels of the output
+    /// image in row-major order.
+    ///
+    /// By default, this calls [`Self::apply_pixel`] for each pixel. Filters may override this
+    /// with a faster implementation that processes the image as a whole, as long as it produces
+    /// the same output.
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        let width = image.width();
+
+        image
+            .data
+            .iter()
+            .zip(0..)
+            .map(|(&pixel, i)| self.apply_pixel(image, i % width, i / width, pixel))
+            .collect()
+    }
 }
 
+/// A marker trait for types that are [`Send`] when the `parallel` feature is enabled. When the
+/// feature is disabled, this is implemented for all types.
+#[cfg(feature = "parallel")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "parallel")]
+impl<T: Send> MaybeSend for T {}
+
+/// A marker trait for types that are [`Send`] when the `parallel` feature is enabled. When the
+/// feature is disabled, this is implemented for all types.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSend for T {}
+
+/// A marker trait for types that are [`Sync`] when the `parallel` feature is enabled. When the
+/// feature is disabled, this is implemented for all types.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync> MaybeSync for T {}
+
+/// A marker trait for types that are [`Sync`] when the `parallel` feature is enabled. When the
+/// feature is disabled, this is implemented for all types.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
 /// An identity filter. This filter does not modify the image in any way.
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Identity<P: Pixel> {
@@ -103,6 +149,28 @@ where
             pixel.into()
         }
     }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        let width = image.width();
+
+        self.filter
+            .apply_image(image)
+            .into_iter()
+            .zip(&image.data)
+            .zip(0..)
+            .map(|((filtered, &pixel), i)| {
+                if self
+                    .mask
+                    .get_pixel(i % width, i / width)
+                    .is_some_and(BitPixel::value)
+                {
+                    filtered
+                } else {
+                    pixel.into()
+                }
+            })
+            .collect()
+    }
 }
 
 /// Represents a filter which is backed by a kernel matrix, a 2D array of weights. A matrix
@@ -267,6 +335,8 @@ pub struct Convolution<
 {
     /// The kernel, which is the matrix of weights to apply to the output pixel.
     pub kernel: [[Element; KERNEL_WIDTH]; KERNEL_HEIGHT],
+    /// How pixels outside of the image are resolved. Defaults to [`EdgeMode::Skip`].
+    pub edge_mode: EdgeMode<Input>,
     _marker: PhantomData<(Input, Output)>,
 }
 
@@ -304,10 +374,18 @@ where
     pub const fn new(kernel: [[E; KERNEL_WIDTH]; KERNEL_HEIGHT]) -> Self {
         Self {
             kernel,
+            edge_mode: EdgeMode::Skip,
             _marker: PhantomData,
         }
     }
 
+    /// Sets how pixels outside of the image are resolved. See [`EdgeMode`] for more information.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge_mode: EdgeMode<I>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
     /// Returns the center indices of the kernel, corresponding to the pixel being processed.
     #[must_use]
     pub const fn center() -> (usize, usize) {
@@ -402,43 +480,516 @@ where
     }
 }
 
-macro_rules! impl_convolution_apply_pixel {
-    (
-        $self:ident, $image:ident, $x:ident, $y:ident;
-        $center:expr, $w:expr, $h:expr
-    ) => {{
-        let image = $image;
-        let (x, y) = ($x, $y);
+// These kernels have negative weights, so they require a signed kernel element.
+impl<const N: usize, I, O, E> Convolution<3, 3, N, I, O, E>
+where
+    I: Pixel + IntoVector<N>,
+    O: Pixel + FromVector<N>,
+    E: Copy + AsPrimitive<O::Element> + From<i8>,
+    I::Element: AsPrimitive<E>,
+    O::Element: Copy,
+{
+    fn from_i8_kernel(kernel: [[i8; 3]; 3]) -> Self {
+        Self::new(kernel.map(|row| row.map(E::from)))
+    }
+
+    /// Creates a convolution filter that sharpens the image by emphasizing the difference between
+    /// each pixel and its direct neighbors.
+    ///
+    /// Like the other presets with negative weights, this is not available for unsigned kernel
+    /// elements:
+    ///
+    /// ```compile_fail
+    /// # use ril::{Luma, filter::Convolution};
+    /// let filter = Convolution::<3, 3, 1, Luma, Luma, u8>::sharpen();
+    /// ```
+    ///
+    /// # See Also
+    /// * [`UnsharpMask`] for a configurable sharpening filter.
+    #[must_use]
+    pub fn sharpen() -> Self {
+        Self::from_i8_kernel([[0, -1, 0], [-1, 5, -1], [0, -1, 0]])
+    }
+
+    /// Creates a convolution filter that gives the image an embossed, raised appearance, lit from
+    /// the top left.
+    #[must_use]
+    pub fn emboss() -> Self {
+        Self::from_i8_kernel([[-2, -1, 0], [-1, 1, 1], [0, 1, 2]])
+    }
+
+    /// Creates a convolution filter that computes the Laplacian of the image, the second
+    /// derivative of its intensity, which highlights regions of rapid change such as edges.
+    ///
+    /// Negative responses are clamped to zero when the output pixel type cannot represent them.
+    #[must_use]
+    pub fn laplacian() -> Self {
+        Self::from_i8_kernel([[0, 1, 0], [1, -4, 1], [0, 1, 0]])
+    }
+}
+
+/// The edge mode of a neighborhood-based filter, along with the constant pixel of the edge mode
+/// converted into a vector.
+struct Edges<'a, const N: usize, I: Pixel, E> {
+    mode: &'a EdgeMode<I>,
+    constant: Vector<N, E>,
+    /// Whether sums should be rescaled to make up for skipped pixels, which is only the case
+    /// with [`EdgeMode::Skip`] when the total weight of the kernel is non-zero.
+    rescales: bool,
+    /// Whether colors are weighted by their alpha, i.e. convolved with premultiplied alpha, so
+    /// that the colors of transparent pixels do not bleed into their neighbors. This is only the
+    /// case for pixels with alpha when the total weight of the kernel is non-zero, since kernels
+    /// such as those used for edge detection leave no alpha to divide the colors by.
+    premultiplies: bool,
+}
+
+impl<'a, const N: usize, I, E> Edges<'a, N, I, E>
+where
+    I: Pixel + IntoVector<N>,
+    I::Element: Copy + AsPrimitive<E>,
+    E: ConstZero + Num + Copy + 'static,
+{
+    fn new(mode: &'a EdgeMode<I>, weights: impl IntoIterator<Item = E>) -> Self
+    where
+        E: AsPrimitive<f64>,
+    {
+        // Kernels such as those used for edge detection add up to zero, which may be off by some
+        // rounding error
+        let (total, magnitude) = weights.into_iter().fold((0.0, 0.0), |(t, m), weight| {
+            let weight: f64 = weight.as_();
+            (t + weight, m + weight.abs())
+        });
+        let nonzero = total.abs() > magnitude * 1e-9;
+
+        let mut edges = Self {
+            mode,
+            constant: Vector::zero(),
+            rescales: matches!(mode, EdgeMode::Skip) && nonzero,
+            premultiplies: matches!(I::COLOR_TYPE, ColorType::LumaA | ColorType::Rgba) && nonzero,
+        };
+        if let EdgeMode::Constant(pixel) = mode {
+            edges.constant = edges.vector(*pixel);
+        }
+        edges
+    }
+
+    /// Converts the given pixel into a vector, premultiplying its colors by its alpha if needed.
+    /// The alpha is always the last element of the vector.
+    ///
+    /// Colors are multiplied by the alpha as is rather than by a fraction of it, which keeps
+    /// integer kernels precise. This is undone by [`Self::unpremultiply`].
+    #[inline]
+    fn vector(&self, pixel: I) -> Vector<N, E> {
+        let mut vector = pixel.into_vector().map(AsPrimitive::as_);
+        if self.premultiplies {
+            let alpha = vector[N - 1];
+            for c in 0..N - 1 {
+                vector[c] = vector[c] * alpha;
+            }
+        }
+        vector
+    }
+
+    /// Converts the pixels of the given image into vectors. See [`Self::vector`].
+    fn vectorize(&self, image: &Image<I>) -> Vec<Vector<N, E>> {
+        image.data.iter().map(|&pixel| self.vector(pixel)).collect()
+    }
+
+    /// Divides the colors of the given convolved vector by its alpha if they were premultiplied.
+    /// Colors with no alpha left become zero.
+    #[inline]
+    fn unpremultiply(&self, mut vector: Vector<N, E>) -> Vector<N, E>
+    where
+        E: AsPrimitive<f64>,
+    {
+        if self.premultiplies {
+            let alpha = vector[N - 1];
+            let covered = AsPrimitive::<f64>::as_(alpha) > 0.0;
+            for c in 0..N - 1 {
+                vector[c] = if covered {
+                    vector[c] / alpha
+                } else {
+                    E::zero()
+                };
+            }
+        }
+        vector
+    }
+
+    /// Whether pixels outside of the image are skipped.
+    fn skips(&self) -> bool {
+        matches!(self.mode, EdgeMode::Skip)
+    }
+
+    /// Resolves the given coordinate along an axis of the given length into an index, or `None`
+    /// if the constant pixel should be used instead or the pixel should be skipped.
+    #[inline]
+    fn resolve(&self, coord: i64, len: u32) -> Option<usize> {
+        self.mode.resolve(coord, len).map(|coord| coord as usize)
+    }
+
+    /// Looks up the given coordinate within the given row, returning `None` if the pixel should
+    /// be skipped. A row of `None` lies entirely outside of the image.
+    #[inline]
+    fn tap(&self, row: Option<&[Vector<N, E>]>, coord: i64) -> Option<Vector<N, E>> {
+        match row.zip(self.resolve(coord, row.map_or(0, |row| row.len() as u32))) {
+            Some((row, index)) => Some(row[index]),
+            None if self.skips() => None,
+            None => Some(self.constant),
+        }
+    }
+
+    /// With [`EdgeMode::Skip`], rescales the given sum such that the weights of the pixels that
+    /// were covered add up to the given total weight.
+    #[inline]
+    fn rescale(&self, sum: Vector<N, E>, total: E, covered: E) -> Vector<N, E> {
+        if self.rescales && covered != total && !covered.is_zero() {
+            sum.map(|e| e * total / covered)
+        } else {
+            sum
+        }
+    }
+}
+
+/// Computes the weighted sum of the neighborhood of the pixel at `(x, y)`, where `weight(i, j)`
+/// is the weight of row `i` and column `j` of a kernel with the given dimensions and center.
+fn convolve<const N: usize, I, E>(
+    image: &Image<I>,
+    x: u32,
+    y: u32,
+    (width, height): (usize, usize),
+    (center_y, center_x): (usize, usize),
+    edge_mode: &EdgeMode<I>,
+    weight: impl Fn(usize, usize) -> E,
+) -> Vector<N, E>
+where
+    I: Pixel + IntoVector<N>,
+    I::Element: Copy + AsPrimitive<E>,
+    E: ConstZero + Num + Copy + AsPrimitive<f64>,
+{
+    let weights = (0..height).flat_map(|i| (0..width).map(move |j| (i, j)));
+    let edges = Edges::new(edge_mode, weights.map(|(i, j)| weight(i, j)));
+    let (mut output, mut total, mut covered) = (Vector::zero(), E::zero(), E::zero());
+
+    for i in 0..height {
+        let neighbor_y = edges.resolve(i64::from(y) + i as i64 - center_y as i64, image.height());
+
+        for j in 0..width {
+            let weight = weight(i, j);
+            let neighbor_x =
+                edges.resolve(i64::from(x) + j as i64 - center_x as i64, image.width());
+            total = total + weight;
+
+            match neighbor_x.zip(neighbor_y) {
+                Some((neighbor_x, neighbor_y)) => {
+                    let neighbor_pixel = image.pixel(neighbor_x as u32, neighbor_y as u32);
+                    output += edges.vector(*neighbor_pixel) * weight;
+                    covered = covered + weight;
+                }
+                None if edges.skips() => (),
+                None => output += edges.constant * weight,
+            }
+        }
+    }
+
+    edges.unpremultiply(edges.rescale(output, total, covered))
+}
+
+/// The number of rows processed at a time when convolving an entire image.
+const BLOCK_ROWS: usize = 16;
+
+/// Calls `f` with the index and contents of each row of the given row-major data, processing
+/// blocks of [`BLOCK_ROWS`] rows at a time. With the `parallel` feature, blocks are processed in
+/// parallel.
+fn for_each_row<T: MaybeSend>(
+    data: &mut [T],
+    width: usize,
+    f: impl Fn(usize, &mut [T]) + MaybeSend + MaybeSync,
+) {
+    let process_block = |(block, rows): (usize, &mut [T])| {
+        for (i, row) in rows.chunks_mut(width).enumerate() {
+            f(block * BLOCK_ROWS + i, row);
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        data.par_chunks_mut(width * BLOCK_ROWS)
+            .enumerate()
+            .for_each(process_block);
+    }
+    #[cfg(not(feature = "parallel"))]
+    data.chunks_mut(width * BLOCK_ROWS)
+        .enumerate()
+        .for_each(process_block);
+}
+
+/// Convolves the entire image with the given flattened kernel, producing the same (unclamped)
+/// output as calling [`convolve`] on each pixel.
+///
+/// Separable kernels are detected and applied as a horizontal pass followed by a vertical pass,
+/// which takes `O(w + h)` rather than `O(w * h)` operations per pixel for a `w` by `h` kernel.
+/// All passes operate on whole rows at a time in order to stay cache-friendly.
+fn convolve_image<const N: usize, I, E>(
+    image: &Image<I>,
+    kernel: &[E],
+    kernel_width: usize,
+    center: (usize, usize),
+    edge_mode: &EdgeMode<I>,
+) -> Vec<Vector<N, E>>
+where
+    I: Pixel + IntoVector<N> + MaybeSync,
+    I::Element: Copy + AsPrimitive<E>,
+    E: ConstZero + Num + Copy + AsPrimitive<f64> + MaybeSend + MaybeSync,
+{
+    let edges = Edges::new(edge_mode, kernel.iter().copied());
+    let source = edges.vectorize(image);
+
+    let mut output = match separate(kernel, kernel_width) {
+        Some((horizontal, vertical, divisor)) => convolve_separable(
+            image,
+            &source,
+            (&horizontal, &vertical, divisor),
+            center,
+            &edges,
+        ),
+        None => convolve_dense(image, &source, kernel, kernel_width, center, &edges),
+    };
+    for vector in &mut output {
+        *vector = edges.unpremultiply(*vector);
+    }
+    output
+}
+
+/// Attempts to split the given flattened kernel into horizontal and vertical weights along with
+/// a divisor, such that `kernel[i][j] = vertical[i] * horizontal[j] / divisor`.
+///
+/// Weights are taken directly from the kernel rather than normalized, so that kernels with
+/// integer weights can be separated without losing precision.
+fn separate<E>(kernel: &[E], width: usize) -> Option<(Vec<E>, Vec<E>, E)>
+where
+    E: Num + Copy + AsPrimitive<f64>,
+{
+    // Use the largest weight as the pivot for numerical stability
+    let (pivot, max) = kernel
+        .iter()
+        .map(|weight| weight.as_().abs())
+        .enumerate()
+        .fold(
+            (0, 0.0),
+            |acc, (i, weight)| {
+                if weight > acc.1 {
+                    (i, weight)
+                } else {
+                    acc
+                }
+            },
+        );
+    if max == 0.0 {
+        return None;
+    }
+
+    let (p, q) = (pivot / width, pivot % width);
+    let at = |i: usize, j: usize| kernel[i * width + j];
+    let rows = kernel.len() / width;
+
+    // A kernel is separable if and only if it has a rank of 1, which is the case when every
+    // 2x2 minor involving the pivot is zero.
+    let tolerance = max * max * 1e-9;
+    let separable = (0..rows).all(|i| {
+        (0..width).all(|j| {
+            let minor = at(i, j).as_() * at(p, q).as_() - at(i, q).as_() * at(p, j).as_();
+            minor.abs() <= tolerance
+        })
+    });
+
+    if separable {
+        let horizontal = (0..width).map(|j| at(p, j)).collect();
+        let vertical = (0..rows).map(|i| at(i, q)).collect();
+        Some((horizontal, vertical, at(p, q)))
+    } else {
+        None
+    }
+}
+
+/// Convolves the given image data with a separable kernel, given as its horizontal weights,
+/// vertical weights, and divisor. See [`separate`] for more information.
+fn convolve_separable<const N: usize, I, E>(
+    image: &Image<I>,
+    source: &[Vector<N, E>],
+    (horizontal, vertical, divisor): (&[E], &[E], E),
+    (center_y, center_x): (usize, usize),
+    edges: &Edges<N, I, E>,
+) -> Vec<Vector<N, E>>
+where
+    I: Pixel + IntoVector<N> + MaybeSync,
+    I::Element: Copy + AsPrimitive<E>,
+    E: ConstZero + Num + Copy + MaybeSend + MaybeSync + 'static,
+{
+    let (width, height) = image.dimensions();
+    let row_len = width as usize;
+
+    let sum = |weights: &[E]| weights.iter().fold(E::zero(), |acc, &weight| acc + weight);
+    let (horizontal_total, vertical_total) = (sum(horizontal), sum(vertical));
+
+    // Skipped pixels are made up for once both passes are done, which keeps integer kernels
+    // exact. The horizontal weights that are covered only depend on the column.
+    let horizontal_covered = (0..row_len)
+        .map(|x| {
+            let start = x as i64 - center_x as i64;
+            let weights = horizontal
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| edges.resolve(start + *j as i64, width).is_some());
+            weights.fold(E::zero(), |acc, (_, &weight)| acc + weight)
+        })
+        .collect::<Vec<_>>();
+
+    let mut intermediate = vec![Vector::zero(); source.len()];
+    for_each_row(&mut intermediate, row_len, |y, row| {
+        let source = &source[y * row_len..][..row_len];
+
+        for (x, output) in row.iter_mut().enumerate() {
+            let start = x as i64 - center_x as i64;
+
+            // Fast path for pixels whose neighborhood lies entirely within the image
+            if start >= 0 && start as usize + horizontal.len() <= row_len {
+                for (&value, &weight) in source[start as usize..].iter().zip(horizontal) {
+                    *output += value * weight;
+                }
+                continue;
+            }
+
+            for (j, &weight) in horizontal.iter().enumerate() {
+                if let Some(value) = edges.tap(Some(source), start + j as i64) {
+                    *output += value * weight;
+                }
+            }
+        }
+    });
+
+    let mut output = vec![Vector::zero(); source.len()];
+    for_each_row(&mut output, row_len, |y, row| {
+        let mut covered = E::zero();
+
+        for (i, &weight) in vertical.iter().enumerate() {
+            let neighbor_y = y as i64 + i as i64 - center_y as i64;
+
+            match edges.resolve(neighbor_y, height) {
+                Some(neighbor_y) => {
+                    let source = &intermediate[neighbor_y * row_len..][..row_len];
+                    for (output, &value) in row.iter_mut().zip(source) {
+                        *output += value * weight;
+                    }
+                    covered = covered + weight;
+                }
+                None if edges.skips() => (),
+                None => {
+                    // Each pixel of a row outside of the image is the result of the horizontal
+                    // pass over a row of constant pixels
+                    let value = edges.constant * (horizontal_total * weight);
+                    for output in row.iter_mut() {
+                        *output += value;
+                    }
+                }
+            }
+        }
+
+        for (output, &horizontal_covered) in row.iter_mut().zip(&horizontal_covered) {
+            if divisor != E::one() {
+                *output = output.map(|e| e / divisor);
+            }
+            *output = edges.rescale(
+                *output,
+                horizontal_total * vertical_total / divisor,
+                horizontal_covered * covered / divisor,
+            );
+        }
+    });
+
+    output
+}
+
+/// Convolves the given image data with an arbitrary flattened kernel.
+fn convolve_dense<const N: usize, I, E>(
+    image: &Image<I>,
+    source: &[Vector<N, E>],
+    kernel: &[E],
+    kernel_width: usize,
+    (center_y, center_x): (usize, usize),
+    edges: &Edges<N, I, E>,
+) -> Vec<Vector<N, E>>
+where
+    I: Pixel + IntoVector<N> + MaybeSync,
+    I::Element: Copy + AsPrimitive<E>,
+    E: ConstZero + Num + Copy + MaybeSend + MaybeSync + 'static,
+{
+    let (width, height) = image.dimensions();
+    let row_len = width as usize;
+    let total = kernel.iter().fold(E::zero(), |acc, &weight| acc + weight);
+
+    let mut output = vec![Vector::zero(); source.len()];
+    for_each_row(&mut output, row_len, |y, row| {
+        let mut covered = vec![E::zero(); row_len];
 
-        let (center_y, center_x) = $center;
-        let mut output = Vector::zero();
+        for (i, weights) in kernel.chunks(kernel_width).enumerate() {
+            let neighbor_y = y as i64 + i as i64 - center_y as i64;
+            let source = edges
+                .resolve(neighbor_y, height)
+                .map(|neighbor_y| &source[neighbor_y * row_len..][..row_len]);
 
-        for i in 0..$h {
-            for j in 0..$w {
-                let kernel_value = $self.weight(i, j);
-                let neighbor_x = x.saturating_sub(center_x as u32).saturating_add(j as u32);
-                let neighbor_y = y.saturating_sub(center_y as u32).saturating_add(i as u32);
+            for (j, &weight) in weights.iter().enumerate() {
+                if weight.is_zero() {
+                    continue;
+                }
 
-                if let Some(neighbor_pixel) = image.get_pixel(neighbor_x, neighbor_y) {
-                    output += neighbor_pixel.into_vector().map(AsPrimitive::as_) * kernel_value;
+                let offset = j as i64 - center_x as i64;
+                for (x, (output, covered)) in row.iter_mut().zip(&mut covered).enumerate() {
+                    if let Some(value) = edges.tap(source, x as i64 + offset) {
+                        *output += value * weight;
+                        *covered = *covered + weight;
+                    }
                 }
             }
         }
 
-        Self::Output::from_vector(output.map(|e| {
-            let min = <O::Element as Bounded>::min_value().as_();
-            let max = <O::Element as Bounded>::max_value().as_();
-            num_traits::clamp(e, min, max).as_()
-        }))
-    }};
+        for (output, covered) in row.iter_mut().zip(covered) {
+            *output = edges.rescale(*output, total, covered);
+        }
+    });
+
+    output
+}
+
+/// Converts the given vector into a pixel, clamping each element into the range of the
+/// subpixel type.
+fn saturate<const N: usize, O, E>(vector: Vector<N, E>) -> O
+where
+    O: FromVector<N>,
+    O::Element: Copy + Bounded + Zero + AsPrimitive<E>,
+    E: AsPrimitive<O::Element> + PartialOrd,
+{
+    O::from_vector(vector.map(|e| {
+        let min = <O::Element as Bounded>::min_value().as_();
+        let max = <O::Element as Bounded>::max_value().as_();
+        num_traits::clamp(e, min, max).as_()
+    }))
 }
 
 impl<const KERNEL_WIDTH: usize, const KERNEL_HEIGHT: usize, const N: usize, I, O, E> Filter
     for Convolution<KERNEL_WIDTH, KERNEL_HEIGHT, N, I, O, E>
 where
-    I: Pixel + IntoVector<N>,
+    I: Pixel + IntoVector<N> + MaybeSync,
     O: Pixel + FromVector<N>,
-    E: ConstZero + Num + AsPrimitive<O::Element> + PartialOrd,
+    E: ConstZero
+        + Num
+        + AsPrimitive<O::Element>
+        + AsPrimitive<f64>
+        + PartialOrd
+        + MaybeSend
+        + MaybeSync,
     I::Element: Copy + AsPrimitive<E>,
     O::Element: Copy + Bounded + Zero + AsPrimitive<E>,
 {
@@ -452,10 +1003,31 @@ where
         y: u32,
         _pixel: Self::Input,
     ) -> Self::Output {
-        impl_convolution_apply_pixel! {
-            self, image, x, y;
-            Self::center(), KERNEL_WIDTH, KERNEL_HEIGHT
-        }
+        let dimensions = (KERNEL_WIDTH, KERNEL_HEIGHT);
+        saturate(convolve(
+            image,
+            x,
+            y,
+            dimensions,
+            Self::center(),
+            &self.edge_mode,
+            |i, j| self.weight(i, j),
+        ))
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        let kernel = self.kernel.iter().flatten().copied().collect::<Vec<_>>();
+
+        convolve_image(
+            image,
+            &kernel,
+            KERNEL_WIDTH,
+            Self::center(),
+            &self.edge_mode,
+        )
+        .into_iter()
+        .map(saturate)
+        .collect()
     }
 }
 
@@ -479,6 +1051,8 @@ where
     ///
     /// This is the number of columns in the kernel matrix.
     pub width: usize,
+    /// How pixels outside of the image are resolved. Defaults to [`EdgeMode::Skip`].
+    pub edge_mode: EdgeMode<Input>,
     _marker: PhantomData<(Input, Output)>,
 }
 
@@ -502,10 +1076,18 @@ where
         Self {
             kernel,
             width,
+            edge_mode: EdgeMode::Skip,
             _marker: PhantomData,
         }
     }
 
+    /// Sets how pixels outside of the image are resolved. See [`EdgeMode`] for more information.
+    #[must_use]
+    pub fn with_edge_mode(mut self, edge_mode: EdgeMode<I>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
     /// The dimensions of the kernel matrix.
     #[must_use]
     pub fn dimensions(&self) -> (usize, usize) {
@@ -516,7 +1098,7 @@ where
     /// Returns the center indices of the kernel, corresponding to the pixel being processed.
     #[must_use]
     pub fn center(&self) -> (usize, usize) {
-        let (height, width) = self.dimensions();
+        let (width, height) = self.dimensions();
         (height / 2, width / 2)
     }
 
@@ -526,7 +1108,7 @@ where
     /// Panics if the indices are out of bounds.
     #[must_use]
     pub fn weight(&self, i: usize, j: usize) -> E {
-        let (height, width) = self.dimensions();
+        let (width, height) = self.dimensions();
         assert!(i < height && j < width, "Indices out of bounds");
         self.kernel[i * width + j]
     }
@@ -536,7 +1118,7 @@ where
     /// # Panics
     /// Panics if the indices are out of bounds.
     pub fn set_weight(&mut self, i: usize, j: usize, value: E) {
-        let (height, width) = self.dimensions();
+        let (width, height) = self.dimensions();
         assert!(i < height && j < width, "Indices out of bounds");
         self.kernel[i * width + j] = value;
     }
@@ -562,9 +1144,17 @@ where
 
 impl<const N: usize, I, O, E> Filter for DynamicConvolution<N, I, O, E>
 where
-    I: Pixel + IntoVector<N>,
+    I: Pixel + IntoVector<N> + MaybeSync,
     O: Pixel + FromVector<N>,
-    E: Float + ConstZero + Add<Output = E> + Mul<Output = E> + AsPrimitive<O::Element> + PartialOrd,
+    E: Float
+        + ConstZero
+        + Add<Output = E>
+        + Mul<Output = E>
+        + AsPrimitive<O::Element>
+        + AsPrimitive<f64>
+        + PartialOrd
+        + MaybeSend
+        + MaybeSync,
     I::Element: Copy + AsPrimitive<E>,
     O::Element: Bounded + Zero + AsPrimitive<E> + 'static,
 {
@@ -578,9 +1168,1477 @@ where
         y: u32,
         _pixel: Self::Input,
     ) -> Self::Output {
-        impl_convolution_apply_pixel! {
-            self, image, x, y;
-            self.center(), self.width, self.dimensions().1
+        saturate(convolve(
+            image,
+            x,
+            y,
+            self.dimensions(),
+            self.center(),
+            &self.edge_mode,
+            |i, j| self.weight(i, j),
+        ))
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        convolve_image(
+            image,
+            &self.kernel,
+            self.width,
+            self.center(),
+            &self.edge_mode,
+        )
+        .into_iter()
+        .map(saturate)
+        .collect()
+    }
+}
+
+impl<const N: usize, I, O, E> DynamicConvolution<N, I, O, E>
+where
+    I: Pixel + IntoVector<N>,
+    O: Pixel + FromVector<N>,
+    E: Float + 'static,
+    f64: AsPrimitive<E>,
+{
+    /// Creates a convolution filter that blurs the image along a line of the given length,
+    /// simulating the streaks of a moving camera or subject.
+    ///
+    /// The angle is specified in degrees clockwise from the positive x-axis, so `0.0` blurs
+    /// horizontally and `90.0` blurs vertically. The kernel is a square matrix with the given
+    /// length as its side length, rounded up to the nearest odd number.
+    ///
+    /// # Panics
+    /// * `length` is zero.
+    #[must_use]
+    pub fn motion_blur(length: usize, degrees: f64) -> Self {
+        assert!(length > 0, "motion blur length must be greater than zero");
+
+        let size = length | 1;
+        let center = (size / 2) as f64;
+        let radius = (length as f64 - 1.0) / 2.0;
+        let (sin, cos) = degrees.to_radians().sin_cos();
+
+        let kernel = (0..size * size)
+            .map(|index| {
+                let dx = (index % size) as f64 - center;
+                let dy = (index / size) as f64 - center;
+                // Weigh each cell by its distance from the line segment
+                let along = dx.mul_add(cos, dy * sin);
+                let across = dy.mul_add(cos, -dx * sin).abs();
+
+                if along.abs() <= radius + 0.5 {
+                    (1.0 - across).max(0.0).as_()
+                } else {
+                    E::zero()
+                }
+            })
+            .collect();
+
+        let mut filter = Self::new(kernel, size);
+        filter.normalize();
+        filter
+    }
+}
+
+/// A convolution filter with a separable kernel, which is a kernel that can be expressed as the
+/// outer product of a vertical and a horizontal vector of weights.
+///
+/// Many common kernels, such as those of Gaussian and box blurs, are separable.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let blur = SeparableConvolution::<3, Rgb>::gaussian_blur(2.5);
+///
+/// Image::<Rgb>::open("sample.png")?
+///     .filtered(&blur)
+///     .save_inferred("blurry.png")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SeparableConvolution<const N: usize, Input, Output = Input, Element = f64>
+where
+    Element: Float,
+    Input: Pixel + IntoVector<N>,
+    Output: Pixel + FromVector<N>,
+{
+    /// The horizontal weights of the kernel, i.e. the weights of each column.
+    pub horizontal: Vec<Element>,
+    /// The vertical weights of the kernel, i.e. the weights of each row.
+    pub vertical: Vec<Element>,
+    /// How pixels outside of the image are resolved. Defaults to [`EdgeMode::Skip`].
+    pub edge_mode: EdgeMode<Input>,
+    _marker: PhantomData<(Input, Output)>,
+}
+
+impl<const N: usize, I, O, E> SeparableConvolution<N, I, O, E>
+where
+    I: Pixel + IntoVector<N>,
+    O: Pixel + FromVector<N>,
+    E: Float,
+{
+    /// Creates a new separable convolution filter from the given horizontal and vertical weights.
+    ///
+    /// # Panics
+    /// * Either of the weight vectors is empty.
+    #[must_use]
+    pub fn new(horizontal: Vec<E>, vertical: Vec<E>) -> Self {
+        assert!(
+            !horizontal.is_empty() && !vertical.is_empty(),
+            "Kernel cannot be empty"
+        );
+
+        Self {
+            horizontal,
+            vertical,
+            edge_mode: EdgeMode::Skip,
+            _marker: PhantomData,
         }
     }
+
+    /// Sets how pixels outside of the image are resolved. See [`EdgeMode`] for more information.
+    #[must_use]
+    pub fn with_edge_mode(mut self, edge_mode: EdgeMode<I>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// The dimensions of the kernel matrix, as `(width, height)`.
+    #[must_use]
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.horizontal.len(), self.vertical.len())
+    }
+
+    /// Returns the center indices of the kernel, corresponding to the pixel being processed.
+    #[must_use]
+    pub fn center(&self) -> (usize, usize) {
+        (self.vertical.len() / 2, self.horizontal.len() / 2)
+    }
+
+    /// Returns the weight at the given position in the kernel matrix, K_{i,j}.
+    ///
+    /// # Panics
+    /// Panics if the indices are out of bounds.
+    #[must_use]
+    pub fn weight(&self, i: usize, j: usize) -> E {
+        self.vertical[i] * self.horizontal[j]
+    }
+
+    /// Creates a convolution filter that applies a Gaussian blur with the given standard
+    /// deviation (in pixels) to the image.
+    ///
+    /// The kernel extends three standard deviations in each direction. A standard deviation of
+    /// zero or less does not blur the image at all.
+    #[must_use]
+    pub fn gaussian_blur(sigma: E) -> Self
+    where
+        E: 'static,
+        f64: AsPrimitive<E>,
+    {
+        let sigma = sigma.to_f64().unwrap_or_default();
+        if sigma <= 0.0 {
+            return Self::new(vec![E::one()], vec![E::one()]);
+        }
+
+        let radius = (sigma * 3.0).ceil() as i64;
+        let weights = (-radius..=radius)
+            .map(|x| (-((x * x) as f64) / (2.0 * sigma * sigma)).exp())
+            .collect::<Vec<_>>();
+        let sum = weights.iter().sum::<f64>();
+        let weights = weights
+            .into_iter()
+            .map(|weight| (weight / sum).as_())
+            .collect::<Vec<_>>();
+
+        Self::new(weights.clone(), weights)
+    }
+
+    /// Creates a convolution filter that applies a box blur with the given kernel dimensions to
+    /// the image.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    #[must_use]
+    pub fn box_blur(width: usize, height: usize) -> Self
+    where
+        E: 'static,
+        usize: AsPrimitive<E>,
+    {
+        Self::new(
+            vec![E::one() / width.as_(); width],
+            vec![E::one() / height.as_(); height],
+        )
+    }
+}
+
+impl<const N: usize, I, O, E> SeparableConvolution<N, I, O, E>
+where
+    I: Pixel + IntoVector<N> + MaybeSync,
+    O: Pixel + FromVector<N>,
+    E: Float + ConstZero + AsPrimitive<f64> + MaybeSend + MaybeSync,
+    I::Element: Copy + AsPrimitive<E>,
+{
+    /// Convolves the entire image without clamping the output.
+    fn convolve_image(&self, image: &Image<I>) -> Vec<Vector<N, E>> {
+        let (horizontal, vertical) = (&self.horizontal, &self.vertical);
+        let weights = vertical
+            .iter()
+            .flat_map(|&v| horizontal.iter().map(move |&h| v * h));
+        let edges = Edges::new(&self.edge_mode, weights);
+        let source = edges.vectorize(image);
+
+        let mut output = convolve_separable(
+            image,
+            &source,
+            (horizontal, vertical, E::one()),
+            self.center(),
+            &edges,
+        );
+        for vector in &mut output {
+            *vector = edges.unpremultiply(*vector);
+        }
+        output
+    }
+}
+
+impl<const N: usize, I, O, E> Filter for SeparableConvolution<N, I, O, E>
+where
+    I: Pixel + IntoVector<N> + MaybeSync,
+    O: Pixel + FromVector<N>,
+    E: Float + ConstZero + AsPrimitive<O::Element> + AsPrimitive<f64> + MaybeSend + MaybeSync,
+    I::Element: Copy + AsPrimitive<E>,
+    O::Element: Bounded + Zero + AsPrimitive<E> + 'static,
+{
+    type Input = I;
+    type Output = O;
+
+    fn apply_pixel(
+        &self,
+        image: &Image<Self::Input>,
+        x: u32,
+        y: u32,
+        _pixel: Self::Input,
+    ) -> Self::Output {
+        saturate(convolve(
+            image,
+            x,
+            y,
+            self.dimensions(),
+            self.center(),
+            &self.edge_mode,
+            |i, j| self.weight(i, j),
+        ))
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        self.convolve_image(image)
+            .into_iter()
+            .map(saturate)
+            .collect()
+    }
+}
+
+/// A filter which sharpens the image by amplifying the difference between the image and a
+/// Gaussian-blurred copy of it.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let sharpen = UnsharpMask::<3, Rgb>::new(2.0, 1.5).with_threshold(4.0);
+///
+/// Image::<Rgb>::open("sample.png")?
+///     .filtered(&sharpen)
+///     .save_inferred("sharp.png")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct UnsharpMask<const N: usize, P, Element = f64>
+where
+    Element: Float,
+    P: Pixel + IntoVector<N> + FromVector<N>,
+{
+    /// The blur used to compute the details of the image.
+    pub blur: SeparableConvolution<N, P, P, Element>,
+    /// How strongly the details are amplified. `1.0` doubles the contrast of the details.
+    pub amount: Element,
+    /// The minimum difference between a subpixel and its blurred counterpart for it to be
+    /// sharpened, which avoids amplifying noise.
+    pub threshold: Element,
+}
+
+impl<const N: usize, P, E> UnsharpMask<N, P, E>
+where
+    P: Pixel + IntoVector<N> + FromVector<N>,
+    E: Float + 'static,
+    f64: AsPrimitive<E>,
+{
+    /// Creates a new unsharp mask filter with the given blur standard deviation (in pixels) and
+    /// amount, with no threshold.
+    #[must_use]
+    pub fn new(sigma: E, amount: E) -> Self {
+        Self {
+            blur: SeparableConvolution::gaussian_blur(sigma),
+            amount,
+            threshold: E::zero(),
+        }
+    }
+
+    /// Sets the threshold of the filter. See [`Self::threshold`] for more information.
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: E) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets how pixels outside of the image are resolved when blurring the image. See
+    /// [`EdgeMode`] for more information.
+    #[must_use]
+    pub fn with_edge_mode(mut self, edge_mode: EdgeMode<P>) -> Self {
+        self.blur.edge_mode = edge_mode;
+        self
+    }
+}
+
+impl<const N: usize, P, E> UnsharpMask<N, P, E>
+where
+    P: Pixel + IntoVector<N> + FromVector<N>,
+    E: Float + ConstZero + AsPrimitive<<P as FromVector<N>>::Element>,
+    <P as IntoVector<N>>::Element: Copy + AsPrimitive<E>,
+    <P as FromVector<N>>::Element: Bounded + Zero + AsPrimitive<E> + 'static,
+{
+    /// Sharpens the given pixel given its blurred counterpart.
+    fn sharpen(&self, pixel: P, blurred: Vector<N, E>) -> P {
+        let original = pixel.into_vector().map(AsPrimitive::as_);
+        let details = (original - blurred).map(|detail: E| {
+            if detail.abs() < self.threshold {
+                E::zero()
+            } else {
+                detail * self.amount
+            }
+        });
+
+        saturate(original + details)
+    }
+}
+
+impl<const N: usize, P, E> Filter for UnsharpMask<N, P, E>
+where
+    P: Pixel + IntoVector<N> + FromVector<N> + MaybeSync,
+    E: Float
+        + ConstZero
+        + AsPrimitive<<P as FromVector<N>>::Element>
+        + AsPrimitive<f64>
+        + MaybeSend
+        + MaybeSync,
+    <P as IntoVector<N>>::Element: Copy + AsPrimitive<E>,
+    <P as FromVector<N>>::Element: Bounded + Zero + AsPrimitive<E> + 'static,
+{
+    type Input = P;
+    type Output = P;
+
+    fn apply_pixel(
+        &self,
+        image: &Image<Self::Input>,
+        x: u32,
+        y: u32,
+        pixel: Self::Input,
+    ) -> Self::Output {
+        let blur = &self.blur;
+        let blurred = convolve(
+            image,
+            x,
+            y,
+            blur.dimensions(),
+            blur.center(),
+            &blur.edge_mode,
+            |i, j| blur.weight(i, j),
+        );
+
+        self.sharpen(pixel, blurred)
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        self.blur
+            .convolve_image(image)
+            .into_iter()
+            .zip(&image.data)
+            .map(|(blurred, &pixel)| self.sharpen(pixel, blurred))
+            .collect()
+    }
+}
+
+/// A gradient operator used for edge detection, consisting of a pair of 3x3 kernels which
+/// approximate the horizontal and vertical derivatives of the image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeOperator {
+    /// The Sobel operator, which weighs the direct neighbors twice as much as the diagonal ones.
+    Sobel,
+    /// The Scharr operator, which has better rotational symmetry than the Sobel operator.
+    Scharr,
+    /// The Prewitt operator, which weighs all neighbors equally.
+    Prewitt,
+}
+
+impl EdgeOperator {
+    /// Returns the kernel approximating the horizontal derivative. The kernel approximating the
+    /// vertical derivative is its transpose.
+    #[must_use]
+    pub const fn kernel(self) -> [[f64; 3]; 3] {
+        match self {
+            Self::Sobel => [[-1.0, 0.0, 1.0], [-2.0, 0.0, 2.0], [-1.0, 0.0, 1.0]],
+            Self::Scharr => [[-3.0, 0.0, 3.0], [-10.0, 0.0, 10.0], [-3.0, 0.0, 3.0]],
+            Self::Prewitt => [[-1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [-1.0, 0.0, 1.0]],
+        }
+    }
+
+    /// The sum of the positive weights of the kernel, used to normalize the gradient.
+    const fn scale(self) -> f64 {
+        match self {
+            Self::Sobel => 4.0,
+            Self::Scharr => 16.0,
+            Self::Prewitt => 3.0,
+        }
+    }
+}
+
+/// A filter which detects edges by computing the magnitude of the gradient of the image with the
+/// given [`EdgeOperator`].
+///
+/// The gradient is normalized such that a step from the minimum to the maximum subpixel value
+/// results in the maximum subpixel value. Each channel is processed independently, so this filter
+/// is usually applied to grayscale images.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// Image::<Luma>::open("sample.png")?
+///     .filtered(&EdgeDetection::<1, Luma>::sobel())
+///     .save_inferred("edges.png")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct EdgeDetection<const N: usize, Input, Output = Input, Element = f64>
+where
+    Element: Float,
+    Input: Pixel + IntoVector<N>,
+    Output: Pixel + FromVector<N>,
+{
+    /// The gradient operator used to detect edges.
+    pub operator: EdgeOperator,
+    /// How pixels outside of the image are resolved. Defaults to [`EdgeMode::Skip`].
+    pub edge_mode: EdgeMode<Input>,
+    _marker: PhantomData<(Input, Output, Element)>,
+}
+
+impl<const N: usize, I, O, E> EdgeDetection<N, I, O, E>
+where
+    I: Pixel + IntoVector<N>,
+    O: Pixel + FromVector<N>,
+    E: Float,
+{
+    /// Creates a new edge detection filter with the given operator.
+    #[must_use]
+    pub const fn new(operator: EdgeOperator) -> Self {
+        Self {
+            operator,
+            edge_mode: EdgeMode::Skip,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets how pixels outside of the image are resolved. See [`EdgeMode`] for more information.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge_mode: EdgeMode<I>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// Creates a new edge detection filter with the [`Sobel`][EdgeOperator::Sobel] operator.
+    #[must_use]
+    pub const fn sobel() -> Self {
+        Self::new(EdgeOperator::Sobel)
+    }
+
+    /// Creates a new edge detection filter with the [`Scharr`][EdgeOperator::Scharr] operator.
+    #[must_use]
+    pub const fn scharr() -> Self {
+        Self::new(EdgeOperator::Scharr)
+    }
+
+    /// Creates a new edge detection filter with the [`Prewitt`][EdgeOperator::Prewitt] operator.
+    #[must_use]
+    pub const fn prewitt() -> Self {
+        Self::new(EdgeOperator::Prewitt)
+    }
+}
+
+impl<const N: usize, I, O, E> EdgeDetection<N, I, O, E>
+where
+    I: Pixel + IntoVector<N>,
+    O: Pixel + FromVector<N>,
+    E: Float,
+{
+    /// Returns the normalized kernel approximating the horizontal derivative.
+    fn kernel(&self) -> [[E; 3]; 3]
+    where
+        E: 'static,
+        f64: AsPrimitive<E>,
+    {
+        let scale = self.operator.scale();
+
+        self.operator
+            .kernel()
+            .map(|row| row.map(|weight| (weight / scale).as_()))
+    }
+}
+
+impl<const N: usize, I, O, E> Filter for EdgeDetection<N, I, O, E>
+where
+    I: Pixel + IntoVector<N> + MaybeSync,
+    O: Pixel + FromVector<N>,
+    E: Float + ConstZero + AsPrimitive<O::Element> + AsPrimitive<f64> + MaybeSend + MaybeSync,
+    I::Element: Copy + AsPrimitive<E>,
+    O::Element: Bounded + Zero + AsPrimitive<E> + 'static,
+    f64: AsPrimitive<E>,
+{
+    type Input = I;
+    type Output = O;
+
+    fn apply_pixel(
+        &self,
+        image: &Image<Self::Input>,
+        x: u32,
+        y: u32,
+        _pixel: Self::Input,
+    ) -> Self::Output {
+        let kernel = self.kernel();
+
+        let edge_mode = &self.edge_mode;
+        let gx = convolve(image, x, y, (3, 3), (1, 1), edge_mode, |i, j| kernel[i][j]);
+        let gy = convolve(image, x, y, (3, 3), (1, 1), edge_mode, |i, j| kernel[j][i]);
+        saturate(gx.merge(gy, |a, b| a.hypot(b)))
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        let kernel = self.kernel();
+        let horizontal = kernel.iter().flatten().copied().collect::<Vec<_>>();
+        let vertical = (0..9).map(|i| kernel[i % 3][i / 3]).collect::<Vec<_>>();
+
+        let gx = convolve_image(image, &horizontal, 3, (1, 1), &self.edge_mode);
+        let gy = convolve_image(image, &vertical, 3, (1, 1), &self.edge_mode);
+        gx.into_iter()
+            .zip(gy)
+            .map(|(gx, gy)| saturate(gx.merge(gy, |a, b| a.hypot(b))))
+            .collect()
+    }
+}
+
+/// The offsets `(dx, dy)` of the pixels of a neighborhood relative to its center.
+type Offsets = Vec<(i64, i64)>;
+
+/// A structuring element, which is a binary mask describing the neighborhood of each pixel that
+/// is considered by a [`Morphology`] filter.
+///
+/// The center of the structuring element corresponds to the pixel being processed, and defaults to
+/// the center of its bounding box.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StructuringElement {
+    width: u32,
+    height: u32,
+    data: Vec<bool>,
+    center: (u32, u32),
+}
+
+impl StructuringElement {
+    fn from_fn(width: u32, height: u32, f: impl Fn(u32, u32) -> bool) -> Self {
+        assert_ne!(width, 0, "width must be non-zero");
+        assert_ne!(height, 0, "height must be non-zero");
+
+        Self {
+            width,
+            height,
+            data: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| f(x, y))
+                .collect(),
+            center: (width / 2, height / 2),
+        }
+    }
+
+    /// Creates a new rectangular structuring element with the given dimensions, where every pixel
+    /// is part of the neighborhood.
+    ///
+    /// Rectangular structuring elements are applied as a horizontal pass followed by a vertical
+    /// pass, which is much faster than other structuring elements of the same size.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    #[must_use]
+    pub fn rectangle(width: u32, height: u32) -> Self {
+        Self::from_fn(width, height, |_, _| true)
+    }
+
+    /// Creates a new square structuring element with the given side length. This is equivalent to
+    /// calling [`Self::rectangle`] with the same width and height.
+    ///
+    /// # Panics
+    /// * The size is zero.
+    #[must_use]
+    pub fn square(size: u32) -> Self {
+        Self::rectangle(size, size)
+    }
+
+    /// Creates a new cross-shaped structuring element with the given dimensions, consisting of the
+    /// center row and center column.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    #[must_use]
+    pub fn cross(width: u32, height: u32) -> Self {
+        Self::from_fn(width, height, |x, y| x == width / 2 || y == height / 2)
+    }
+
+    /// Creates a new elliptical structuring element with the given dimensions, consisting of the
+    /// pixels whose centers lie within the ellipse inscribed in the bounding box.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    #[must_use]
+    pub fn ellipse(width: u32, height: u32) -> Self {
+        let (rx, ry) = (f64::from(width) / 2.0, f64::from(height) / 2.0);
+
+        Self::from_fn(width, height, |x, y| {
+            let dx = (f64::from(x) + 0.5 - rx) / rx;
+            let dy = (f64::from(y) + 0.5 - ry) / ry;
+            dx * dx + dy * dy <= 1.0
+        })
+    }
+
+    /// Creates a new structuring element from the given mask, where pixels that are on are part of
+    /// the neighborhood.
+    #[must_use]
+    pub fn from_image(image: &Image<BitPixel>) -> Self {
+        Self::from_fn(image.width(), image.height(), |x, y| {
+            image.pixel(x, y).value()
+        })
+    }
+
+    /// Sets the center of the structuring element, relative to the top-left corner of its bounding
+    /// box.
+    ///
+    /// # Panics
+    /// * The center lies outside of the bounding box.
+    #[must_use]
+    pub fn with_center(mut self, x: u32, y: u32) -> Self {
+        assert!(
+            x < self.width && y < self.height,
+            "center must lie within the structuring element"
+        );
+        self.center = (x, y);
+        self
+    }
+
+    /// Returns the dimensions of the structuring element as `(width, height)`.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the center of the structuring element as `(x, y)`.
+    #[must_use]
+    pub const fn center(&self) -> (u32, u32) {
+        self.center
+    }
+
+    /// Returns whether the pixel at the given coordinates is part of the neighborhood.
+    ///
+    /// # Panics
+    /// * The coordinates lie outside of the bounding box.
+    #[must_use]
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        assert!(
+            x < self.width && y < self.height,
+            "coordinates out of bounds"
+        );
+        self.data[(y * self.width + x) as usize]
+    }
+
+    /// Returns the offsets `(dx, dy)` of each pixel of the neighborhood relative to the center.
+    fn offsets(&self) -> Offsets {
+        let (cx, cy) = (i64::from(self.center.0), i64::from(self.center.1));
+
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.contains(x, y))
+            .map(|(x, y)| (i64::from(x) - cx, i64::from(y) - cy))
+            .collect()
+    }
+
+    /// If this is a rectangle, returns the offsets of its center row and center column.
+    fn separate(&self) -> Option<(Offsets, Offsets)> {
+        if !self.data.iter().all(|&on| on) {
+            return None;
+        }
+
+        let (cx, cy) = (i64::from(self.center.0), i64::from(self.center.1));
+        Some((
+            (0..i64::from(self.width)).map(|x| (x - cx, 0)).collect(),
+            (0..i64::from(self.height)).map(|y| (0, y - cy)).collect(),
+        ))
+    }
+}
+
+impl From<&Image<BitPixel>> for StructuringElement {
+    fn from(image: &Image<BitPixel>) -> Self {
+        Self::from_image(image)
+    }
+}
+
+/// A morphological operation performed by a [`Morphology`] filter.
+///
+/// Erosion takes the minimum of the neighborhood of each pixel, and dilation takes the maximum.
+/// The other operations are combinations of the two.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MorphologyOperation {
+    /// Shrinks bright regions, removing bright details smaller than the structuring element.
+    Erode,
+    /// Grows bright regions, filling dark details smaller than the structuring element.
+    Dilate,
+    /// An erosion followed by a dilation, which removes bright specks while preserving the shape
+    /// of larger regions.
+    Open,
+    /// A dilation followed by an erosion, which fills dark holes and gaps while preserving the
+    /// shape of larger regions.
+    Close,
+    /// The difference between the dilation and the erosion, which outlines the edges of regions.
+    Gradient,
+    /// The difference between the image and its opening, which extracts the bright details that
+    /// are smaller than the structuring element. Also known as the white top-hat transform.
+    TopHat,
+    /// The difference between the closing and the image, which extracts the dark details that
+    /// are smaller than the structuring element. Also known as the black top-hat transform.
+    BlackHat,
+}
+
+/// A vector of the channels of the pixel type `P`.
+type Channels<const N: usize, P> = Vector<N, <P as IntoVector<N>>::Element>;
+
+/// A filter which performs a [`MorphologyOperation`] with the given [`StructuringElement`].
+///
+/// Each channel is processed independently. On [`BitPixel`] images, erosion turns off every pixel
+/// next to a pixel that is off, while dilation turns on every pixel next to a pixel that is on.
+///
+/// # Example
+/// Cleaning up a mask before pasting an image with it:
+///
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::<Rgb>::open("sample.png")?;
+/// let overlay = Image::new(image.width(), image.height(), Rgb::new(255, 0, 0));
+/// let mask = Image::<BitPixel>::open("mask.png")?
+///     .filtered(&Morphology::<1, BitPixel>::open(StructuringElement::ellipse(5, 5)));
+///
+/// image.paste_with_mask(0, 0, &overlay, &mask);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Morphology<const N: usize, P: Pixel> {
+    /// The morphological operation to perform.
+    pub operation: MorphologyOperation,
+    /// The neighborhood of each pixel that is considered.
+    pub element: StructuringElement,
+    /// How pixels outside of the image are resolved. Defaults to [`EdgeMode::Skip`], such that
+    /// pixels outside of the image have no effect.
+    pub edge_mode: EdgeMode<P>,
+}
+
+impl<const N: usize, P: Pixel> Morphology<N, P> {
+    /// Creates a new morphology filter with the given operation and structuring element.
+    #[must_use]
+    pub fn new(operation: MorphologyOperation, element: StructuringElement) -> Self {
+        Self {
+            operation,
+            element,
+            edge_mode: EdgeMode::Skip,
+        }
+    }
+
+    /// Sets how pixels outside of the image are resolved. See [`EdgeMode`] for more information.
+    #[must_use]
+    pub fn with_edge_mode(mut self, edge_mode: EdgeMode<P>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// Creates a new filter which erodes the image with the given structuring element.
+    #[must_use]
+    pub fn erode(element: StructuringElement) -> Self {
+        Self::new(MorphologyOperation::Erode, element)
+    }
+
+    /// Creates a new filter which dilates the image with the given structuring element.
+    #[must_use]
+    pub fn dilate(element: StructuringElement) -> Self {
+        Self::new(MorphologyOperation::Dilate, element)
+    }
+
+    /// Creates a new filter which opens the image with the given structuring element.
+    #[must_use]
+    pub fn open(element: StructuringElement) -> Self {
+        Self::new(MorphologyOperation::Open, element)
+    }
+
+    /// Creates a new filter which closes the image with the given structuring element.
+    #[must_use]
+    pub fn close(element: StructuringElement) -> Self {
+        Self::new(MorphologyOperation::Close, element)
+    }
+
+    /// Creates a new filter which computes the morphological gradient of the image with the given
+    /// structuring element.
+    #[must_use]
+    pub fn gradient(element: StructuringElement) -> Self {
+        Self::new(MorphologyOperation::Gradient, element)
+    }
+
+    /// Creates a new filter which computes the top-hat transform of the image with the given
+    /// structuring element.
+    #[must_use]
+    pub fn top_hat(element: StructuringElement) -> Self {
+        Self::new(MorphologyOperation::TopHat, element)
+    }
+
+    /// Creates a new filter which computes the black-hat transform of the image with the given
+    /// structuring element.
+    #[must_use]
+    pub fn black_hat(element: StructuringElement) -> Self {
+        Self::new(MorphologyOperation::BlackHat, element)
+    }
+}
+
+/// Resolves the pixel at the given coordinates through the edge mode, where `get(x, y)` looks up a
+/// pixel within an image of the given dimensions. Returns `None` if the pixel should be skipped.
+#[inline]
+fn neighbor<const N: usize, P, T>(
+    edge_mode: &EdgeMode<P>,
+    (width, height): (u32, u32),
+    x: i64,
+    y: i64,
+    get: impl FnOnce(u32, u32) -> Vector<N, T>,
+) -> Option<Vector<N, T>>
+where
+    P: Pixel + IntoVector<N, Element = T>,
+{
+    match (edge_mode.resolve(x, width), edge_mode.resolve(y, height)) {
+        (Some(x), Some(y)) => Some(get(x, y)),
+        _ => match edge_mode {
+            EdgeMode::Constant(pixel) => Some(pixel.into_vector()),
+            _ => None,
+        },
+    }
+}
+
+/// Takes the element-wise minimum, or maximum if `dilate` is true, of the neighborhood given by
+/// the offsets, where `tap(dx, dy)` looks up a neighbor and returns `None` if it should be
+/// skipped. Dilation reflects the structuring element.
+#[inline]
+fn extremum<const N: usize, T>(
+    offsets: &[(i64, i64)],
+    dilate: bool,
+    mut tap: impl FnMut(i64, i64) -> Option<Vector<N, T>>,
+) -> Option<Vector<N, T>>
+where
+    T: Copy + Zero + PartialOrd,
+{
+    offsets
+        .iter()
+        .filter_map(|&(dx, dy)| if dilate { tap(-dx, -dy) } else { tap(dx, dy) })
+        .reduce(|acc, value| acc.merge(value, |a, b| if (b > a) == dilate { b } else { a }))
+}
+
+/// Computes the element-wise difference `a - b`, saturating at zero.
+fn difference<const N: usize, T>(a: Vector<N, T>, b: Vector<N, T>) -> Vector<N, T>
+where
+    T: Copy + Num + PartialOrd,
+{
+    a.merge(b, |a, b| if a > b { a - b } else { T::zero() })
+}
+
+impl<const N: usize, P> Morphology<N, P>
+where
+    P: Pixel + IntoVector<N> + FromVector<N, Element = <P as IntoVector<N>>::Element> + MaybeSync,
+    <P as IntoVector<N>>::Element: Copy + Num + PartialOrd + MaybeSend + MaybeSync,
+{
+    /// Erodes or dilates the single pixel at the given coordinates.
+    fn erode_or_dilate_pixel(
+        &self,
+        image: &Image<P>,
+        offsets: &[(i64, i64)],
+        (x, y): (u32, u32),
+        dilate: bool,
+    ) -> Channels<N, P> {
+        let (cx, cy) = (i64::from(x), i64::from(y));
+
+        extremum(offsets, dilate, |dx, dy| {
+            neighbor(
+                &self.edge_mode,
+                image.dimensions(),
+                cx + dx,
+                cy + dy,
+                |x, y| image.pixel(x, y).into_vector(),
+            )
+        })
+        .unwrap_or_else(|| image.pixel(x, y).into_vector())
+    }
+
+    /// Opens (`dilate` is false) or closes (`dilate` is true) the single pixel at the given
+    /// coordinates, performing the first operation on each pixel of the neighborhood.
+    fn open_or_close_pixel(
+        &self,
+        image: &Image<P>,
+        offsets: &[(i64, i64)],
+        (x, y): (u32, u32),
+        dilate: bool,
+    ) -> Channels<N, P> {
+        let (cx, cy) = (i64::from(x), i64::from(y));
+        let first = |x, y| self.erode_or_dilate_pixel(image, offsets, (x, y), dilate);
+
+        extremum(offsets, !dilate, |dx, dy| {
+            neighbor(&self.edge_mode, image.dimensions(), cx + dx, cy + dy, first)
+        })
+        .unwrap_or_else(|| first(x, y))
+    }
+
+    /// Erodes or dilates the given vectorized image with the given dimensions.
+    fn erode_or_dilate(
+        &self,
+        source: &[Channels<N, P>],
+        dimensions: (u32, u32),
+        dilate: bool,
+    ) -> Vec<Channels<N, P>> {
+        let pass = |source: &[Channels<N, P>], offsets: &[(i64, i64)]| {
+            let width = dimensions.0 as usize;
+            let mut output = source.to_vec();
+
+            for_each_row(&mut output, width, |y, row| {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let value = extremum(offsets, dilate, |dx, dy| {
+                        neighbor(
+                            &self.edge_mode,
+                            dimensions,
+                            x as i64 + dx,
+                            y as i64 + dy,
+                            |x, y| source[y as usize * width + x as usize],
+                        )
+                    });
+                    if let Some(value) = value {
+                        *pixel = value;
+                    }
+                }
+            });
+            output
+        };
+
+        match self.element.separate() {
+            Some((row, column)) => pass(&pass(source, &row), &column),
+            None => pass(source, &self.element.offsets()),
+        }
+    }
+}
+
+impl<const N: usize, P> Filter for Morphology<N, P>
+where
+    P: Pixel + IntoVector<N> + FromVector<N, Element = <P as IntoVector<N>>::Element> + MaybeSync,
+    <P as IntoVector<N>>::Element: Copy + Num + PartialOrd + MaybeSend + MaybeSync,
+{
+    type Input = P;
+    type Output = P;
+
+    fn apply_pixel(
+        &self,
+        image: &Image<Self::Input>,
+        x: u32,
+        y: u32,
+        pixel: Self::Input,
+    ) -> Self::Output {
+        let offsets = self.element.offsets();
+        let erode = || self.erode_or_dilate_pixel(image, &offsets, (x, y), false);
+        let dilate = || self.erode_or_dilate_pixel(image, &offsets, (x, y), true);
+        let open = || self.open_or_close_pixel(image, &offsets, (x, y), false);
+        let close = || self.open_or_close_pixel(image, &offsets, (x, y), true);
+
+        P::from_vector(match self.operation {
+            MorphologyOperation::Erode => erode(),
+            MorphologyOperation::Dilate => dilate(),
+            MorphologyOperation::Open => open(),
+            MorphologyOperation::Close => close(),
+            MorphologyOperation::Gradient => difference(dilate(), erode()),
+            MorphologyOperation::TopHat => difference(pixel.into_vector(), open()),
+            MorphologyOperation::BlackHat => difference(close(), pixel.into_vector()),
+        })
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        let dimensions = image.dimensions();
+        let source = image
+            .data
+            .iter()
+            .map(|pixel| pixel.into_vector())
+            .collect::<Vec<_>>();
+        let erode = |source: &[_]| self.erode_or_dilate(source, dimensions, false);
+        let dilate = |source: &[_]| self.erode_or_dilate(source, dimensions, true);
+
+        let output = match self.operation {
+            MorphologyOperation::Erode => erode(&source),
+            MorphologyOperation::Dilate => dilate(&source),
+            MorphologyOperation::Open => dilate(&erode(&source)),
+            MorphologyOperation::Close => erode(&dilate(&source)),
+            MorphologyOperation::Gradient => dilate(&source)
+                .into_iter()
+                .zip(erode(&source))
+                .map(|(a, b)| difference(a, b))
+                .collect(),
+            MorphologyOperation::TopHat => source
+                .iter()
+                .zip(dilate(&erode(&source)))
+                .map(|(&a, b)| difference(a, b))
+                .collect(),
+            MorphologyOperation::BlackHat => erode(&dilate(&source))
+                .into_iter()
+                .zip(&source)
+                .map(|(a, &b)| difference(a, b))
+                .collect(),
+        };
+        output.into_iter().map(P::from_vector).collect()
+    }
+}
+
+/// A histogram of the values of a single channel within a sliding window. Bins are grouped into
+/// coarse bins of 256 in order to speed up rank queries over 16-bit values.
+struct Histogram {
+    fine: Vec<u32>,
+    coarse: Vec<u32>,
+}
+
+impl Histogram {
+    fn new(bins: usize) -> Self {
+        Self {
+            fine: vec![0; bins],
+            coarse: vec![0; (bins - 1) / 256 + 1],
+        }
+    }
+
+    #[inline]
+    fn add(&mut self, bin: usize) {
+        self.fine[bin] += 1;
+        self.coarse[bin / 256] += 1;
+    }
+
+    #[inline]
+    fn remove(&mut self, bin: usize) {
+        self.fine[bin] -= 1;
+        self.coarse[bin / 256] -= 1;
+    }
+
+    /// Returns the bin of the `n`th smallest value, starting from zero.
+    fn nth(&self, mut n: u32) -> usize {
+        let mut start = 0;
+        for (i, &count) in self.coarse.iter().enumerate() {
+            if n < count {
+                start = i * 256;
+                break;
+            }
+            n -= count;
+        }
+
+        for (bin, &count) in self.fine[start..].iter().enumerate() {
+            if n < count {
+                return start + bin;
+            }
+            n -= count;
+        }
+        unreachable!("histogram has less values than requested")
+    }
+}
+
+/// A rank filter, which replaces each pixel with the value at the given percentile of its
+/// rectangular neighborhood. This includes the median, minimum, and maximum filters.
+///
+/// Each channel is processed independently. Median filters are effective at removing salt and
+/// pepper noise while preserving edges.
+///
+/// Filtering an entire image uses a sliding window histogram, which takes a constant number of
+/// operations per pixel with respect to the width of the window. Only pixels with unsigned
+/// integer subpixels are supported.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// Image::<Rgb>::open("sample.png")?
+///     .filtered(&RankFilter::<3, Rgb>::median(5, 5))
+///     .save_inferred("denoised.png")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RankFilter<const N: usize, P: Pixel> {
+    /// The width of the neighborhood.
+    pub width: u32,
+    /// The height of the neighborhood.
+    pub height: u32,
+    /// The percentile of the neighborhood to take, between `0.0` (the minimum) and `1.0` (the
+    /// maximum).
+    pub percentile: f64,
+    /// How pixels outside of the image are resolved. Defaults to [`EdgeMode::Skip`].
+    pub edge_mode: EdgeMode<P>,
+}
+
+impl<const N: usize, P: Pixel> RankFilter<N, P> {
+    /// Creates a new rank filter with the given neighborhood dimensions and percentile.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    /// * The percentile is not between `0.0` and `1.0`.
+    #[must_use]
+    pub fn new(width: u32, height: u32, percentile: f64) -> Self {
+        assert_ne!(width, 0, "width must be non-zero");
+        assert_ne!(height, 0, "height must be non-zero");
+        assert!(
+            (0.0..=1.0).contains(&percentile),
+            "percentile must be between 0.0 and 1.0"
+        );
+
+        Self {
+            width,
+            height,
+            percentile,
+            edge_mode: EdgeMode::Skip,
+        }
+    }
+
+    /// Sets how pixels outside of the image are resolved. See [`EdgeMode`] for more information.
+    #[must_use]
+    pub fn with_edge_mode(mut self, edge_mode: EdgeMode<P>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// Creates a new median filter with the given neighborhood dimensions.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    #[must_use]
+    pub fn median(width: u32, height: u32) -> Self {
+        Self::new(width, height, 0.5)
+    }
+
+    /// Creates a new minimum filter with the given neighborhood dimensions.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    #[must_use]
+    pub fn minimum(width: u32, height: u32) -> Self {
+        Self::new(width, height, 0.0)
+    }
+
+    /// Creates a new maximum filter with the given neighborhood dimensions.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    #[must_use]
+    pub fn maximum(width: u32, height: u32) -> Self {
+        Self::new(width, height, 1.0)
+    }
+
+    /// Returns the index of the value to take out of the given number of sorted values.
+    fn rank(&self, count: u32) -> u32 {
+        (f64::from(count - 1) * self.percentile).round() as u32
+    }
+}
+
+impl<const N: usize, P> RankFilter<N, P>
+where
+    P: Pixel
+        + IntoVector<N>
+        + FromVector<N, Element = <P as IntoVector<N>>::Element>
+        + MaybeSend
+        + MaybeSync,
+    <P as IntoVector<N>>::Element: PrimInt + Unsigned + AsPrimitive<usize> + MaybeSend + MaybeSync,
+    usize: AsPrimitive<<P as IntoVector<N>>::Element>,
+{
+    /// Adds the column of the window at the given coordinates to the histograms of each channel,
+    /// or removes it if `add` is false. Returns the number of pixels that were added or removed.
+    fn slide(
+        &self,
+        (data, dimensions): (&[P], (u32, u32)),
+        (x, top): (i64, i64),
+        histograms: &mut [Histogram],
+        add: bool,
+    ) -> u32 {
+        let mut count = 0;
+
+        for y in top..top + i64::from(self.height) {
+            let value = neighbor(&self.edge_mode, dimensions, x, y, |x, y| {
+                data[(y * dimensions.0 + x) as usize].into_vector()
+            });
+            if let Some(value) = value {
+                for (histogram, value) in histograms.iter_mut().zip(value.elements()) {
+                    if add {
+                        histogram.add(value.as_());
+                    } else {
+                        histogram.remove(value.as_());
+                    }
+                }
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+impl<const N: usize, P> Filter for RankFilter<N, P>
+where
+    P: Pixel
+        + IntoVector<N>
+        + FromVector<N, Element = <P as IntoVector<N>>::Element>
+        + MaybeSend
+        + MaybeSync,
+    <P as IntoVector<N>>::Element: PrimInt + Unsigned + AsPrimitive<usize> + MaybeSend + MaybeSync,
+    usize: AsPrimitive<<P as IntoVector<N>>::Element>,
+{
+    type Input = P;
+    type Output = P;
+
+    fn apply_pixel(
+        &self,
+        image: &Image<Self::Input>,
+        x: u32,
+        y: u32,
+        pixel: Self::Input,
+    ) -> Self::Output {
+        let left = i64::from(x) - i64::from(self.width / 2);
+        let top = i64::from(y) - i64::from(self.height / 2);
+        let mut values = (top..top + i64::from(self.height))
+            .flat_map(|y| (left..left + i64::from(self.width)).map(move |x| (x, y)))
+            .filter_map(|(x, y)| {
+                neighbor(&self.edge_mode, image.dimensions(), x, y, |x, y| {
+                    image.pixel(x, y).into_vector()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if values.is_empty() {
+            return pixel;
+        }
+        let rank = self.rank(values.len() as u32) as usize;
+        let mut output = pixel.into_vector();
+        for c in 0..N {
+            values.sort_unstable_by_key(|value| value[c]);
+            output[c] = values[rank][c];
+        }
+        P::from_vector(output)
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        let bins = <P as IntoVector<N>>::Element::max_value().as_() + 1;
+        let width = i64::from(self.width);
+        let source = (&*image.data, image.dimensions());
+        let mut output = image.data.clone();
+
+        for_each_row(&mut output, image.width() as usize, |y, row| {
+            let top = y as i64 - i64::from(self.height / 2);
+            let mut histograms = (0..N).map(|_| Histogram::new(bins)).collect::<Vec<_>>();
+            let mut count = 0;
+
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let left = x as i64 - i64::from(self.width / 2);
+                if x == 0 {
+                    for x in left..left + width {
+                        count += self.slide(source, (x, top), &mut histograms, true);
+                    }
+                } else {
+                    count -= self.slide(source, (left - 1, top), &mut histograms, false);
+                    count += self.slide(source, (left + width - 1, top), &mut histograms, true);
+                }
+
+                if count > 0 {
+                    let rank = self.rank(count);
+                    let mut value = pixel.into_vector();
+                    for (c, histogram) in histograms.iter().enumerate() {
+                        value[c] = histogram.nth(rank).as_();
+                    }
+                    *pixel = P::from_vector(value);
+                }
+            }
+        });
+        output
+    }
+}
+
+/// An edge-preserving smoothing filter, which averages each pixel with its neighbors weighted by
+/// both their distance to the pixel and their difference in color from the pixel.
+///
+/// Neighbors that differ greatly in color from the pixel, such as those across an edge, barely
+/// contribute to the average, which smooths out noise and texture while keeping edges sharp.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// Image::<Rgb>::open("sample.png")?
+///     .filtered(&BilateralFilter::<3, Rgb>::new(3.0, 30.0))
+///     .save_inferred("smoothed.png")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BilateralFilter<const N: usize, P: Pixel> {
+    /// The standard deviation of the Gaussian weighing neighbors by their distance, in pixels.
+    pub sigma_space: f64,
+    /// The standard deviation of the Gaussian weighing neighbors by their difference in color, in
+    /// the units of the subpixels (e.g. `0` to `255` for [`Rgb`][crate::Rgb]).
+    pub sigma_color: f64,
+    /// The radius of the neighborhood. Defaults to twice `sigma_space`, rounded up.
+    pub radius: u32,
+    /// How pixels outside of the image are resolved. Defaults to [`EdgeMode::Skip`].
+    pub edge_mode: EdgeMode<P>,
+}
+
+impl<const N: usize, P: Pixel> BilateralFilter<N, P> {
+    /// Creates a new bilateral filter with the given standard deviations in space and in color.
+    ///
+    /// # Panics
+    /// * Either standard deviation is not positive.
+    #[must_use]
+    pub fn new(sigma_space: f64, sigma_color: f64) -> Self {
+        assert!(sigma_space > 0.0, "sigma_space must be positive");
+        assert!(sigma_color > 0.0, "sigma_color must be positive");
+
+        Self {
+            sigma_space,
+            sigma_color,
+            radius: (sigma_space * 2.0).ceil() as u32,
+            edge_mode: EdgeMode::Skip,
+        }
+    }
+
+    /// Sets the radius of the neighborhood.
+    #[must_use]
+    pub const fn with_radius(mut self, radius: u32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets how pixels outside of the image are resolved. See [`EdgeMode`] for more information.
+    #[must_use]
+    pub fn with_edge_mode(mut self, edge_mode: EdgeMode<P>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// Returns the weights of each pixel of the neighborhood by their distance, in row-major
+    /// order.
+    fn spatial_weights(&self) -> Vec<f64> {
+        let radius = i64::from(self.radius);
+        let denominator = 2.0 * self.sigma_space * self.sigma_space;
+
+        (-radius..=radius)
+            .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+            .map(|(dx, dy)| (-((dx * dx + dy * dy) as f64) / denominator).exp())
+            .collect()
+    }
+}
+
+impl<const N: usize, P> BilateralFilter<N, P>
+where
+    P: Pixel
+        + IntoVector<N>
+        + FromVector<N, Element = <P as IntoVector<N>>::Element>
+        + MaybeSend
+        + MaybeSync,
+    <P as IntoVector<N>>::Element: Copy + Bounded + Zero + AsPrimitive<f64>,
+    f64: AsPrimitive<<P as IntoVector<N>>::Element>,
+{
+    /// Filters the pixel at the given coordinates of the given row-major pixels.
+    fn filter_pixel(
+        &self,
+        (data, dimensions): (&[P], (u32, u32)),
+        (x, y): (u32, u32),
+        spatial_weights: &[f64],
+    ) -> P {
+        let lookup = |x: u32, y: u32| {
+            data[(y * dimensions.0 + x) as usize]
+                .into_vector()
+                .map(AsPrimitive::<f64>::as_)
+        };
+        let center = lookup(x, y);
+        let radius = i64::from(self.radius);
+        let denominator = 2.0 * self.sigma_color * self.sigma_color;
+
+        let (mut sum, mut total) = (Vector::<N, f64>::zero(), 0.0);
+        let offsets = (-radius..=radius).flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)));
+        for ((dx, dy), spatial) in offsets.zip(spatial_weights) {
+            let (x, y) = (i64::from(x) + dx, i64::from(y) + dy);
+            let value = neighbor(&self.edge_mode, dimensions, x, y, |x, y| {
+                data[(y * dimensions.0 + x) as usize].into_vector()
+            });
+
+            if let Some(value) = value {
+                // Differences are accumulated rather than values, such that uniform regions are
+                // left exactly as they are
+                let difference = value.map(AsPrimitive::<f64>::as_) - center;
+                let weight = spatial * (-difference.norm() / denominator).exp();
+                sum += difference * weight;
+                total += weight;
+            }
+        }
+        saturate(center + sum * total.recip())
+    }
+}
+
+impl<const N: usize, P> Filter for BilateralFilter<N, P>
+where
+    P: Pixel
+        + IntoVector<N>
+        + FromVector<N, Element = <P as IntoVector<N>>::Element>
+        + MaybeSend
+        + MaybeSync,
+    <P as IntoVector<N>>::Element: Copy + Bounded + Zero + AsPrimitive<f64>,
+    f64: AsPrimitive<<P as IntoVector<N>>::Element>,
+{
+    type Input = P;
+    type Output = P;
+
+    fn apply_pixel(
+        &self,
+        image: &Image<Self::Input>,
+        x: u32,
+        y: u32,
+        _pixel: Self::Input,
+    ) -> Self::Output {
+        let source = (&*image.data, image.dimensions());
+        self.filter_pixel(source, (x, y), &self.spatial_weights())
+    }
+
+    fn apply_image(&self, image: &Image<Self::Input>) -> Vec<Self::Output> {
+        let source = (&*image.data, image.dimensions());
+        let spatial_weights = self.spatial_weights();
+        let mut output = image.data.clone();
+
+        for_each_row(&mut output, image.width() as usize, |y, row| {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = self.filter_pixel(source, (x as u32, y as u32), &spatial_weights);
+            }
+        });
+        output
+    }
 }
@@ -1,5 +1,8 @@
 use crate::{
-    error::{Error::InvalidExtension, Result},
+    error::{
+        Error::{self, InvalidExtension},
+        Result,
+    },
     FrameIterator, Image, Pixel,
 };
 use std::{
@@ -10,15 +13,26 @@ use std::{
     path::Path,
 };
 
+#[cfg(feature = "bmp")]
+use crate::encodings::bmp;
 #[cfg(feature = "gif")]
 use crate::encodings::gif;
 #[cfg(feature = "jpeg")]
 use crate::encodings::jpeg;
 #[cfg(feature = "png")]
 use crate::encodings::png;
+#[cfg(feature = "tiff")]
+use crate::encodings::tiff;
 #[cfg(feature = "webp")]
 use crate::encodings::webp;
-#[cfg(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp"))]
+#[cfg(any(
+    feature = "png",
+    feature = "gif",
+    feature = "jpeg",
+    feature = "bmp",
+    feature = "tiff",
+    feature = "webp"
+))]
 use crate::{Decoder, Encoder};
 
 /// Represents the underlying encoding format of an image.
@@ -63,6 +77,56 @@ impl ImageFormat {
         self == &Self::Unknown
     }
 
+    /// Returns whether a decoder implementation for this format is available, i.e. whether
+    /// [`run_decoder`][Self::run_decoder] can be used without returning
+    /// [`UnsupportedFormat`][Error::UnsupportedFormat].
+    ///
+    /// This depends on which cargo features are enabled. [`Unknown`][Self::Unknown] can never be
+    /// decoded.
+    #[must_use]
+    pub const fn can_decode(&self) -> bool {
+        match self {
+            #[cfg(feature = "png")]
+            Self::Png => true,
+            #[cfg(feature = "jpeg")]
+            Self::Jpeg => true,
+            #[cfg(feature = "gif")]
+            Self::Gif => true,
+            #[cfg(feature = "bmp")]
+            Self::Bmp => true,
+            #[cfg(feature = "tiff")]
+            Self::Tiff => true,
+            #[cfg(feature = "webp")]
+            Self::WebP => true,
+            _ => false,
+        }
+    }
+
+    /// Returns whether an encoder implementation for this format is available, i.e. whether
+    /// [`run_encoder`][Self::run_encoder] can be used without returning
+    /// [`UnsupportedFormat`][Error::UnsupportedFormat].
+    ///
+    /// This depends on which cargo features are enabled. [`Unknown`][Self::Unknown] can never be
+    /// encoded.
+    #[must_use]
+    pub const fn can_encode(&self) -> bool {
+        match self {
+            #[cfg(feature = "png")]
+            Self::Png => true,
+            #[cfg(feature = "jpeg")]
+            Self::Jpeg => true,
+            #[cfg(feature = "gif")]
+            Self::Gif => true,
+            #[cfg(feature = "bmp")]
+            Self::Bmp => true,
+            #[cfg(feature = "tiff")]
+            Self::Tiff => true,
+            #[cfg(feature = "webp")]
+            Self::WebP => true,
+            _ => false,
+        }
+    }
+
     /// Parses the given extension and returns the corresponding image format.
     ///
     /// If the extension is an unknown extension, Ok([`ImageFormat::Unknown`]) is returned.
@@ -85,7 +149,7 @@ impl ImageFormat {
                 "jpg" | "jpeg" => Self::Jpeg,
                 "gif" => Self::Gif,
                 "bmp" => Self::Bmp,
-                "tiff" => Self::Tiff,
+                "tif" | "tiff" => Self::Tiff,
                 "webp" => Self::WebP,
                 _ => Self::Unknown,
             },
@@ -150,12 +214,19 @@ impl ImageFormat {
     ///
     /// # Errors
     /// * An error occured while encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation is found for this image encoding.
+    /// * `UnsupportedFormat`: No encoder implementation is found for this image encoding. This is
+    ///   the case if the format is [`Unknown`][Self::Unknown] or if the cargo feature for the
+    ///   format is not enabled. See [`can_encode`][Self::can_encode].
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
-        allow(unused_variables, unreachable_code)
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "bmp",
+            feature = "tiff",
+            feature = "webp"
+        )),
+        allow(unused_variables)
     )]
     pub fn run_encoder<P: Pixel>(&self, image: &Image<P>, dest: impl Write) -> Result<()> {
         match self {
@@ -165,12 +236,13 @@ impl ImageFormat {
             Self::Jpeg => jpeg::JpegEncoder::encode_static(image, dest),
             #[cfg(feature = "gif")]
             Self::Gif => gif::GifEncoder::encode_static(image, dest),
+            #[cfg(feature = "bmp")]
+            Self::Bmp => bmp::BmpEncoder::encode_static(image, dest),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffEncoder::encode_static(image, dest),
             #[cfg(feature = "webp")]
             Self::WebP => webp::WebPStaticEncoder::encode_static(image, dest),
-            _ => panic!(
-                "No encoder implementation is found for this image format. \
-                 Did you forget to enable the feature?"
-            ),
+            _ => Err(Error::UnsupportedFormat(*self)),
         }
     }
 
@@ -179,12 +251,19 @@ impl ImageFormat {
     ///
     /// # Errors
     /// * An error occured while encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation is found for this image encoding.
+    /// * `UnsupportedFormat`: No encoder implementation is found for this image encoding. This is
+    ///   the case if the format is [`Unknown`][Self::Unknown] or if the cargo feature for the
+    ///   format is not enabled. See [`can_encode`][Self::can_encode].
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
-        allow(unused_variables, unreachable_code)
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "bmp",
+            feature = "tiff",
+            feature = "webp"
+        )),
+        allow(unused_variables)
     )]
     pub fn run_sequence_encoder<P: Pixel>(
         &self,
@@ -198,12 +277,13 @@ impl ImageFormat {
             Self::Jpeg => jpeg::JpegEncoder::encode_sequence(seq, dest),
             #[cfg(feature = "gif")]
             Self::Gif => gif::GifEncoder::encode_sequence(seq, dest),
+            #[cfg(feature = "bmp")]
+            Self::Bmp => bmp::BmpEncoder::encode_sequence(seq, dest),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffEncoder::encode_sequence(seq, dest),
             #[cfg(feature = "webp")]
             Self::WebP => webp::WebPMuxEncoder::encode_sequence(seq, dest),
-            _ => panic!(
-                "No encoder implementation is found for this image format. \
-                 Did you forget to enable the feature?"
-            ),
+            _ => Err(Error::UnsupportedFormat(*self)),
         }
     }
 
@@ -211,12 +291,19 @@ impl ImageFormat {
     ///
     /// # Errors
     /// * An error occured while decoding.
-    ///
-    /// # Panics
-    /// * No decoder implementation is found for this image encoding.
+    /// * `UnsupportedFormat`: No decoder implementation is found for this image encoding. This is
+    ///   the case if the format is [`Unknown`][Self::Unknown] or if the cargo feature for the
+    ///   format is not enabled. See [`can_decode`][Self::can_decode].
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
-        allow(unused_variables, unreachable_code)
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "bmp",
+            feature = "tiff",
+            feature = "webp"
+        )),
+        allow(unused_variables)
     )]
     #[allow(clippy::needless_pass_by_value)] // would require a major refactor
     pub fn run_decoder<P: Pixel>(&self, stream: impl Read) -> Result<Image<P>> {
@@ -227,12 +314,13 @@ impl ImageFormat {
             Self::Jpeg => jpeg::JpegDecoder::new().decode(stream),
             #[cfg(feature = "gif")]
             Self::Gif => gif::GifDecoder::new().decode(stream),
+            #[cfg(feature = "bmp")]
+            Self::Bmp => bmp::BmpDecoder::new().decode(stream),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffDecoder::new().decode(stream),
             #[cfg(feature = "webp")]
             Self::WebP => webp::WebPDecoder::default().decode(stream),
-            _ => panic!(
-                "No encoder implementation is found for this image format. \
-                 Did you forget to enable the feature?"
-            ),
+            _ => Err(Error::UnsupportedFormat(*self)),
         }
     }
 
@@ -240,11 +328,18 @@ impl ImageFormat {
     ///
     /// # Errors
     /// * An error occured while decoding.
-    ///
-    /// # Panics
-    /// * No decoder implementation is found for this image encoding.
+    /// * `UnsupportedFormat`: No decoder implementation is found for this image encoding. This is
+    ///   the case if the format is [`Unknown`][Self::Unknown] or if the cargo feature for the
+    ///   format is not enabled. See [`can_decode`][Self::can_decode].
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "bmp",
+            feature = "tiff",
+            feature = "webp"
+        )),
         allow(unused_variables, unreachable_code)
     )]
     #[allow(clippy::needless_pass_by_value)] // would require a major refactor
@@ -259,12 +354,13 @@ impl ImageFormat {
             Self::Jpeg => Box::new(jpeg::JpegDecoder::new().decode_sequence(stream)?),
             #[cfg(feature = "gif")]
             Self::Gif => Box::new(gif::GifDecoder::new().decode_sequence(stream)?),
+            #[cfg(feature = "bmp")]
+            Self::Bmp => Box::new(bmp::BmpDecoder::new().decode_sequence(stream)?),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => Box::new(tiff::TiffDecoder::new().decode_sequence(stream)?),
             #[cfg(feature = "webp")]
             Self::WebP => Box::new(webp::WebPDecoder::default().decode_sequence(stream)?),
-            _ => panic!(
-                "No encoder implementation is found for this image format. \
-                 Did you forget to enable the feature?"
-            ),
+            _ => return Err(Error::UnsupportedFormat(*self)),
         })
     }
 }
@@ -0,0 +1,755 @@
+//! Histograms of 8-bit channels, along with lookup tables for adjusting the tones of an image.
+//!
+//! See [`Image::histograms`] for computing the histograms of each channel of an image,
+//! [`Image::apply_lookup_table`] for applying a [`LookupTable`] to an image, and
+//! [`Image::threshold`] for binarizing an image.
+
+use crate::{Image, Luma};
+
+/// A histogram of the values of a single 8-bit channel, counting the occurrences of each value.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let image = Image::<Rgb>::open("sample.png")?;
+/// let [r, g, b] = image.histograms();
+///
+/// println!("mean red value: {:?}", r.mean());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Histogram {
+    bins: [u64; 256],
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Histogram {
+    /// Creates a new, empty histogram.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { bins: [0; 256] }
+    }
+
+    /// Creates a new histogram counting the given values.
+    #[must_use]
+    pub fn from_values(values: impl IntoIterator<Item = u8>) -> Self {
+        let mut histogram = Self::new();
+        for value in values {
+            histogram.add(value);
+        }
+        histogram
+    }
+
+    /// Creates a new histogram counting the values of the given band. See [`Banded`][crate::Banded]
+    /// for splitting an image into its bands.
+    #[must_use]
+    pub fn from_band(band: &Image<Luma>) -> Self {
+        Self::from_values(band.data.iter().map(Luma::value))
+    }
+
+    /// Counts an occurrence of the given value.
+    pub fn add(&mut self, value: u8) {
+        self.bins[value as usize] += 1;
+    }
+
+    /// Returns the number of occurrences of each value.
+    #[must_use]
+    pub const fn bins(&self) -> &[u64; 256] {
+        &self.bins
+    }
+
+    /// Returns the number of occurrences of the given value.
+    #[must_use]
+    pub const fn count(&self, value: u8) -> u64 {
+        self.bins[value as usize]
+    }
+
+    /// Returns the total number of values counted.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.bins.iter().sum()
+    }
+
+    /// Returns whether no values have been counted.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bins.iter().all(|&count| count == 0)
+    }
+
+    /// Returns the cumulative number of occurrences of each value, i.e. the number of values that
+    /// are less than or equal to each value.
+    #[must_use]
+    pub fn cumulative(&self) -> [u64; 256] {
+        let mut total = 0;
+        self.bins.map(|count| {
+            total += count;
+            total
+        })
+    }
+
+    /// Returns the smallest value counted, or `None` if the histogram is empty.
+    #[must_use]
+    pub fn min(&self) -> Option<u8> {
+        self.bins
+            .iter()
+            .position(|&count| count > 0)
+            .map(|v| v as u8)
+    }
+
+    /// Returns the largest value counted, or `None` if the histogram is empty.
+    #[must_use]
+    pub fn max(&self) -> Option<u8> {
+        self.bins
+            .iter()
+            .rposition(|&count| count > 0)
+            .map(|v| v as u8)
+    }
+
+    /// Returns the mean of the values counted, or `None` if the histogram is empty.
+    #[must_use]
+    pub fn mean(&self) -> Option<f64> {
+        let total = self.total();
+        let sum = (0..=255u8)
+            .zip(self.bins)
+            .map(|(value, count)| f64::from(value) * count as f64)
+            .sum::<f64>();
+
+        (total > 0).then(|| sum / total as f64)
+    }
+
+    /// Returns the value at the given percentile, between `0.0` (the minimum) and `1.0` (the
+    /// maximum), or `None` if the histogram is empty.
+    ///
+    /// # Panics
+    /// * The percentile is not between `0.0` and `1.0`.
+    #[must_use]
+    pub fn percentile(&self, percentile: f64) -> Option<u8> {
+        assert!(
+            (0.0..=1.0).contains(&percentile),
+            "percentile must be between 0.0 and 1.0"
+        );
+
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let rank = ((total - 1) as f64 * percentile).round() as u64;
+        self.cumulative()
+            .iter()
+            .position(|&count| count > rank)
+            .map(|v| v as u8)
+    }
+
+    /// Computes a threshold separating the values into two classes using Otsu's method, which
+    /// maximizes the variance between the classes. Values greater than the threshold belong to
+    /// the brighter class. Returns `None` if the histogram is empty.
+    ///
+    /// This works best for histograms with two distinct peaks, e.g. dark text on a bright page.
+    #[must_use]
+    pub fn otsu_threshold(&self) -> Option<u8> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let sum = (0..256)
+            .map(|v| v as f64 * self.bins[v] as f64)
+            .sum::<f64>();
+
+        let (mut weight, mut partial) = (0, 0.0);
+        let (mut best, mut first, mut last) = (0.0, 255, 255);
+        for value in 0..255 {
+            weight += self.bins[value];
+            partial += value as f64 * self.bins[value] as f64;
+            if weight == 0 || weight == total {
+                continue;
+            }
+
+            let (w0, w1) = (weight as f64, (total - weight) as f64);
+            let difference = partial / w0 - (sum - partial) / w1;
+            let variance = w0 * w1 * difference * difference;
+            if variance > best {
+                best = variance;
+                first = value;
+                last = value;
+            } else if value == last + 1 && self.bins[value] == 0 {
+                // Empty values do not change the variance, so center the threshold in the gap
+                last = value;
+            }
+        }
+        Some(((first + last) / 2) as u8)
+    }
+
+    /// Computes a threshold using the triangle method, which draws a line from the peak of the
+    /// histogram to the far end of its longest tail and picks the value furthest below that line.
+    /// Values greater than the threshold belong to the brighter class. Returns `None` if the
+    /// histogram is empty.
+    ///
+    /// This works best for histograms with a single dominant peak, e.g. a few features on a
+    /// uniform background.
+    #[must_use]
+    pub fn triangle_threshold(&self) -> Option<u8> {
+        let (min, max) = (self.min()? as i32, self.max()? as i32);
+        let peak = (min..=max)
+            .max_by_key(|&v| (self.bins[v as usize], -v))
+            .unwrap_or(min);
+        // The far end lies just beyond the last value of the tail, where the count is zero
+        let end = if peak - min > max - peak {
+            min - 1
+        } else {
+            max + 1
+        };
+
+        let height = self.bins[peak as usize] as f64;
+        let span = f64::from(end - peak);
+        let (lower, upper) = if end < peak {
+            (end + 1, peak)
+        } else {
+            (peak, end)
+        };
+        let threshold = (lower..upper)
+            .map(|v| {
+                let line = height * f64::from(end - v) / span;
+                (v, line - self.bins[v as usize] as f64)
+            })
+            .fold((peak, 0.0), |best, (v, distance)| {
+                if distance > best.1 {
+                    (v, distance)
+                } else {
+                    best
+                }
+            })
+            .0;
+
+        Some(threshold as u8)
+    }
+
+    /// Clips the count of each value to the given limit, redistributing the excess evenly among
+    /// all values.
+    pub(crate) fn clip(&mut self, limit: u64) {
+        let mut excess = 0;
+        for count in &mut self.bins {
+            if *count > limit {
+                excess += *count - limit;
+                *count = limit;
+            }
+        }
+
+        let (share, remainder) = (excess / 256, (excess % 256) as usize);
+        for count in &mut self.bins {
+            *count += share;
+        }
+        if let Some(step) = 256usize.checked_div(remainder) {
+            for i in (0..256).step_by(step).take(remainder) {
+                self.bins[i] += 1;
+            }
+        }
+    }
+}
+
+/// Adjusts the black point, white point, and gamma of the tones of an image.
+///
+/// Input values at or below the black point become the output black point, and input values at
+/// or above the white point become the output white point. Values in between are mapped along a
+/// gamma curve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Levels {
+    /// The input value which is mapped to the output black point. Defaults to `0`.
+    pub black: u8,
+    /// The input value which is mapped to the output white point. Defaults to `255`.
+    pub white: u8,
+    /// The gamma of the curve between the black and white points. Values greater than `1.0`
+    /// brighten the midtones, and values less than `1.0` darken them. Defaults to `1.0`.
+    pub gamma: f64,
+    /// The darkest output value. Defaults to `0`.
+    pub output_black: u8,
+    /// The brightest output value. Defaults to `255`.
+    pub output_white: u8,
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Self::new(0, 255)
+    }
+}
+
+impl Levels {
+    /// Creates new levels with the given input black and white points.
+    ///
+    /// # Panics
+    /// * The black point is not less than the white point.
+    #[must_use]
+    pub const fn new(black: u8, white: u8) -> Self {
+        assert!(
+            black < white,
+            "black point must be less than the white point"
+        );
+
+        Self {
+            black,
+            white,
+            gamma: 1.0,
+            output_black: 0,
+            output_white: 255,
+        }
+    }
+
+    /// Sets the gamma of the curve between the black and white points.
+    ///
+    /// # Panics
+    /// * The gamma is not positive.
+    #[must_use]
+    pub fn with_gamma(mut self, gamma: f64) -> Self {
+        assert!(gamma > 0.0, "gamma must be positive");
+        self.gamma = gamma;
+        self
+    }
+
+    /// Sets the output black and white points.
+    #[must_use]
+    pub const fn with_output(mut self, black: u8, white: u8) -> Self {
+        self.output_black = black;
+        self.output_white = white;
+        self
+    }
+}
+
+/// Options for contrast limited adaptive histogram equalization (CLAHE). See [`Image::clahe`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClaheOptions {
+    /// The number of tiles the image is divided into horizontally and vertically, each of which is
+    /// equalized separately. Defaults to `(8, 8)`.
+    pub tiles: (u32, u32),
+    /// The maximum count of each value in the histogram of a tile, relative to the average count.
+    /// Lower limits result in less contrast and less amplified noise, and a limit of
+    /// [`f64::INFINITY`] disables clipping entirely. Defaults to `2.0`.
+    pub clip_limit: f64,
+}
+
+impl Default for ClaheOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClaheOptions {
+    /// Creates new options with the default values.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tiles: (8, 8),
+            clip_limit: 2.0,
+        }
+    }
+
+    /// Sets the number of tiles the image is divided into horizontally and vertically.
+    ///
+    /// # Panics
+    /// * Either number of tiles is zero.
+    #[must_use]
+    pub const fn with_tiles(mut self, x: u32, y: u32) -> Self {
+        assert!(x > 0 && y > 0, "number of tiles must be non-zero");
+        self.tiles = (x, y);
+        self
+    }
+
+    /// Sets the clip limit, relative to the average count of each value.
+    ///
+    /// # Panics
+    /// * The clip limit is not positive.
+    #[must_use]
+    pub fn with_clip_limit(mut self, clip_limit: f64) -> Self {
+        assert!(clip_limit > 0.0, "clip limit must be positive");
+        self.clip_limit = clip_limit;
+        self
+    }
+}
+
+/// The method used to compute the threshold of [`Image::threshold`], which sets each pixel that
+/// is brighter than the threshold and unsets all others.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Threshold {
+    /// Use the given threshold for the entire image.
+    Fixed(u8),
+    /// Compute a global threshold with Otsu's method. See [`Histogram::otsu_threshold`].
+    Otsu,
+    /// Compute a global threshold with the triangle method. See
+    /// [`Histogram::triangle_threshold`].
+    Triangle,
+    /// Use the mean of the square neighborhood of each pixel, minus the given offset, as the
+    /// threshold of that pixel. This is robust against uneven lighting, e.g. in scanned documents.
+    AdaptiveMean {
+        /// The distance from the pixel to the edges of its neighborhood, i.e. a radius of `1`
+        /// results in a 3x3 neighborhood.
+        radius: u32,
+        /// The value subtracted from the mean. Positive offsets keep uniform regions set, which
+        /// suppresses noise in the background of dark features.
+        offset: f64,
+    },
+    /// Like [`Self::AdaptiveMean`], but weighs the neighborhood of each pixel with a Gaussian
+    /// function so that nearer pixels contribute more to the threshold.
+    AdaptiveGaussian {
+        /// The standard deviation of the Gaussian function. The neighborhood extends three
+        /// standard deviations from the pixel.
+        sigma: f64,
+        /// The value subtracted from the weighted mean.
+        offset: f64,
+    },
+}
+
+/// A table mapping each 8-bit value to another, which is an efficient way to apply any tone
+/// adjustment such as curves or levels. See [`Image::apply_lookup_table`].
+///
+/// # Example
+/// An S-shaped curve which increases contrast:
+///
+/// ```no_run
+/// # use ril::prelude::*;
+/// use ril::histogram::LookupTable;
+///
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::<Rgb>::open("sample.png")?;
+/// let curve = LookupTable::from_curve(&[(0, 0), (64, 48), (192, 208), (255, 255)]);
+/// image.apply_lookup_table(&curve);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LookupTable(
+    /// The output value of each input value.
+    pub [u8; 256],
+);
+
+impl Default for LookupTable {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl LookupTable {
+    /// Creates a new lookup table which maps each value to itself.
+    #[must_use]
+    pub fn identity() -> Self {
+        Self::from_fn(|value| value)
+    }
+
+    /// Creates a new lookup table which maps each value through the given function.
+    #[must_use]
+    pub fn from_fn(f: impl Fn(u8) -> u8) -> Self {
+        let mut table = [0; 256];
+        for (value, output) in (0..=255).zip(&mut table) {
+            *output = f(value);
+        }
+        Self(table)
+    }
+
+    /// Creates a new lookup table from a smooth curve passing through the given control points,
+    /// as `(input, output)` pairs. The curve is monotonic between control points, and is flat
+    /// before the first control point and after the last control point.
+    ///
+    /// If multiple control points have the same input, only the last one is used. With no control
+    /// points, this is the identity.
+    #[must_use]
+    pub fn from_curve(points: &[(u8, u8)]) -> Self {
+        let mut points = points
+            .iter()
+            .rev()
+            .map(|&(x, y)| (f64::from(x), f64::from(y)))
+            .collect::<Vec<_>>();
+        // Stable sorting keeps the last of each duplicate input first
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        points.dedup_by(|a, b| a.0 == b.0);
+
+        if points.is_empty() {
+            return Self::identity();
+        }
+        let tangents = monotone_tangents(&points);
+
+        Self::from_fn(|value| {
+            let x = f64::from(value);
+            let k = points.iter().rposition(|&(px, _)| px <= x).unwrap_or(0);
+            let (x0, y0) = points[k];
+            let (x1, y1) = match points.get(k + 1) {
+                Some(&point) if x > x0 => point,
+                _ => return y0 as u8,
+            };
+
+            // Cubic Hermite interpolation
+            let h = x1 - x0;
+            let t = (x - x0) / h;
+            let (t2, t3) = (t * t, t * t * t);
+            let y = (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                + (t3 - 2.0 * t2 + t) * h * tangents[k]
+                + (-2.0 * t3 + 3.0 * t2) * y1
+                + (t3 - t2) * h * tangents[k + 1];
+            y.round().clamp(0.0, 255.0) as u8
+        })
+    }
+
+    /// Creates a new lookup table from the given [`Levels`].
+    #[must_use]
+    pub fn from_levels(levels: Levels) -> Self {
+        let (black, white) = (f64::from(levels.black), f64::from(levels.white));
+        let (output_black, output_white) = (
+            f64::from(levels.output_black),
+            f64::from(levels.output_white),
+        );
+
+        Self::from_fn(|value| {
+            let t = ((f64::from(value) - black) / (white - black)).clamp(0.0, 1.0);
+            let t = t.powf(levels.gamma.recip());
+            (output_black + t * (output_white - output_black)).round() as u8
+        })
+    }
+
+    /// Creates a new lookup table which equalizes the given histogram, spreading its values out
+    /// such that they are distributed as evenly as possible over the entire range.
+    #[must_use]
+    pub fn equalize(histogram: &Histogram) -> Self {
+        let cumulative = histogram.cumulative();
+        let total = cumulative[255];
+        let min = histogram.min().map_or(0, |min| cumulative[min as usize]);
+        if total == min {
+            return Self::identity();
+        }
+
+        Self::from_fn(|value| {
+            let count = cumulative[value as usize].saturating_sub(min);
+            (count as f64 * 255.0 / (total - min) as f64).round() as u8
+        })
+    }
+
+    /// Creates a new lookup table which stretches the values of the given histogram over the
+    /// entire range. The given fraction of the darkest and brightest values are ignored when
+    /// determining the range of the histogram, and are clipped.
+    ///
+    /// # Panics
+    /// * The cutoff is not between `0.0` and `0.5`.
+    #[must_use]
+    pub fn auto_contrast(histogram: &Histogram, cutoff: f64) -> Self {
+        assert!(
+            (0.0..=0.5).contains(&cutoff),
+            "cutoff must be between 0.0 and 0.5"
+        );
+
+        match (
+            histogram.percentile(cutoff),
+            histogram.percentile(1.0 - cutoff),
+        ) {
+            (Some(low), Some(high)) if low < high => {
+                let (low, high) = (f64::from(low), f64::from(high));
+                Self::from_fn(|value| {
+                    let t = (f64::from(value) - low) / (high - low);
+                    (t * 255.0).round().clamp(0.0, 255.0) as u8
+                })
+            }
+            _ => Self::identity(),
+        }
+    }
+
+    /// Returns the output value of the given input value.
+    #[must_use]
+    pub const fn get(&self, value: u8) -> u8 {
+        self.0[value as usize]
+    }
+
+    /// Returns a lookup table which applies this table followed by the given table.
+    #[must_use]
+    pub fn then(&self, other: &Self) -> Self {
+        Self::from_fn(|value| other.get(self.get(value)))
+    }
+}
+
+/// Computes the tangents of a monotone cubic curve through the given points, which are sorted by
+/// their distinct x-coordinates, using the Fritsch-Carlson method.
+fn monotone_tangents(points: &[(f64, f64)]) -> Vec<f64> {
+    let secants = points
+        .windows(2)
+        .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+        .collect::<Vec<_>>();
+    if secants.is_empty() {
+        return vec![0.0];
+    }
+
+    let mut tangents = Vec::with_capacity(points.len());
+    tangents.push(secants[0]);
+    for w in secants.windows(2) {
+        tangents.push(if w[0] * w[1] <= 0.0 {
+            0.0
+        } else {
+            (w[0] + w[1]) / 2.0
+        });
+    }
+    tangents.push(secants[secants.len() - 1]);
+
+    for (k, &secant) in secants.iter().enumerate() {
+        if secant == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+
+        let (a, b) = (tangents[k] / secant, tangents[k + 1] / secant);
+        let magnitude = a * a + b * b;
+        if magnitude > 9.0 {
+            let scale = 3.0 / magnitude.sqrt();
+            tangents[k] = scale * a * secant;
+            tangents[k + 1] = scale * b * secant;
+        }
+    }
+    tangents
+}
+
+/// Performs contrast limited adaptive histogram equalization on the given channel with the given
+/// dimensions.
+pub(crate) fn clahe(channel: &[u8], (width, height): (u32, u32), options: ClaheOptions) -> Vec<u8> {
+    let tiles_x = options.tiles.0.min(width) as usize;
+    let tiles_y = options.tiles.1.min(height) as usize;
+    let (width, height) = (width as usize, height as usize);
+
+    // Equalize each tile separately
+    let tables = (0..tiles_y)
+        .flat_map(|ty| (0..tiles_x).map(move |tx| (tx, ty)))
+        .map(|(tx, ty)| {
+            let (x0, x1) = (tx * width / tiles_x, (tx + 1) * width / tiles_x);
+            let (y0, y1) = (ty * height / tiles_y, (ty + 1) * height / tiles_y);
+
+            let mut histogram = Histogram::from_values(
+                (y0..y1).flat_map(|y| channel[y * width + x0..y * width + x1].iter().copied()),
+            );
+            let limit = options.clip_limit * histogram.total() as f64 / 256.0;
+            if limit.is_finite() {
+                histogram.clip((limit as u64).max(1));
+            }
+            LookupTable::equalize(&histogram)
+        })
+        .collect::<Vec<_>>();
+
+    // Interpolate between the tables of the four nearest tiles
+    let (tile_width, tile_height) = (
+        width as f64 / tiles_x as f64,
+        height as f64 / tiles_y as f64,
+    );
+    let neighbors = |position: f64, size: f64, tiles: usize| {
+        let position = ((position + 0.5) / size - 0.5).max(0.0);
+        let first = (position.floor() as usize).min(tiles - 1);
+        let second = (first + 1).min(tiles - 1);
+        (first, second, (position - first as f64).min(1.0))
+    };
+
+    channel
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            let (x0, x1, tx) = neighbors((i % width) as f64, tile_width, tiles_x);
+            let (y0, y1, ty) = neighbors((i / width) as f64, tile_height, tiles_y);
+            let lookup = |x: usize, y: usize| f64::from(tables[y * tiles_x + x].get(value));
+
+            let top = lookup(x0, y0) * (1.0 - tx) + lookup(x1, y0) * tx;
+            let bottom = lookup(x0, y1) * (1.0 - tx) + lookup(x1, y1) * tx;
+            (top * (1.0 - ty) + bottom * ty).round() as u8
+        })
+        .collect()
+}
+
+/// Computes the local mean of each value of the given channel, ignoring values outside of the
+/// channel.
+fn local_means(channel: &[u8], (width, height): (usize, usize), method: Threshold) -> Vec<f64> {
+    if let Threshold::AdaptiveGaussian { sigma, .. } = method {
+        let radius = (sigma * 3.0).ceil() as usize;
+        let weights = (0..=radius)
+            .map(|d| (-((d * d) as f64) / (2.0 * sigma * sigma)).exp())
+            .collect::<Vec<_>>();
+        // The covered part of the neighborhood is a rectangle, so each pass can be normalized
+        // separately
+        let blur = |length: usize, get: &dyn Fn(usize) -> f64| {
+            (0..length)
+                .map(|i| {
+                    let (start, end) = (i.saturating_sub(radius), (i + radius + 1).min(length));
+                    let (sum, total) = (start..end).fold((0.0, 0.0), |(sum, total), j| {
+                        let weight = weights[i.abs_diff(j)];
+                        (sum + weight * get(j), total + weight)
+                    });
+                    sum / total
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let rows = (0..height)
+            .flat_map(|y| blur(width, &|x| f64::from(channel[y * width + x])))
+            .collect::<Vec<_>>();
+        let columns = (0..width)
+            .map(|x| blur(height, &|y| rows[y * width + x]))
+            .collect::<Vec<_>>();
+        return (0..width * height)
+            .map(|i| columns[i % width][i / width])
+            .collect();
+    }
+
+    let radius = match method {
+        Threshold::AdaptiveMean { radius, .. } => radius as usize,
+        _ => 0,
+    };
+    // Summed-area table, where each entry is the sum of all values above and to the left of it
+    let stride = width + 1;
+    let mut sums = vec![0_u64; stride * (height + 1)];
+    for y in 0..height {
+        let mut row = 0;
+        for x in 0..width {
+            row += u64::from(channel[y * width + x]);
+            sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row;
+        }
+    }
+
+    (0..width * height)
+        .map(|i| {
+            let (x, y) = (i % width, i / width);
+            let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+            let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+            let sum = sums[y1 * stride + x1] + sums[y0 * stride + x0]
+                - sums[y0 * stride + x1]
+                - sums[y1 * stride + x0];
+            sum as f64 / ((x1 - x0) * (y1 - y0)) as f64
+        })
+        .collect()
+}
+
+/// Thresholds the given channel with the given dimensions, returning whether each value is
+/// greater than its threshold.
+pub(crate) fn threshold(
+    channel: &[u8],
+    (width, height): (u32, u32),
+    method: Threshold,
+) -> Vec<bool> {
+    let global = |threshold: Option<u8>| {
+        // Empty channels have no values to threshold
+        let threshold = threshold.unwrap_or(u8::MAX);
+        channel.iter().map(|&value| value > threshold).collect()
+    };
+
+    match method {
+        Threshold::Fixed(threshold) => global(Some(threshold)),
+        Threshold::Otsu => global(Histogram::from_values(channel.iter().copied()).otsu_threshold()),
+        Threshold::Triangle => {
+            global(Histogram::from_values(channel.iter().copied()).triangle_threshold())
+        }
+        Threshold::AdaptiveMean { offset, .. } | Threshold::AdaptiveGaussian { offset, .. } => {
+            if let Threshold::AdaptiveGaussian { sigma, .. } = method {
+                assert!(sigma > 0.0, "sigma must be positive");
+            }
+
+            let means = local_means(channel, (width as usize, height as usize), method);
+            channel
+                .iter()
+                .zip(means)
+                .map(|(&value, mean)| f64::from(value) > mean - offset)
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,776 @@
+//! Color management through ICC color profiles.
+//!
+//! Pixel data on its own does not say which colors it represents; an RGB triplet of
+//! `(255, 0, 0)` is a different shade of red depending on whether it is meant to be interpreted
+//! as sRGB, Display P3, or Adobe RGB. Images can embed an ICC profile describing the color space
+//! their pixel data is in, which decoders preserve in [`Metadata::icc_profile`].
+//!
+//! This module can parse matrix/TRC based ICC v2 and v4 profiles, which covers the vast majority
+//! of profiles embedded in RGB and grayscale images, and convert colors between them. Profiles
+//! based on lookup tables (such as most CMYK profiles) are not supported.
+//!
+//! # Example
+//! Converting an image into sRGB from whatever color profile it has embedded:
+//!
+//! ```no_run
+//! use ril::prelude::*;
+//!
+//! # fn main() -> ril::Result<()> {
+//! let mut image = Image::<Rgb>::open("photo.jpg")?;
+//! image.convert_to_profile(&ColorProfile::srgb())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Metadata::icc_profile`]: crate::Metadata::icc_profile
+
+use crate::{Error, Pixel, Result, Rgb, Rgb16, Rgba, Rgba16};
+
+type Matrix = [[f32; 3]; 3];
+
+/// The D50 white point used as the illuminant of the ICC profile connection space.
+const D50: [f32; 3] = [0.9642, 1.0, 0.8249];
+
+/// The Bradford chromatic adaptation matrix.
+const BRADFORD: Matrix = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+/// The xy chromaticity coordinates of the D65 white point.
+const D65_XY: (f32, f32) = (0.3127, 0.3290);
+
+/// A tone reproduction curve, or transfer function, which maps encoded color components into
+/// linear light.
+///
+/// All components are normalized to the range `0.0..=1.0`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ToneCurve {
+    /// The identity function, used by color spaces which are already linear.
+    Linear,
+    /// A pure power function, where `linear = encoded ^ gamma`.
+    Gamma(f32),
+    /// An ICC parametric curve with the parameters `[g, a, b, c, d, e, f]`, where:
+    ///
+    /// * `linear = (a * encoded + b) ^ g + e` when `encoded >= d`
+    /// * `linear = c * encoded + f` otherwise
+    ///
+    /// This is the most general form of the ICC parametric curve, all other forms can be
+    /// expressed with it.
+    Parametric([f32; 7]),
+    /// A lookup table of evenly spaced samples of the curve, which are linearly interpolated.
+    Table(Vec<f32>),
+}
+
+impl ToneCurve {
+    /// The piecewise sRGB transfer function, as used by sRGB and Display P3.
+    pub const SRGB: Self = Self::Parametric([
+        2.4,
+        1.0 / 1.055,
+        0.055 / 1.055,
+        1.0 / 12.92,
+        0.04045,
+        0.0,
+        0.0,
+    ]);
+
+    /// Converts the given encoded component into linear light.
+    #[must_use]
+    pub fn to_linear(&self, value: f32) -> f32 {
+        let value = value.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => value,
+            Self::Gamma(gamma) => value.powf(*gamma),
+            Self::Parametric([g, a, b, c, d, e, f]) => {
+                if value >= *d {
+                    (a * value + b).max(0.0).powf(*g) + e
+                } else {
+                    c * value + f
+                }
+            }
+            Self::Table(table) => match table.len() {
+                0 => value,
+                1 => table[0],
+                len => {
+                    let position = value * (len - 1) as f32;
+                    let index = (position as usize).min(len - 2);
+                    let t = position - index as f32;
+
+                    table[index] + (table[index + 1] - table[index]) * t
+                }
+            },
+        }
+    }
+
+    /// Converts the given component in linear light into its encoded form. This is the inverse of
+    /// [`to_linear`][Self::to_linear].
+    #[must_use]
+    pub fn from_linear(&self, value: f32) -> f32 {
+        let value = value.clamp(0.0, 1.0);
+
+        let encoded = match self {
+            Self::Linear => value,
+            Self::Gamma(gamma) => value.powf(gamma.recip()),
+            Self::Parametric([g, a, b, c, d, e, f]) => {
+                if value >= (a * d + b).max(0.0).powf(*g) + e {
+                    ((value - e).max(0.0).powf(g.recip()) - b) / a
+                } else if *c == 0.0 {
+                    *d
+                } else {
+                    (value - f) / c
+                }
+            }
+            Self::Table(table) => match table.len() {
+                0 => value,
+                1 => table[0],
+                len => {
+                    // Tables are monotonically increasing (or decreasing) in practice, so they
+                    // can be inverted with a binary search.
+                    let increasing = table[len - 1] >= table[0];
+                    let index = table
+                        .partition_point(|&sample| (sample < value) == increasing)
+                        .clamp(1, len - 1);
+                    let (lo, hi) = (table[index - 1], table[index]);
+                    let t = if hi == lo {
+                        0.0
+                    } else {
+                        (value - lo) / (hi - lo)
+                    };
+
+                    (index as f32 - 1.0 + t) / (len - 1) as f32
+                }
+            },
+        };
+
+        encoded.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ProfileKind {
+    Rgb {
+        matrix: Matrix,
+        curves: [ToneCurve; 3],
+    },
+    Gray {
+        curve: ToneCurve,
+    },
+}
+
+/// A matrix/TRC based color profile, describing how to map the pixel data of an image into the
+/// D50 XYZ profile connection space.
+///
+/// Profiles can be parsed from ICC profiles with [`from_icc`][Self::from_icc], or built from one
+/// of the common color spaces such as [`srgb`][Self::srgb] or [`display_p3`][Self::display_p3].
+///
+/// # See Also
+/// * [`ColorTransform`] for converting colors between two profiles.
+/// * [`Image::convert_color_profile`][crate::Image::convert_color_profile] and
+///   [`Image::convert_to_profile`][crate::Image::convert_to_profile] for converting images.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorProfile {
+    description: String,
+    kind: ProfileKind,
+}
+
+impl ColorProfile {
+    /// Creates an RGB color profile from the xy chromaticity coordinates of its red, green, and
+    /// blue primaries and its white point, along with the tone curve shared by all three channels.
+    #[must_use]
+    pub fn from_primaries(
+        description: impl Into<String>,
+        red: (f32, f32),
+        green: (f32, f32),
+        blue: (f32, f32),
+        white: (f32, f32),
+        curve: ToneCurve,
+    ) -> Self {
+        let xyz = |(x, y): (f32, f32)| [x / y, 1.0, (1.0 - x - y) / y];
+        let primaries = transpose([xyz(red), xyz(green), xyz(blue)]);
+        let white = xyz(white);
+
+        // Scale each primary such that (1, 1, 1) maps to the white point
+        let scale = mul_vector(&invert(&primaries), white);
+        let matrix = primaries.map(|row| [row[0] * scale[0], row[1] * scale[1], row[2] * scale[2]]);
+
+        Self {
+            description: description.into(),
+            kind: ProfileKind::Rgb {
+                matrix: mul(&adaptation_to_d50(white), &matrix),
+                curves: [curve.clone(), curve.clone(), curve],
+            },
+        }
+    }
+
+    /// Creates a grayscale color profile with the given tone curve.
+    #[must_use]
+    pub fn from_gray_curve(description: impl Into<String>, curve: ToneCurve) -> Self {
+        Self {
+            description: description.into(),
+            kind: ProfileKind::Gray { curve },
+        }
+    }
+
+    /// The sRGB color space, which is what most images are in. Images without an embedded color
+    /// profile are usually assumed to be sRGB.
+    #[must_use]
+    pub fn srgb() -> Self {
+        Self::from_primaries(
+            "sRGB",
+            (0.64, 0.33),
+            (0.30, 0.60),
+            (0.15, 0.06),
+            D65_XY,
+            ToneCurve::SRGB,
+        )
+    }
+
+    /// The sRGB color space without its transfer function, i.e. sRGB in linear light.
+    #[must_use]
+    pub fn linear_srgb() -> Self {
+        Self::from_primaries(
+            "Linear sRGB",
+            (0.64, 0.33),
+            (0.30, 0.60),
+            (0.15, 0.06),
+            D65_XY,
+            ToneCurve::Linear,
+        )
+    }
+
+    /// The Display P3 color space, which has a wider gamut than sRGB. This is used by many modern
+    /// displays and is embedded in photos taken by iPhones.
+    #[must_use]
+    pub fn display_p3() -> Self {
+        Self::from_primaries(
+            "Display P3",
+            (0.680, 0.320),
+            (0.265, 0.690),
+            (0.150, 0.060),
+            D65_XY,
+            ToneCurve::SRGB,
+        )
+    }
+
+    /// The Adobe RGB (1998) color space, which has a wider gamut than sRGB, mostly in greens and
+    /// cyans. This is common in photos taken by professional cameras.
+    #[must_use]
+    pub fn adobe_rgb() -> Self {
+        Self::from_primaries(
+            "Adobe RGB (1998)",
+            (0.64, 0.33),
+            (0.21, 0.71),
+            (0.15, 0.06),
+            D65_XY,
+            ToneCurve::Gamma(563.0 / 256.0),
+        )
+    }
+
+    /// A grayscale color space using the sRGB transfer function.
+    #[must_use]
+    pub fn gray() -> Self {
+        Self::from_gray_curve("Gray", ToneCurve::SRGB)
+    }
+
+    /// Returns the human-readable description of this profile. This may be empty if the profile
+    /// was parsed from an ICC profile without a description.
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns whether this is a grayscale color profile.
+    #[must_use]
+    pub const fn is_gray(&self) -> bool {
+        matches!(self.kind, ProfileKind::Gray { .. })
+    }
+
+    /// Returns an RGB profile describing the same colors as this profile, such that it can be
+    /// embedded into images of RGB pixels. Grayscale profiles are mapped to an RGB profile using
+    /// the same tone curve, where equal components have the luminance of the gray value.
+    pub(crate) fn to_rgb(&self) -> Self {
+        match &self.kind {
+            ProfileKind::Rgb { .. } => self.clone(),
+            ProfileKind::Gray { curve } => Self::from_primaries(
+                self.description.clone(),
+                (0.64, 0.33),
+                (0.30, 0.60),
+                (0.15, 0.06),
+                D65_XY,
+                curve.clone(),
+            ),
+        }
+    }
+
+    /// Returns the matrix which maps linear RGB components into the D50 XYZ profile connection
+    /// space. For grayscale profiles, only the first column is populated.
+    fn matrix(&self) -> Matrix {
+        match &self.kind {
+            ProfileKind::Rgb { matrix, .. } => *matrix,
+            ProfileKind::Gray { .. } => {
+                [[D50[0], 0.0, 0.0], [D50[1], 0.0, 0.0], [D50[2], 0.0, 0.0]]
+            }
+        }
+    }
+
+    fn curves(&self) -> [ToneCurve; 3] {
+        match &self.kind {
+            ProfileKind::Rgb { curves, .. } => curves.clone(),
+            ProfileKind::Gray { curve } => [curve.clone(), curve.clone(), curve.clone()],
+        }
+    }
+
+    /// Parses a matrix/TRC based ICC v2 or v4 profile from its raw bytes, such as those in
+    /// [`Metadata::icc_profile`][crate::Metadata::icc_profile].
+    ///
+    /// # Errors
+    /// * `InvalidIccProfile`: The profile is malformed, or it is not a matrix/TRC based RGB or
+    ///   grayscale profile.
+    pub fn from_icc(data: &[u8]) -> Result<Self> {
+        let reader = IccReader { data };
+
+        if data.len() < 132 || &data[36..40] != b"acsp" {
+            return Err(invalid("not an ICC profile"));
+        }
+        if &data[20..24] != b"XYZ " {
+            return Err(invalid(
+                "only profiles with an XYZ connection space are supported",
+            ));
+        }
+
+        let description = reader
+            .tag(*b"desc")
+            .and_then(|tag| reader.description(tag))
+            .unwrap_or_default();
+
+        let kind = match &data[16..20] {
+            b"RGB " => {
+                let mut matrix = [[0.0; 3]; 3];
+                for (column, signature) in [*b"rXYZ", *b"gXYZ", *b"bXYZ"].into_iter().enumerate() {
+                    let xyz = reader.xyz(reader.required_tag(signature)?)?;
+                    for (row, value) in xyz.into_iter().enumerate() {
+                        matrix[row][column] = value;
+                    }
+                }
+                // The matrix is inverted when converting into this profile
+                if !determinant(&matrix).is_normal() {
+                    return Err(invalid("colorant tags do not form an invertible matrix"));
+                }
+
+                ProfileKind::Rgb {
+                    matrix,
+                    curves: [
+                        reader.curve(reader.required_tag(*b"rTRC")?)?,
+                        reader.curve(reader.required_tag(*b"gTRC")?)?,
+                        reader.curve(reader.required_tag(*b"bTRC")?)?,
+                    ],
+                }
+            }
+            b"GRAY" => ProfileKind::Gray {
+                curve: reader.curve(reader.required_tag(*b"kTRC")?)?,
+            },
+            _ => return Err(invalid("only RGB and grayscale profiles are supported")),
+        };
+
+        Ok(Self { description, kind })
+    }
+
+    /// Encodes this profile as an ICC v4 profile, which can be embedded into images through
+    /// [`Metadata::icc_profile`][crate::Metadata::icc_profile].
+    #[must_use]
+    pub fn to_icc(&self) -> Vec<u8> {
+        let mut tags = vec![
+            (*b"desc", encode_mluc(&self.description)),
+            (*b"cprt", encode_mluc("No copyright, use freely")),
+            (*b"wtpt", encode_xyz(D50)),
+        ];
+        let color_space = match &self.kind {
+            ProfileKind::Rgb { matrix, curves } => {
+                let matrix = transpose(*matrix);
+                for (signature, column) in [*b"rXYZ", *b"gXYZ", *b"bXYZ"].into_iter().zip(matrix) {
+                    tags.push((signature, encode_xyz(column)));
+                }
+                for (signature, curve) in [*b"rTRC", *b"gTRC", *b"bTRC"].into_iter().zip(curves) {
+                    tags.push((signature, encode_curve(curve)));
+                }
+
+                b"RGB "
+            }
+            ProfileKind::Gray { curve } => {
+                tags.push((*b"kTRC", encode_curve(curve)));
+                b"GRAY"
+            }
+        };
+
+        let mut header = [0_u8; 128];
+        header[8..12].copy_from_slice(&[4, 0x30, 0, 0]);
+        header[12..16].copy_from_slice(b"mntr");
+        header[16..20].copy_from_slice(color_space);
+        header[20..24].copy_from_slice(b"XYZ ");
+        header[36..40].copy_from_slice(b"acsp");
+        header[68..80].copy_from_slice(&encode_xyz(D50)[8..]);
+
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut data = Vec::new();
+        let mut offset = 128 + 4 + tags.len() * 12;
+
+        for (signature, tag) in tags {
+            table.extend_from_slice(&signature);
+            table.extend_from_slice(&(offset as u32).to_be_bytes());
+            table.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+
+            let padding = (4 - tag.len() % 4) % 4;
+            offset += tag.len() + padding;
+            data.extend(tag);
+            data.resize(data.len() + padding, 0);
+        }
+
+        header[0..4].copy_from_slice(&(offset as u32).to_be_bytes());
+
+        let mut profile = header.to_vec();
+        profile.extend(table);
+        profile.extend(data);
+        profile
+    }
+}
+
+impl Default for ColorProfile {
+    fn default() -> Self {
+        Self::srgb()
+    }
+}
+
+/// A precomputed conversion of colors from one [`ColorProfile`] into another.
+///
+/// Colors that fall outside of the gamut of the target profile are clipped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorTransform {
+    matrix: Matrix,
+    input: [ToneCurve; 3],
+    output: [ToneCurve; 3],
+}
+
+impl ColorTransform {
+    /// Creates a new transform which converts colors from the `source` profile into the `target`
+    /// profile.
+    #[must_use]
+    pub fn new(source: &ColorProfile, target: &ColorProfile) -> Self {
+        let source_matrix = source.matrix();
+        let matrix = if target.is_gray() {
+            // Only the luminance (Y) of the connection space is kept for grayscale targets
+            [source_matrix[1]; 3]
+        } else {
+            mul(&invert(&target.matrix()), &source_matrix)
+        };
+
+        Self {
+            matrix,
+            input: source.curves(),
+            output: target.curves(),
+        }
+    }
+
+    /// Converts the given normalized color components.
+    #[must_use]
+    pub fn apply(&self, components: [f32; 3]) -> [f32; 3] {
+        let linear = [
+            self.input[0].to_linear(components[0]),
+            self.input[1].to_linear(components[1]),
+            self.input[2].to_linear(components[2]),
+        ];
+        let [r, g, b] = mul_vector(&self.matrix, linear);
+
+        [
+            self.output[0].from_linear(r),
+            self.output[1].from_linear(g),
+            self.output[2].from_linear(b),
+        ]
+    }
+}
+
+/// A pixel type with color components that can be converted between color profiles.
+pub trait ColorManaged: Pixel {
+    /// Returns the red, green, and blue components of this pixel, normalized to `0.0..=1.0`.
+    fn color_components(&self) -> [f32; 3];
+
+    /// Returns this pixel with its red, green, and blue components replaced with the given
+    /// normalized components. The alpha channel, if any, is left untouched.
+    #[must_use]
+    fn with_color_components(self, components: [f32; 3]) -> Self;
+}
+
+macro_rules! impl_color_managed {
+    ($($t:ty: $max:literal $(=> $a:ident)?),+) => {
+        $(
+            impl ColorManaged for $t {
+                fn color_components(&self) -> [f32; 3] {
+                    [
+                        f32::from(self.r) / $max,
+                        f32::from(self.g) / $max,
+                        f32::from(self.b) / $max,
+                    ]
+                }
+
+                fn with_color_components(self, [r, g, b]: [f32; 3]) -> Self {
+                    Self {
+                        r: (r * $max).round() as _,
+                        g: (g * $max).round() as _,
+                        b: (b * $max).round() as _,
+                        $($a: self.$a,)?
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_color_managed!(Rgb: 255.0, Rgba: 255.0 => a, Rgb16: 65535.0, Rgba16: 65535.0 => a);
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidIccProfile(message.to_string())
+}
+
+struct IccReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> IccReader<'a> {
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.data.get(offset..offset + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.data.get(offset..offset + 4)?;
+        Some(u32::from_be_bytes(bytes.try_into().ok()?))
+    }
+
+    fn s15_fixed16(&self, offset: usize) -> Option<f32> {
+        self.u32(offset).map(|n| n as i32 as f32 / 65536.0)
+    }
+
+    /// Returns the data of the tag with the given signature.
+    fn tag(&self, signature: [u8; 4]) -> Option<&'a [u8]> {
+        let count = self.u32(128)? as usize;
+
+        (0..count).map(|i| 132 + i * 12).find_map(|entry| {
+            if self.data.get(entry..entry + 4)? != signature {
+                return None;
+            }
+            let offset = self.u32(entry + 4)? as usize;
+            let size = self.u32(entry + 8)? as usize;
+
+            self.data.get(offset..offset.checked_add(size)?)
+        })
+    }
+
+    fn required_tag(&self, signature: [u8; 4]) -> Result<&'a [u8]> {
+        self.tag(signature).ok_or_else(|| {
+            Error::InvalidIccProfile(format!(
+                "missing the {} tag",
+                String::from_utf8_lossy(&signature),
+            ))
+        })
+    }
+
+    fn xyz(&self, tag: &[u8]) -> Result<[f32; 3]> {
+        let tag = IccReader { data: tag };
+        if tag.data.get(..4) != Some(b"XYZ ") {
+            return Err(invalid("expected an XYZ tag"));
+        }
+
+        Some([tag.s15_fixed16(8), tag.s15_fixed16(12), tag.s15_fixed16(16)])
+            .and_then(|[x, y, z]| Some([x?, y?, z?]))
+            .ok_or_else(|| invalid("truncated XYZ tag"))
+    }
+
+    fn curve(&self, tag: &[u8]) -> Result<ToneCurve> {
+        let tag = IccReader { data: tag };
+        let truncated = || invalid("truncated curve tag");
+
+        match tag.data.get(..4) {
+            Some(b"curv") => {
+                let count = tag.u32(8).ok_or_else(truncated)? as usize;
+
+                Ok(match count {
+                    0 => ToneCurve::Linear,
+                    1 => ToneCurve::Gamma(f32::from(tag.u16(12).ok_or_else(truncated)?) / 256.0),
+                    _ => ToneCurve::Table(
+                        (0..count)
+                            .map(|i| tag.u16(12 + i * 2).map(|n| f32::from(n) / 65535.0))
+                            .collect::<Option<_>>()
+                            .ok_or_else(truncated)?,
+                    ),
+                })
+            }
+            Some(b"para") => {
+                let kind = tag.u16(8).ok_or_else(truncated)?;
+                let count = match kind {
+                    0 => 1,
+                    1 => 3,
+                    2 => 4,
+                    3 => 5,
+                    4 => 7,
+                    _ => return Err(invalid("unknown parametric curve type")),
+                };
+                let mut params = [0.0; 7];
+                for (i, param) in params.iter_mut().enumerate().take(count) {
+                    *param = tag.s15_fixed16(12 + i * 4).ok_or_else(truncated)?;
+                }
+
+                // Normalize all parametric curve types into the most general form
+                let [g, a, b, c, ..] = params;
+                if matches!(kind, 1 | 2) && a == 0.0 {
+                    return Err(invalid("parametric curve has a zero coefficient"));
+                }
+                Ok(ToneCurve::Parametric(match kind {
+                    0 => [g, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                    1 => [g, a, b, 0.0, -b / a, 0.0, 0.0],
+                    2 => [g, a, b, 0.0, -b / a, c, c],
+                    _ => params,
+                }))
+            }
+            _ => Err(invalid("only curv and para curve tags are supported")),
+        }
+    }
+
+    /// Reads a v2 `desc` or a v4 `mluc` description tag.
+    fn description(&self, tag: &[u8]) -> Option<String> {
+        let tag = IccReader { data: tag };
+
+        match tag.data.get(..4)? {
+            b"desc" => {
+                let len = tag.u32(8)? as usize;
+                let text = tag.data.get(12..len.checked_add(12)?)?;
+
+                Some(
+                    String::from_utf8_lossy(text)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                )
+            }
+            b"mluc" => {
+                let len = tag.u32(20)? as usize;
+                let offset = tag.u32(24)? as usize;
+                let text = tag.data.get(offset..offset.checked_add(len)?)?;
+                let units = text
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+                    .collect::<Vec<_>>();
+
+                Some(String::from_utf16_lossy(&units))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn encode_s15_fixed16(value: f32) -> [u8; 4] {
+    ((value * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn encode_xyz(xyz: [f32; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for value in xyz {
+        tag.extend_from_slice(&encode_s15_fixed16(value));
+    }
+    tag
+}
+
+fn encode_mluc(text: &str) -> Vec<u8> {
+    let text = text
+        .encode_utf16()
+        .flat_map(u16::to_be_bytes)
+        .collect::<Vec<_>>();
+
+    let mut tag = b"mluc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&1_u32.to_be_bytes());
+    tag.extend_from_slice(&12_u32.to_be_bytes());
+    tag.extend_from_slice(b"enUS");
+    tag.extend_from_slice(&(text.len() as u32).to_be_bytes());
+    tag.extend_from_slice(&28_u32.to_be_bytes());
+    tag.extend(text);
+    tag
+}
+
+fn encode_curve(curve: &ToneCurve) -> Vec<u8> {
+    match curve {
+        ToneCurve::Linear => b"curv\0\0\0\0\0\0\0\0".to_vec(),
+        ToneCurve::Gamma(gamma) => {
+            let mut tag = b"curv\0\0\0\0\0\0\0\x01".to_vec();
+            tag.extend_from_slice(&((gamma * 256.0).round() as u16).to_be_bytes());
+            tag
+        }
+        ToneCurve::Parametric(params) => {
+            let mut tag = b"para\0\0\0\0\0\x04\0\0".to_vec();
+            for param in params {
+                tag.extend_from_slice(&encode_s15_fixed16(*param));
+            }
+            tag
+        }
+        ToneCurve::Table(table) => {
+            let mut tag = b"curv\0\0\0\0".to_vec();
+            tag.extend_from_slice(&(table.len() as u32).to_be_bytes());
+            for sample in table {
+                tag.extend_from_slice(
+                    &((sample.clamp(0.0, 1.0) * 65535.0).round() as u16).to_be_bytes(),
+                );
+            }
+            tag
+        }
+    }
+}
+
+/// Returns the Bradford chromatic adaptation matrix from the given white point into D50.
+fn adaptation_to_d50(white: [f32; 3]) -> Matrix {
+    let source = mul_vector(&BRADFORD, white);
+    let target = mul_vector(&BRADFORD, D50);
+    let scale = [
+        [target[0] / source[0], 0.0, 0.0],
+        [0.0, target[1] / source[1], 0.0],
+        [0.0, 0.0, target[2] / source[2]],
+    ];
+
+    mul(&invert(&BRADFORD), &mul(&scale, &BRADFORD))
+}
+
+fn mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+fn mul_vector(m: &Matrix, v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn transpose(m: Matrix) -> Matrix {
+    [
+        [m[0][0], m[1][0], m[2][0]],
+        [m[0][1], m[1][1], m[2][1]],
+        [m[0][2], m[1][2], m[2][2]],
+    ]
+}
+
+fn determinant(m: &Matrix) -> f32 {
+    let [[a, b, c], [d, e, f], [g, h, i]] = *m;
+    a * (e * i - f * h) + b * (f * g - d * i) + c * (d * h - e * g)
+}
+
+fn invert(m: &Matrix) -> Matrix {
+    let [[a, b, c], [d, e, f], [g, h, i]] = *m;
+    let (x, y, z) = (e * i - f * h, f * g - d * i, d * h - e * g);
+    let det = determinant(m);
+
+    [
+        [x / det, (c * h - b * i) / det, (b * f - c * e) / det],
+        [y / det, (a * i - c * g) / det, (c * d - a * f) / det],
+        [z / det, (b * g - a * h) / det, (a * e - b * d) / det],
+    ]
+}
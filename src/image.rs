@@ -1,18 +1,26 @@
 #![allow(clippy::wildcard_imports)]
 
 use crate::{
+    blend::BlendMode,
+    composite::CompositeOperator,
+    dither::Dither,
     draw::Draw,
     error::{Error, Result},
     fill::{Fill, IntoFill},
     format::ImageFormat,
+    histogram::{self, ClaheOptions, Histogram, Levels, LookupTable, Threshold},
+    icc::{ColorManaged, ColorProfile, ColorTransform},
+    metadata::Metadata,
     pixel::*,
+    transform::{invert_matrix, EdgeMode, RotateOptions, WarpOptions},
+    vector::{FromVector, IntoVector},
 };
 
 #[cfg(feature = "resize")]
 use crate::ResizeAlgorithm;
 
 use crate::filter::Filter;
-use num_traits::{SaturatingAdd, SaturatingSub};
+use num_traits::{AsPrimitive, Bounded};
 use std::{
     fmt::{self, Display},
     fs::File,
@@ -29,6 +37,12 @@ pub enum OverlayMode {
     Replace,
     /// Merge the alpha values of overlay image with the alpha values of the base image.
     Merge,
+    /// Blend the colors of the overlay image with the colors of the base image using the given
+    /// [`BlendMode`], then merge them like [`Self::Merge`].
+    Blend(BlendMode),
+    /// Composite the overlay image onto the base image using the given Porter-Duff
+    /// [`CompositeOperator`].
+    Composite(CompositeOperator),
 }
 
 impl Default for OverlayMode {
@@ -42,10 +56,21 @@ impl Display for OverlayMode {
         match self {
             Self::Merge => write!(f, "merge"),
             Self::Replace => write!(f, "replace"),
+            Self::Blend(mode) => write!(f, "{mode}"),
+            Self::Composite(operator) => write!(f, "{operator}"),
         }
     }
 }
 
+/// Converts the given value into a subpixel, clamping it into the range of the subpixel type.
+fn saturate_subpixel<T>(value: f64) -> T
+where
+    T: AsPrimitive<f64> + Bounded,
+    f64: AsPrimitive<T>,
+{
+    num_traits::clamp(value, T::min_value().as_(), T::max_value().as_()).as_()
+}
+
 /// A high-level image representation.
 ///
 /// This represents a static, single-frame image.
@@ -64,6 +89,7 @@ pub struct Image<P: Pixel> {
     pub(crate) format: ImageFormat,
     pub(crate) overlay: OverlayMode,
     pub(crate) palette: Option<Box<[P::Color]>>,
+    pub(crate) metadata: Metadata,
 }
 
 macro_rules! assert_nonzero {
@@ -110,6 +136,7 @@ impl<P: Pixel> Image<P> {
             format: ImageFormat::default(),
             overlay: OverlayMode::default(),
             palette: None,
+            metadata: Metadata::new(),
         }
     }
 
@@ -193,6 +220,7 @@ impl<P: Pixel> Image<P> {
             format: ImageFormat::default(),
             overlay: OverlayMode::default(),
             palette: None,
+            metadata: Metadata::new(),
         }
     }
 
@@ -247,6 +275,7 @@ impl<P: Pixel> Image<P> {
             format: ImageFormat::default(),
             overlay: OverlayMode::default(),
             palette: Some(palette),
+            metadata: Metadata::new(),
         };
 
         let palette = unsafe {
@@ -290,9 +319,8 @@ impl<P: Pixel> Image<P> {
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     ///   specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     ///
     /// # Example
     /// ```no_run
@@ -320,9 +348,8 @@ impl<P: Pixel> Image<P> {
     ///
     /// # Errors
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     ///
     /// # Examples
     /// ```no_run,ignore
@@ -346,9 +373,8 @@ impl<P: Pixel> Image<P> {
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     ///   specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     ///
     /// # Examples
     /// ```no_run,ignore
@@ -376,9 +402,8 @@ impl<P: Pixel> Image<P> {
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     ///   specifying it.
     /// * `IoError`: The file could not be opened.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     ///
     /// # Example
     /// ```no_run
@@ -405,13 +430,35 @@ impl<P: Pixel> Image<P> {
         format.run_decoder(buffer.as_slice())
     }
 
+    /// Opens a file from the given path and decodes it into an image like [`open`][Self::open],
+    /// then rotates and flips it according to its EXIF orientation tag such that it is upright.
+    ///
+    /// This is useful for photos taken by phones and cameras, which are often stored sideways
+    /// along with an orientation tag describing how they should be displayed.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
+    /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
+    ///   specifying it.
+    /// * `IoError`: The file could not be opened.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
+    ///
+    /// # See Also
+    /// - [`Self::apply_exif_orientation`] to do this for images decoded in other ways
+    pub fn open_with_orientation(path: impl AsRef<Path>) -> Result<Self> {
+        let mut image = Self::open(path)?;
+        image.apply_exif_orientation();
+
+        Ok(image)
+    }
+
     /// Encodes the image with the given encoding and writes it to the given write buffer.
     ///
     /// # Errors
     /// * An error occured during encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No encoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     ///
     /// # Example
     /// ```no_run
@@ -432,9 +479,8 @@ impl<P: Pixel> Image<P> {
     ///
     /// # Errors
     /// * An error occured during encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No encoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     ///
     /// # Example
     /// ```no_run
@@ -462,9 +508,8 @@ impl<P: Pixel> Image<P> {
     /// # Errors
     /// * Could not infer encoding format.
     /// * An error occured during encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No encoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     ///
     /// # Example
     /// ```no_run
@@ -547,6 +592,29 @@ impl<P: Pixel> Image<P> {
         self
     }
 
+    /// Returns a reference to the metadata of the image, such as its EXIF data or ICC profile.
+    ///
+    /// This is populated when decoding an image from a format that supports metadata, and is
+    /// written back when encoding it. See [`Metadata`] for more information.
+    #[inline]
+    #[must_use]
+    pub const fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Returns a mutable reference to the metadata of the image.
+    #[inline]
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Returns the same image with its metadata set to the given value.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     /// Returns the dimensions of the image.
     #[inline]
     #[must_use]
@@ -672,11 +740,13 @@ impl<P: Pixel> Image<P> {
     /// For anything with alpha, alpha is not brightened.
     pub fn brighten(&mut self, amount: P::Subpixel)
     where
-        P::Subpixel: SaturatingAdd + Copy,
+        P::Subpixel: AsPrimitive<f64> + Bounded,
+        f64: AsPrimitive<P::Subpixel>,
     {
-        self.data
-            .iter_mut()
-            .for_each(|p| *p = p.map_subpixels(|value| value.saturating_add(&amount), |a| a));
+        let amount = amount.as_();
+        self.data.iter_mut().for_each(|p| {
+            *p = p.map_subpixels(|value| saturate_subpixel(value.as_() + amount), |a| a);
+        });
     }
 
     /// Darkens the image by decreasing all pixels by the specified amount of subpixels in place.
@@ -689,11 +759,13 @@ impl<P: Pixel> Image<P> {
     /// For anything with alpha, alpha is not brightened.
     pub fn darken(&mut self, amount: P::Subpixel)
     where
-        P::Subpixel: SaturatingSub + Copy,
+        P::Subpixel: AsPrimitive<f64> + Bounded,
+        f64: AsPrimitive<P::Subpixel>,
     {
-        self.data
-            .iter_mut()
-            .for_each(|p| *p = p.map_subpixels(|value| value.saturating_sub(&amount), |a| a));
+        let amount = amount.as_();
+        self.data.iter_mut().for_each(|p| {
+            *p = p.map_subpixels(|value| saturate_subpixel(value.as_() - amount), |a| a);
+        });
     }
 
     /// Takes this image and brightens it by increasing all pixels by the specified amount of
@@ -708,9 +780,13 @@ impl<P: Pixel> Image<P> {
     #[must_use]
     pub fn brightened(self, amount: P::Subpixel) -> Self
     where
-        P::Subpixel: SaturatingAdd + Copy,
+        P::Subpixel: AsPrimitive<f64> + Bounded,
+        f64: AsPrimitive<P::Subpixel>,
     {
-        self.map_pixels(|pixel| pixel.map_subpixels(|value| value.saturating_add(&amount), |a| a))
+        let amount = amount.as_();
+        self.map_pixels(|pixel| {
+            pixel.map_subpixels(|value| saturate_subpixel(value.as_() + amount), |a| a)
+        })
     }
 
     /// Takes this image and darkens it by decreasing all pixels by the specified amount of
@@ -725,9 +801,13 @@ impl<P: Pixel> Image<P> {
     #[must_use]
     pub fn darkened(self, amount: P::Subpixel) -> Self
     where
-        P::Subpixel: SaturatingSub + Copy,
+        P::Subpixel: AsPrimitive<f64> + Bounded,
+        f64: AsPrimitive<P::Subpixel>,
     {
-        self.map_pixels(|pixel| pixel.map_subpixels(|value| value.saturating_sub(&amount), |a| a))
+        let amount = amount.as_();
+        self.map_pixels(|pixel| {
+            pixel.map_subpixels(|value| saturate_subpixel(value.as_() - amount), |a| a)
+        })
     }
 
     #[allow(clippy::cast_lossless)]
@@ -755,23 +835,30 @@ impl<P: Pixel> Image<P> {
     /// The hue is a standard angle degree, that is a value between 0 and 360, although values
     /// below and above will be wrapped using the modulo operator.
     ///
+    /// The rotation is computed at the full precision of the pixel type, so 16-bit and floating
+    /// point pixels are not rounded to 8 bits. Pixels with less than three components, such as
+    /// grayscale pixels, have no hue and are left untouched.
+    ///
     /// For anything with alpha, alpha is not rotated.
-    pub fn hue_rotate(&mut self, degrees: i32)
+    pub fn hue_rotate<const N: usize>(&mut self, degrees: i32)
     where
-        P: TrueColor,
+        P: IntoVector<N> + FromVector<N, Element = <P as IntoVector<N>>::Element>,
+        <P as IntoVector<N>>::Element: Copy + AsPrimitive<f64> + Bounded,
+        f64: AsPrimitive<<P as IntoVector<N>>::Element>,
     {
+        if N < 3 {
+            return;
+        }
         let mat = Self::prepare_hue_matrix(degrees);
 
         self.data.iter_mut().for_each(|p| {
-            let (r, g, b, a) = p.as_rgba_tuple();
-            let (r, g, b) = (f64::from(r), f64::from(g), f64::from(b));
-
-            *p = P::from_rgba_tuple((
-                mat.2.mul_add(b, mat.0.mul_add(r, mat.1 * g)) as u8,
-                mat.5.mul_add(b, mat.3.mul_add(r, mat.4 * g)) as u8,
-                mat.8.mul_add(b, mat.6.mul_add(r, mat.7 * g)) as u8,
-                a,
-            ));
+            let mut vector = p.into_vector();
+            let (r, g, b) = (vector[0].as_(), vector[1].as_(), vector[2].as_());
+
+            vector[0] = saturate_subpixel(mat.2.mul_add(b, mat.0.mul_add(r, mat.1 * g)));
+            vector[1] = saturate_subpixel(mat.5.mul_add(b, mat.3.mul_add(r, mat.4 * g)));
+            vector[2] = saturate_subpixel(mat.8.mul_add(b, mat.6.mul_add(r, mat.7 * g)));
+            *p = P::from_vector(vector);
         });
     }
 
@@ -780,9 +867,11 @@ impl<P: Pixel> Image<P> {
     ///
     /// See [`Self::hue_rotate`] for more information.
     #[must_use]
-    pub fn hue_rotated(mut self, degrees: i32) -> Self
+    pub fn hue_rotated<const N: usize>(mut self, degrees: i32) -> Self
     where
-        P: TrueColor,
+        P: IntoVector<N> + FromVector<N, Element = <P as IntoVector<N>>::Element>,
+        <P as IntoVector<N>>::Element: Copy + AsPrimitive<f64> + Bounded,
+        f64: AsPrimitive<<P as IntoVector<N>>::Element>,
     {
         self.hue_rotate(degrees);
         self
@@ -805,6 +894,7 @@ impl<P: Pixel> Image<P> {
             format: self.format,
             overlay: self.overlay,
             palette: None,
+            metadata: self.metadata,
         }
     }
 
@@ -904,15 +994,14 @@ impl<P: Pixel> Image<P> {
 
     /// Applies the given [`Filter`] to this image in place.
     pub fn apply_filter(&mut self, filter: &impl Filter<Input = P, Output = P>) {
-        let old = self.clone();
-        self.map_in_place(|x, y, pixel| *pixel = filter.apply_pixel(&old, x, y, *pixel));
+        self.data = filter.apply_image(self);
     }
 
     /// Applies the given [`Filter`] to this image, returning a new image.
     #[must_use]
     pub fn filtered<T: Pixel>(self, filter: &impl Filter<Input = P, Output = T>) -> Image<T> {
-        let old = self.clone();
-        self.map_pixels_with_coords(|x, y, pixel| filter.apply_pixel(&old, x, y, pixel))
+        let data = filter.apply_image(&self);
+        self.map_data(|_| data)
     }
 
     /// Crops this image in place to the given bounding box.
@@ -1008,27 +1097,230 @@ impl<P: Pixel> Image<P> {
         std::mem::swap(&mut self.width, &mut self.height);
     }
 
+    /// Rotates and flips this image in place according to the orientation tag of its EXIF
+    /// metadata, such that it is displayed upright. The orientation tag is then reset to 1 (upright)
+    /// so that the orientation is not applied twice by image viewers if the image is encoded again.
+    ///
+    /// This does nothing if the image has no EXIF orientation tag.
+    ///
+    /// # See Also
+    /// - [`Metadata::exif_orientation`] for the orientation that is applied
+    /// - [`Self::open_with_orientation`], which applies this automatically when opening a file
+    pub fn apply_exif_orientation(&mut self) {
+        match self.metadata.exif_orientation() {
+            Some(2) => self.mirror(),
+            Some(3) => self.rotate_180(),
+            Some(4) => self.flip(),
+            Some(5) => {
+                self.rotate_90();
+                self.mirror();
+            }
+            Some(6) => self.rotate_90(),
+            Some(7) => {
+                self.rotate_270();
+                self.mirror();
+            }
+            Some(8) => self.rotate_270(),
+            _ => return,
+        }
+
+        self.metadata.set_exif_orientation(1);
+    }
+
     /// Rotates this image in place about its center. There are optimized rotating algorithms for
     /// 90, 180, and 270 degree rotations (clockwise).
     ///
-    /// As mentioned, the argument is specified in degrees.
+    /// As mentioned, the argument is specified in degrees. Any other angle is rotated with
+    /// [`Self::rotate_by`] using the default [`RotateOptions`], which keeps the dimensions of the
+    /// image.
     ///
     /// # See Also
     /// - [`Self::rotated`] for this method which does operate in-place - useful for method chaining
-    pub fn rotate(&mut self, mut degrees: i32) {
-        degrees %= 360;
-
-        match degrees {
+    /// - [`Self::rotate_by`] for rotating by fractional angles with more options
+    pub fn rotate(&mut self, degrees: i32) {
+        match degrees.rem_euclid(360) {
             0 => (),
             90 => self.rotate_90(),
             180 => self.rotate_180(),
             270 => self.rotate_270(),
-            _ => unimplemented!(
-                "currently only rotations of 0, 90, 180, and 270 degrees are supported",
-            ),
+            _ => self.rotate_by(f64::from(degrees), RotateOptions::default()),
         }
     }
 
+    /// Rotates this image clockwise by the given angle in degrees about its center in place,
+    /// sampling pixels with the interpolation algorithm given in `options`. With the `resize`
+    /// feature, this can also be a [`ResizeAlgorithm`][crate::ResizeAlgorithm], see
+    /// [`Interpolation`][crate::transform::Interpolation] for more information.
+    ///
+    /// Areas of the image not covered by the rotated image are filled with the fill pixel given
+    /// in `options`. If [`RotateOptions::expand`] is `true`, the image is resized to fit the entire
+    /// rotated image, otherwise it keeps its dimensions.
+    ///
+    /// Pixels with an alpha channel are interpolated in premultiplied alpha, so fully transparent
+    /// pixels do not bleed into their neighbors.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let mut image = Image::<Rgba>::open("sample.png")?;
+    /// image.rotate_by(
+    ///     30.0,
+    ///     RotateOptions::new()
+    ///         .with_interpolation(ResizeAlgorithm::Bicubic)
+    ///         .with_expand(true),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # See Also
+    /// - [`Self::rotated_by`] for this method which does not operate in-place - useful for
+    ///   method chaining
+    pub fn rotate_by(&mut self, degrees: f64, options: RotateOptions<P>) {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (width, height) = (f64::from(self.width()), f64::from(self.height()));
+
+        let (new_width, new_height) = if options.expand {
+            // A small epsilon is subtracted to account for floating-point error, e.g. cos(90°)
+            let fit = |n: f64| ((n - 1e-6).ceil() as u32).max(1);
+            (
+                fit(width * cos.abs() + height * sin.abs()),
+                fit(width * sin.abs() + height * cos.abs()),
+            )
+        } else {
+            (self.width(), self.height())
+        };
+
+        let (cx, cy) = (width / 2.0, height / 2.0);
+        let (ncx, ncy) = (f64::from(new_width) / 2.0, f64::from(new_height) / 2.0);
+
+        self.data = crate::transform::remap(
+            self,
+            new_width,
+            new_height,
+            options.interpolation,
+            EdgeMode::Constant(options.fill),
+            |x, y| {
+                let (dx, dy) = (x - ncx, y - ncy);
+                (cx + dx * cos + dy * sin, cy - dx * sin + dy * cos)
+            },
+        );
+        self.width = NonZeroU32::new(new_width).unwrap();
+        self.height = NonZeroU32::new(new_height).unwrap();
+    }
+
+    /// Takes the image and rotates it clockwise by the given angle in degrees about its center.
+    /// Useful for method chaining. See [`Self::rotate_by`] for more information.
+    #[must_use]
+    pub fn rotated_by(mut self, degrees: f64, options: RotateOptions<P>) -> Self {
+        self.rotate_by(degrees, options);
+        self
+    }
+
+    /// Warps this image in place with the given affine transformation, sampling pixels with the
+    /// interpolation algorithm given in `options`. With the `resize` feature, this can also be a
+    /// [`ResizeAlgorithm`][crate::ResizeAlgorithm], see
+    /// [`Interpolation`][crate::transform::Interpolation] for more information.
+    ///
+    /// The matrix `[[a, b, c], [d, e, f]]` maps the point `(x, y)` of the source image onto the
+    /// point `(a * x + b * y + c, d * x + e * y + f)` of the warped image, where integer
+    /// coordinates refer to the centers of pixels. Pixels of the warped image that map outside of
+    /// the source image are resolved using the edge mode given in `options`.
+    ///
+    /// # Panics
+    /// * The matrix is not invertible.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let mut image = Image::<Rgb>::open("sample.png")?;
+    /// // Shear the image horizontally, wrapping pixels around the edges
+    /// image.warp_affine(
+    ///     [[1.0, 0.3, 0.0], [0.0, 1.0, 0.0]],
+    ///     WarpOptions::new().with_edge_mode(EdgeMode::Wrap),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # See Also
+    /// - [`Self::warped_affine`] for this method which does not operate in-place - useful for
+    ///   method chaining
+    /// - [`Self::warp_perspective`] for perspective transformations
+    pub fn warp_affine(&mut self, matrix: [[f64; 3]; 2], options: WarpOptions<P>) {
+        let [first, second] = matrix;
+        self.warp_perspective([first, second, [0.0, 0.0, 1.0]], options);
+    }
+
+    /// Takes the image and warps it with the given affine transformation. Useful for method
+    /// chaining. See [`Self::warp_affine`] for more information.
+    ///
+    /// # Panics
+    /// * The matrix is not invertible.
+    #[must_use]
+    pub fn warped_affine(mut self, matrix: [[f64; 3]; 2], options: WarpOptions<P>) -> Self {
+        self.warp_affine(matrix, options);
+        self
+    }
+
+    /// Warps this image in place with the given perspective transformation (homography),
+    /// sampling pixels with the interpolation algorithm given in `options`. With the `resize`
+    /// feature, this can also be a [`ResizeAlgorithm`][crate::ResizeAlgorithm], see
+    /// [`Interpolation`][crate::transform::Interpolation] for more information.
+    ///
+    /// The matrix maps the point `(x, y)` of the source image onto the point `(u / w, v / w)` of
+    /// the warped image, where `[u, v, w]` is the product of the matrix and `[x, y, 1]`, and
+    /// integer coordinates refer to the centers of pixels. Pixels of the warped image that map
+    /// outside of the source image are resolved using the edge mode given in `options`.
+    ///
+    /// Use [`homography`][crate::transform::homography] to compute the matrix from four point
+    /// correspondences, for example to deskew a photographed document.
+    ///
+    /// # Panics
+    /// * The matrix is not invertible.
+    ///
+    /// # See Also
+    /// - [`Self::warped_perspective`] for this method which does not operate in-place - useful
+    ///   for method chaining
+    pub fn warp_perspective(&mut self, matrix: [[f64; 3]; 3], options: WarpOptions<P>) {
+        let [[a, b, c], [d, e, f], [g, h, i]] =
+            invert_matrix(matrix).expect("transformation matrix must be invertible");
+        let (width, height) = options.dimensions.unwrap_or((self.width(), self.height()));
+
+        self.data = crate::transform::remap(
+            self,
+            width,
+            height,
+            options.interpolation,
+            options.edge_mode,
+            |x, y| {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let w = g * x + h * y + i;
+                // Points with a non-positive w lie behind the projection and map nowhere
+                if w <= 0.0 {
+                    return (f64::NAN, f64::NAN);
+                }
+
+                ((a * x + b * y + c) / w + 0.5, (d * x + e * y + f) / w + 0.5)
+            },
+        );
+        self.width = NonZeroU32::new(width).unwrap();
+        self.height = NonZeroU32::new(height).unwrap();
+    }
+
+    /// Takes the image and warps it with the given perspective transformation. Useful for method
+    /// chaining. See [`Self::warp_perspective`] for more information.
+    ///
+    /// # Panics
+    /// * The matrix is not invertible.
+    #[must_use]
+    pub fn warped_perspective(mut self, matrix: [[f64; 3]; 3], options: WarpOptions<P>) -> Self {
+        self.warp_perspective(matrix, options);
+        self
+    }
+
     /// Takes the image and rotates it by the specified amount of degrees about its center. Useful
     /// for method chaining. There are optimized rotating algorithms for 90, 180, and 270 degree
     /// rotations.
@@ -1170,6 +1462,29 @@ impl<P: Pixel> Image<P> {
         self.draw(&crate::Paste::new(image).with_position(x, y).with_mask(mask));
     }
 
+    /// Composites the given image onto this image at the given x and y coordinates using the
+    /// given Porter-Duff compositing operator, where the given image is the source and this image
+    /// is the destination. Only the pixels covered by the given image are affected.
+    ///
+    /// This is a shorthand for using the [`Self::draw`] method with [`crate::Composite`], which
+    /// can also be used along with a mask or to composite the pixels not covered by the image.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let mut image = Image::<Rgba>::open("sample.png")?;
+    /// let stencil = Image::open("stencil.png")?;
+    ///
+    /// // Keep only the parts of the image that are covered by the stencil
+    /// image.composite(0, 0, &stencil, CompositeOperator::DestinationIn);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn composite(&mut self, x: u32, y: u32, image: &Self, operator: CompositeOperator) {
+        self.draw(&crate::Composite::new(image, operator).with_position(x, y));
+    }
+
     /// Masks the alpha values of this image with the luminance values of the given single-channel
     /// [`Luma`] image.
     ///
@@ -1202,6 +1517,28 @@ impl<P: Pixel> Image<P> {
             });
     }
 
+    /// Converts the pixels of this image from straight alpha into premultiplied alpha in place.
+    /// See [`Alpha::premultiply`] for more information.
+    ///
+    /// All other methods of this crate expect straight alpha, and already use premultiplied alpha
+    /// internally where it matters, e.g. when resizing, convolving, or compositing. This is mainly
+    /// useful for passing pixel data to other libraries which expect premultiplied alpha.
+    pub fn premultiply(&mut self)
+    where
+        P: Alpha,
+    {
+        self.data.iter_mut().for_each(|p| *p = p.premultiply());
+    }
+
+    /// Converts the pixels of this image from premultiplied alpha back into straight alpha in
+    /// place. See [`Alpha::unpremultiply`] for more information.
+    pub fn unpremultiply(&mut self)
+    where
+        P: Alpha,
+    {
+        self.data.iter_mut().for_each(|p| *p = p.unpremultiply());
+    }
+
     /// Returns the palette associated with this image as a slice.
     /// If there is no palette, this returns `None`.
     #[must_use]
@@ -1272,7 +1609,7 @@ impl<P: Pixel> Image<P> {
                     U::from_raw_parts_paletted(
                         U::COLOR_TYPE,
                         U::BIT_DEPTH,
-                        &[p.palette_index().into() as u8],
+                        &[p.palette_index()],
                         palette.as_deref(),
                     )
                     .expect("could not perform safe conversion of palette references")
@@ -1281,6 +1618,7 @@ impl<P: Pixel> Image<P> {
             format: self.format,
             overlay: self.overlay,
             palette,
+            metadata: self.metadata,
         }
     }
 
@@ -1317,13 +1655,304 @@ impl<P: Pixel> Image<P> {
         P: TrueColor,
         T: Pixel<Color = P> + Paletted<'p, Subpixel = u8>,
     {
-        let width = self.width();
-        let (palette, pixels) = crate::quantize::Quantizer::new()
-            .with_palette_size(palette_size as usize)
-            .quantize(self.data)
-            .expect("unable to quantize image");
+        self.quantize_with(
+            &crate::quantize::Quantizer::new().with_palette_size(palette_size as usize),
+        )
+        .expect("unable to quantize image")
+    }
+
+    /// Quantizes this image with the given [`Quantizer`] and turns it into its paletted
+    /// counterpart. This currently only works with 8-bit palettes.
+    ///
+    /// This allows for more control than [`Self::quantize`], for example dithering:
+    ///
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// use ril::Quantizer;
+    ///
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::<Rgb>::open("sample.png")?;
+    /// let quantizer = Quantizer::new()
+    ///     .with_palette_size(16)
+    ///     .with_dither(Dither::FloydSteinberg);
+    /// let paletted: Image<PalettedRgb> = image.quantize_with(&quantizer)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// * Unable to quantize the image.
+    pub fn quantize_with<'p, T>(self, quantizer: &crate::quantize::Quantizer) -> Result<Image<T>>
+    where
+        Self: 'p,
+        P: TrueColor,
+        T: Pixel<Color = P> + Paletted<'p, Subpixel = u8>,
+    {
+        let (palette, pixels) = quantizer.quantize_image(&self)?;
+
+        Ok(Image::from_paletted_pixels(self.width(), palette, pixels))
+    }
+}
+
+impl<P: ColorManaged> Image<P> {
+    /// Converts the colors of this image from the `source` color profile into the `target` color
+    /// profile in place. The target profile is then embedded into the metadata of this image.
+    ///
+    /// Since the pixels of this image are always RGB, converting into a grayscale profile only
+    /// drops their color. An equivalent RGB profile with the tone curve of the grayscale profile is
+    /// embedded instead, as a grayscale profile cannot describe RGB data.
+    ///
+    /// Colors that fall outside of the gamut of the target profile are clipped.
+    ///
+    /// # See Also
+    /// * [`Self::convert_to_profile`] for converting from the profile embedded in this image.
+    pub fn convert_color_profile(&mut self, source: &ColorProfile, target: &ColorProfile) {
+        let transform = ColorTransform::new(source, target);
+
+        for pixel in &mut self.data {
+            *pixel = pixel.with_color_components(transform.apply(pixel.color_components()));
+        }
+        self.metadata.icc_profile = Some(target.to_rgb().to_icc());
+    }
+
+    /// Converts the colors of this image from its embedded ICC color profile into the `target`
+    /// color profile in place, such as the profiles preserved by decoders in
+    /// [`Metadata::icc_profile`]. If this image has no embedded profile, it is assumed to be sRGB.
+    ///
+    /// # Errors
+    /// * `InvalidIccProfile`: The embedded profile is malformed or unsupported. See
+    ///   [`ColorProfile::from_icc`] for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// // Display P3 images look washed out when their data is interpreted as sRGB
+    /// let mut image = Image::<Rgb>::open("iphone_photo.jpg")?;
+    /// image.convert_to_profile(&ColorProfile::srgb())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert_to_profile(&mut self, target: &ColorProfile) -> Result<()> {
+        let source = self
+            .metadata
+            .color_profile()
+            .transpose()?
+            .unwrap_or_else(ColorProfile::srgb);
+
+        self.convert_color_profile(&source, target);
+        Ok(())
+    }
+}
+
+impl<P: Pixel> Image<P> {
+    /// Computes the histogram of each channel of this image, including the alpha channel if there
+    /// is one.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::<Rgb>::open("sample.png")?;
+    /// let [r, g, b] = image.histograms();
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # See Also
+    /// * [`Histogram::from_band`] for computing the histogram of a single band.
+    #[must_use]
+    pub fn histograms<const N: usize>(&self) -> [Histogram; N]
+    where
+        P: IntoVector<N, Element = u8>,
+    {
+        let mut histograms = [(); N].map(|_| Histogram::new());
+        for pixel in &self.data {
+            for (histogram, &value) in histograms.iter_mut().zip(pixel.into_vector().elements()) {
+                histogram.add(value);
+            }
+        }
+        histograms
+    }
 
-        Image::from_paletted_pixels(width, palette, pixels)
+    /// Maps each channel of each pixel of this image through the lookup table of the channel,
+    /// including the alpha channel if there is one. This is useful for applying per-channel
+    /// curves.
+    pub fn apply_lookup_tables<const N: usize>(&mut self, tables: &[LookupTable; N])
+    where
+        P: IntoVector<N, Element = u8> + FromVector<N, Element = u8>,
+    {
+        for pixel in &mut self.data {
+            let mut vector = pixel.into_vector();
+            for (c, table) in tables.iter().enumerate() {
+                vector[c] = table.get(vector[c]);
+            }
+            *pixel = P::from_vector(vector);
+        }
+    }
+
+    /// Maps each color channel of each pixel of this image through the given lookup table,
+    /// leaving the alpha channel untouched.
+    pub fn apply_lookup_table<const N: usize>(&mut self, table: &LookupTable)
+    where
+        P: IntoVector<N, Element = u8> + FromVector<N, Element = u8>,
+    {
+        let mut tables = [*table; N];
+        if P::COLOR_TYPE.has_alpha() {
+            tables[N - 1] = LookupTable::identity();
+        }
+        self.apply_lookup_tables(&tables);
+    }
+
+    /// Applies a lookup table computed from the histogram of each color channel, leaving the alpha
+    /// channel untouched.
+    fn apply_histogram_tables<const N: usize>(&mut self, f: impl Fn(&Histogram) -> LookupTable)
+    where
+        P: IntoVector<N, Element = u8> + FromVector<N, Element = u8>,
+    {
+        let histograms = self.histograms();
+        let mut tables = [(); N].map(|_| LookupTable::identity());
+        let channels = if P::COLOR_TYPE.has_alpha() { N - 1 } else { N };
+        for (table, histogram) in tables.iter_mut().zip(&histograms).take(channels) {
+            *table = f(histogram);
+        }
+        self.apply_lookup_tables(&tables);
+    }
+
+    /// Maximizes the contrast of this image by stretching the values of each color channel over
+    /// the entire range. The given fraction of the darkest and brightest values of each channel
+    /// are clipped, which makes this robust against outliers (e.g. `0.01` for 1%).
+    ///
+    /// Since each channel is stretched separately, this may also correct color casts.
+    ///
+    /// # Panics
+    /// * The cutoff is not between `0.0` and `0.5`.
+    pub fn auto_contrast<const N: usize>(&mut self, cutoff: f64)
+    where
+        P: IntoVector<N, Element = u8> + FromVector<N, Element = u8>,
+    {
+        self.apply_histogram_tables(|histogram| LookupTable::auto_contrast(histogram, cutoff));
+    }
+
+    /// Equalizes the histogram of each color channel of this image, spreading out the most
+    /// frequent values in order to enhance contrast.
+    ///
+    /// # See Also
+    /// * [`Self::clahe`] for equalizing each region of the image separately.
+    pub fn equalize<const N: usize>(&mut self)
+    where
+        P: IntoVector<N, Element = u8> + FromVector<N, Element = u8>,
+    {
+        self.apply_histogram_tables(LookupTable::equalize);
+    }
+
+    /// Performs contrast limited adaptive histogram equalization (CLAHE) on each color channel of
+    /// this image. This equalizes each tile of the image separately, interpolating between tiles,
+    /// while limiting how much the contrast is enhanced in order to avoid amplifying noise.
+    ///
+    /// Since each channel is equalized separately, this is usually applied to grayscale images.
+    pub fn clahe<const N: usize>(&mut self, options: ClaheOptions)
+    where
+        P: IntoVector<N, Element = u8> + FromVector<N, Element = u8>,
+    {
+        let channels = if P::COLOR_TYPE.has_alpha() { N - 1 } else { N };
+        let mut vectors = self
+            .data
+            .iter()
+            .map(|pixel| pixel.into_vector())
+            .collect::<Vec<_>>();
+
+        for c in 0..channels {
+            let channel = vectors.iter().map(|vector| vector[c]).collect::<Vec<_>>();
+            let equalized = histogram::clahe(&channel, self.dimensions(), options);
+            for (vector, value) in vectors.iter_mut().zip(equalized) {
+                vector[c] = value;
+            }
+        }
+        self.data = vectors.into_iter().map(P::from_vector).collect();
+    }
+
+    /// Adjusts the black point, white point, and gamma of each color channel of this image with
+    /// the given [`Levels`], leaving the alpha channel untouched.
+    pub fn adjust_levels<const N: usize>(&mut self, levels: Levels)
+    where
+        P: IntoVector<N, Element = u8> + FromVector<N, Element = u8>,
+    {
+        self.apply_lookup_table(&LookupTable::from_levels(levels));
+    }
+
+    /// Binarizes this image by comparing the luminance of each pixel against a threshold computed
+    /// with the given [`Threshold`] method, setting the pixels that are brighter than it.
+    ///
+    /// This gives more control than converting to [`BitPixel`] directly, which always uses a
+    /// fixed threshold of `127`.
+    ///
+    /// # Example
+    /// Binarizing an unevenly lit document:
+    ///
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// use ril::histogram::Threshold;
+    ///
+    /// # fn main() -> ril::Result<()> {
+    /// let page = Image::<Rgb>::open("scan.png")?;
+    /// let binarized = page.threshold(Threshold::AdaptiveGaussian { sigma: 8.0, offset: 10.0 });
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    /// * The sigma of [`Threshold::AdaptiveGaussian`] is not positive.
+    #[must_use]
+    pub fn threshold(&self, method: Threshold) -> Image<BitPixel>
+    where
+        P: Into<Luma>,
+    {
+        let luminance = self.data.iter().map(Pixel::luminance).collect::<Vec<_>>();
+
+        Image {
+            width: self.width,
+            height: self.height,
+            data: histogram::threshold(&luminance, self.dimensions(), method)
+                .into_iter()
+                .map(BitPixel::new)
+                .collect(),
+            format: self.format,
+            overlay: self.overlay,
+            palette: None,
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Converts this image to [`BitPixel`] with the given dithering method, which simulates
+    /// shades of gray through the density of set pixels.
+    ///
+    /// With [`Dither::None`], this is equivalent to converting to [`BitPixel`] directly.
+    ///
+    /// # See Also
+    /// * [`Self::threshold`] for binarizing without dithering.
+    #[must_use]
+    pub fn dither(&self, dither: Dither) -> Image<BitPixel>
+    where
+        P: Into<Luma>,
+    {
+        let luminance = self
+            .data
+            .iter()
+            .map(|pixel| [f32::from(pixel.luminance())])
+            .collect::<Vec<_>>();
+        let indices =
+            crate::dither::dither(&luminance, self.width() as usize, &[[0.0], [255.0]], dither);
+
+        Image {
+            width: self.width,
+            height: self.height,
+            data: indices.into_iter().map(|i| BitPixel::new(i == 1)).collect(),
+            format: self.format,
+            overlay: self.overlay,
+            palette: None,
+            metadata: self.metadata.clone(),
+        }
     }
 }
 
@@ -1505,6 +2134,7 @@ macro_rules! map_idx {
             format: $image.format,
             overlay: $image.overlay,
             palette: None,
+            metadata: $image.metadata.clone(),
         }
     }};
 }
@@ -37,6 +37,8 @@
 //! | PNG and APNG  | `png`   | `png`                          | no       |
 //! | JPEG          | `jpeg`  | `jpeg-decoder`, `jpeg-encoder` | no       |
 //! | GIF           | `gif`   | `gif`                          | no       |
+//! | BMP           | `bmp`   |                                | no       |
+//! | TIFF          | `tiff`  | `tiff`                         | no       |
 //! | WebP          | `webp`  | `libwebp-sys2`                 | no       |
 //!
 //! Other features:
@@ -100,7 +102,10 @@
 //! this image has - in this case, the image has RGB pixels.
 //!
 //! Common pixel formats are [`Rgb`] (colored) and [`Rgba`] (colored with transparency),
-//! which are found in the prelude. There are also grayscale counterparts, such as [`Luma`].
+//! which are found in the prelude. There are also grayscale counterparts, [`Luma`] and [`LumaA`].
+//! For images with 16 bits per channel, [`Rgb16`], [`Rgba16`], and [`Luma16`] can be used.
+//! [`RgbF32`] and [`RgbaF32`] store linear light in floating-point, which is useful for running
+//! many operations in a row without losing precision in between.
 //!
 //! ### Reading from a byte stream
 //! You can also read from raw bytes using [`from_bytes`][Image::decode_from_bytes]:
@@ -260,7 +265,10 @@
     clippy::doc_markdown
 )]
 
+pub mod blend;
 pub mod colors;
+pub mod composite;
+pub mod dither;
 pub mod draw;
 pub mod encode;
 pub mod encodings;
@@ -270,7 +278,10 @@ pub mod filter;
 mod format;
 #[cfg(feature = "gradient")]
 pub mod gradient;
+pub mod histogram;
+pub mod icc;
 mod image;
+pub mod metadata;
 pub mod pixel;
 pub mod quantize;
 #[cfg(feature = "resize")]
@@ -278,6 +289,7 @@ mod resize;
 pub mod sequence;
 #[cfg(feature = "text")]
 pub mod text;
+pub mod transform;
 pub mod vector;
 
 macro_rules! inline_doc {
@@ -288,10 +300,19 @@ macro_rules! inline_doc {
 
 inline_doc! {
     pub use crate::image::{Banded, Image, OverlayMode};
-    pub use draw::{Border, BorderPosition, Draw, Ellipse, Line, Paste, Polygon, Rectangle};
+    pub use blend::BlendMode;
+    pub use composite::CompositeOperator;
+    pub use dither::Dither;
+    pub use draw::{
+        Border, BorderPosition, Composite, Draw, Ellipse, FillRule, Line, Paste, Path, PathCommand,
+        Polygon, Rectangle,
+    };
     pub use encode::{Decoder, Encoder, EncoderMetadata, SingleFrameIterator, FrameIterator};
     pub use encodings::ColorType;
     pub use error::{Error, Result};
+    pub use histogram::{ClaheOptions, Histogram, Levels, LookupTable, Threshold};
+    pub use icc::{ColorManaged, ColorProfile, ColorTransform, ToneCurve};
+    pub use transform::{EdgeMode, Interpolation, RotateOptions, WarpOptions};
     #[cfg(feature = "gradient")]
     pub use gradient::{
         BlendMode as GradientBlendMode,
@@ -303,13 +324,17 @@ inline_doc! {
         RadialGradientCover,
     };
     pub use fill::{Fill, IntoFill};
-    pub use filter::{Convolution, DynamicConvolution};
+    pub use filter::{
+        BilateralFilter, Convolution, DynamicConvolution, EdgeDetection, EdgeOperator, Morphology,
+        MorphologyOperation, RankFilter, SeparableConvolution, StructuringElement, UnsharpMask,
+    };
     pub use format::ImageFormat;
+    pub use metadata::Metadata;
     pub use pixel::{
         Alpha, BitPixel, Dynamic, DynamicSubpixel, Paletted, PalettedRgb, PalettedRgba, Pixel, Rgb,
-        Rgba, TrueColor, Luma,
+        Rgba, TrueColor, Luma, LumaA, Luma16, Rgb16, Rgba16, RgbF32, RgbaF32,
     };
-    pub use quantize::Quantizer;
+    pub use quantize::{QuantizeAlgorithm, Quantizer};
     #[cfg(feature = "resize")]
     pub use resize::FilterType as ResizeAlgorithm;
     pub use sequence::{DisposalMethod, Frame, ImageSequence, LoopCount};
@@ -334,12 +359,15 @@ inline_doc! {
 /// ```
 pub mod prelude {
     pub use super::{
-        Alpha, Banded, BitPixel, Border, BorderPosition, ColorType, Convolution, Decoder,
-        DisposalMethod, Draw, Dynamic, DynamicConvolution, DynamicSubpixel, Ellipse, Encoder,
-        EncoderMetadata, Fill, Frame, FrameIterator, FromVector, Image, ImageFormat, ImageSequence,
-        IntoFill, IntoVector, Line, LoopCount, Luma, OverlayMode, Paletted, PalettedRgb,
-        PalettedRgba, Paste, Pixel, Polygon, Rectangle, Rgb, Rgba, SingleFrameIterator, TrueColor,
-        Vector,
+        Alpha, Banded, BilateralFilter, BitPixel, BlendMode, Border, BorderPosition, ColorManaged,
+        ColorProfile, ColorType, Composite, CompositeOperator, Convolution, Decoder,
+        DisposalMethod, Dither, Draw, Dynamic, DynamicConvolution, DynamicSubpixel, EdgeDetection,
+        EdgeMode, Ellipse, Encoder, EncoderMetadata, Fill, FillRule, Frame, FrameIterator,
+        FromVector, Image, ImageFormat, ImageSequence, Interpolation, IntoFill, IntoVector, Line,
+        LoopCount, Luma, Luma16, LumaA, Metadata, Morphology, OverlayMode, Paletted, PalettedRgb,
+        PalettedRgba, Paste, Path, PathCommand, Pixel, Polygon, RankFilter, Rectangle, Rgb, Rgb16,
+        RgbF32, Rgba, Rgba16, RgbaF32, RotateOptions, SeparableConvolution, SingleFrameIterator,
+        StructuringElement, TrueColor, UnsharpMask, Vector, WarpOptions,
     };
 
     #[cfg(feature = "resize")]
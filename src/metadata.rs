@@ -0,0 +1,187 @@
+//! Houses the [`Metadata`] container for auxiliary image data such as EXIF, XMP, and ICC profiles.
+
+use crate::{ColorProfile, Result};
+
+/// Auxiliary data embedded within an encoded image that is not part of its pixel data.
+///
+/// Decoders that support metadata will populate this when decoding an image, and the matching
+/// encoders will write it back when encoding. This means that, for example, decoding a JPEG and
+/// encoding it again retains its EXIF data and ICC profile.
+///
+/// Metadata is stored as-is and is **not** interpreted or validated in any way. Metadata will be
+/// written as long as the target format has a way to represent it, otherwise it is silently
+/// discarded. Currently, the following formats support metadata:
+///
+/// | Format | EXIF | XMP | ICC profile | Text |
+/// |--------|------|-----|-------------|------|
+/// | PNG    | Yes  | Yes | Yes         | Yes  |
+/// | JPEG   | Yes  | Yes | Yes         | No   |
+/// | WebP   | Yes  | Yes | Yes         | No   |
+///
+/// # Note
+/// Metadata is carried over as-is when the image is modified. If the metadata contains
+/// information that becomes inaccurate once the image is modified (for example, the EXIF
+/// dimensions after resizing the image), consider updating or clearing the metadata before
+/// encoding.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Metadata {
+    /// Raw EXIF data, starting at the TIFF header (`II*\0` or `MM\0*`). Any format-specific
+    /// prefixes, such as the `Exif\0\0` identifier in JPEG files, are not included.
+    pub exif: Option<Vec<u8>>,
+    /// The raw XMP packet, which is usually UTF-8 encoded XML.
+    pub xmp: Option<Vec<u8>>,
+    /// The raw embedded ICC color profile.
+    pub icc_profile: Option<Vec<u8>>,
+    /// Textual key-value pairs, such as those from the `tEXt`, `zTXt`, and `iTXt` chunks of PNG
+    /// images.
+    pub text: Vec<(String, String)>,
+}
+
+impl Metadata {
+    /// Creates a new, empty metadata container.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            exif: None,
+            xmp: None,
+            icc_profile: None,
+            text: Vec::new(),
+        }
+    }
+
+    /// Returns whether this container holds no metadata at all.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.exif.is_none()
+            && self.xmp.is_none()
+            && self.icc_profile.is_none()
+            && self.text.is_empty()
+    }
+
+    /// Returns the value of the first text entry with the given key, if any.
+    #[must_use]
+    pub fn text(&self, key: &str) -> Option<&str> {
+        self.text
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Parses the orientation tag from the EXIF data, if any. This is a value from 1 to 8
+    /// describing how the image must be rotated and flipped to be displayed upright, where 1 means
+    /// the image is already upright.
+    ///
+    /// Returns `None` if there is no EXIF data, the EXIF data has no orientation tag, or the
+    /// orientation tag holds an invalid value.
+    ///
+    /// # See Also
+    /// * [`Image::apply_exif_orientation`][crate::Image::apply_exif_orientation] to apply this
+    ///   orientation to an image.
+    #[must_use]
+    pub fn exif_orientation(&self) -> Option<u16> {
+        let exif = self.exif.as_deref()?;
+        let (offset, little_endian) = find_exif_orientation(exif)?;
+        let bytes = [exif[offset], exif[offset + 1]];
+        let orientation = if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        };
+
+        match orientation {
+            1..=8 => Some(orientation),
+            _ => None,
+        }
+    }
+
+    /// Parses the embedded ICC color profile, if any.
+    ///
+    /// # Errors
+    /// * `InvalidIccProfile`: The embedded profile is malformed or unsupported. See
+    ///   [`ColorProfile::from_icc`] for more information.
+    pub fn color_profile(&self) -> Option<Result<ColorProfile>> {
+        self.icc_profile.as_deref().map(ColorProfile::from_icc)
+    }
+
+    /// Overwrites the EXIF orientation tag in place, if there is one.
+    pub(crate) fn set_exif_orientation(&mut self, orientation: u16) {
+        if let Some(exif) = self.exif.as_deref_mut() {
+            if let Some((offset, little_endian)) = find_exif_orientation(exif) {
+                let bytes = if little_endian {
+                    orientation.to_le_bytes()
+                } else {
+                    orientation.to_be_bytes()
+                };
+                exif[offset..offset + 2].copy_from_slice(&bytes);
+            }
+        }
+    }
+
+    /// Sets the raw EXIF data. See [`Metadata::exif`] for more information.
+    #[must_use]
+    pub fn with_exif(mut self, exif: impl Into<Vec<u8>>) -> Self {
+        self.exif = Some(exif.into());
+        self
+    }
+
+    /// Sets the raw XMP packet.
+    #[must_use]
+    pub fn with_xmp(mut self, xmp: impl Into<Vec<u8>>) -> Self {
+        self.xmp = Some(xmp.into());
+        self
+    }
+
+    /// Sets the raw ICC color profile.
+    #[must_use]
+    pub fn with_icc_profile(mut self, icc_profile: impl Into<Vec<u8>>) -> Self {
+        self.icc_profile = Some(icc_profile.into());
+        self
+    }
+
+    /// Adds a textual key-value pair.
+    #[must_use]
+    pub fn with_text(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.text.push((key.into(), value.into()));
+        self
+    }
+}
+
+const EXIF_ORIENTATION_TAG: u16 = 0x0112;
+const EXIF_SHORT_TYPE: u16 = 3;
+
+/// Locates the value of the orientation tag within the first IFD of the given EXIF data,
+/// returning its byte offset along with whether the data is little-endian.
+fn find_exif_orientation(exif: &[u8]) -> Option<(usize, bool)> {
+    let little_endian = match exif.get(..4)? {
+        b"II*\0" => true,
+        b"MM\0*" => false,
+        _ => return None,
+    };
+    let read_u16 = |offset: usize| {
+        let bytes = [*exif.get(offset)?, *exif.get(offset + 1)?];
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let read_u32 = |offset: usize| {
+        let bytes = exif.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let ifd = read_u32(4)? as usize;
+    let entries = read_u16(ifd)? as usize;
+
+    (0..entries)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|&entry| read_u16(entry) == Some(EXIF_ORIENTATION_TAG))
+        .filter(|&entry| read_u16(entry + 2) == Some(EXIF_SHORT_TYPE))
+        .map(|entry| entry + 8)
+        .filter(|&offset| offset + 2 <= exif.len())
+        .map(|offset| (offset, little_endian))
+}
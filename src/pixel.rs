@@ -1,6 +1,8 @@
 //! Encloses pixel-related traits and pixel type implementations.
 
 use crate::{
+    blend::{self, BlendMode},
+    composite::{self, CompositeOperator},
     encodings::ColorType,
     image::OverlayMode,
     Error::{DecodingError, InvalidHexCode, InvalidPaletteIndex, UnsupportedColorType},
@@ -14,7 +16,10 @@ use std::{
 };
 
 mod sealed {
-    use super::{BitPixel, Dynamic, Luma, NoOp, PalettedRgb, PalettedRgba, Rgb, Rgba};
+    use super::{
+        BitPixel, Dynamic, Luma, Luma16, LumaA, NoOp, PalettedRgb, PalettedRgba, Rgb, Rgb16,
+        RgbF32, Rgba, Rgba16, RgbaF32,
+    };
     pub trait Sealed {}
 
     macro_rules! sealed {
@@ -31,7 +36,10 @@ mod sealed {
         const SEALED: bool = true;
     }
 
-    sealed!(NoOp BitPixel Luma Rgb Rgba Dynamic PalettedRgb<'_> PalettedRgba<'_>);
+    sealed!(
+        NoOp BitPixel Luma LumaA Rgb Rgba Luma16 Rgb16 Rgba16 RgbF32 RgbaF32 Dynamic PalettedRgb<'_>
+        PalettedRgba<'_>
+    );
 }
 
 pub use sealed::MaybeSealed;
@@ -49,7 +57,7 @@ pub trait Pixel:
     const BIT_DEPTH: u8;
 
     /// The type of a single component in the pixel.
-    type Subpixel: Copy + Into<usize>;
+    type Subpixel: Copy;
 
     /// The resolved color type of the palette. This is `Self` for non-paletted pixels.
     type Color: Pixel;
@@ -156,6 +164,8 @@ pub trait Pixel:
         match mode {
             OverlayMode::Replace => other,
             OverlayMode::Merge => self.merge(other),
+            OverlayMode::Blend(mode) => self.blend(other, mode),
+            OverlayMode::Composite(operator) => self.composite(other, operator),
         }
     }
 
@@ -173,9 +183,43 @@ pub trait Pixel:
         match mode {
             OverlayMode::Replace => other,
             OverlayMode::Merge => self.merge_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend_with_alpha(other, mode, alpha),
+            OverlayMode::Composite(operator) => self.composite_with_alpha(other, operator, alpha),
         }
     }
 
+    /// Blends the color of the given overlay pixel with the color of this pixel using the given
+    /// blend mode, then merges the result with this pixel, taking into account alpha.
+    #[must_use]
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        self.blend_with_alpha(other, mode, 255)
+    }
+
+    /// Blends the color of the given overlay pixel with the color of this pixel using the given
+    /// blend mode, where the alpha of the overlay pixel is multiplied by the given alpha.
+    ///
+    /// This is used in anti-aliasing. Pixels which cannot be blended are merged instead.
+    #[must_use]
+    fn blend_with_alpha(self, other: Self, _mode: BlendMode, alpha: u8) -> Self {
+        self.merge_with_alpha(other, alpha)
+    }
+
+    /// Composites the given overlay pixel onto this pixel using the given Porter-Duff compositing
+    /// operator.
+    #[must_use]
+    fn composite(self, other: Self, operator: CompositeOperator) -> Self {
+        self.composite_with_alpha(other, operator, 255)
+    }
+
+    /// Composites the given overlay pixel onto this pixel using the given Porter-Duff compositing
+    /// operator, where the operator only applies to the fraction of this pixel given by the alpha.
+    ///
+    /// This is used in anti-aliasing. Pixels which cannot be composited are merged instead.
+    #[must_use]
+    fn composite_with_alpha(self, other: Self, _operator: CompositeOperator, alpha: u8) -> Self {
+        self.merge_with_alpha(other, alpha)
+    }
+
     /// Creates this pixel from any dynamic pixel, dynamically at runtime. Different from the
     /// From/Into traits.
     #[allow(unused_variables)]
@@ -219,6 +263,98 @@ pub trait Alpha: Pixel {
     /// Clones this pixel with the given alpha value.
     #[must_use]
     fn with_alpha(self, alpha: u8) -> Self;
+
+    /// Converts this pixel from straight alpha into premultiplied alpha, multiplying its color
+    /// components by its alpha.
+    ///
+    /// Pixels are assumed to have straight alpha everywhere else, so this is only useful for
+    /// processing pixels yourself or for interfacing with APIs that expect premultiplied alpha.
+    /// Since the color components are rounded, this is lossy for integer subpixels, especially
+    /// when the alpha is low.
+    ///
+    /// The default implementation operates on the raw bytes of the pixel, treating the last
+    /// channel as alpha. It supports 8-bit, 16-bit, and 32-bit floating-point subpixels, and
+    /// leaves pixels of any other layout untouched.
+    #[must_use]
+    fn premultiply(self) -> Self {
+        convert_alpha_bytes(self, false)
+    }
+
+    /// Converts this pixel from premultiplied alpha back into straight alpha, dividing its color
+    /// components by its alpha. Fully transparent pixels become fully transparent black.
+    ///
+    /// See [`Self::premultiply`] for the default implementation.
+    #[must_use]
+    fn unpremultiply(self) -> Self {
+        convert_alpha_bytes(self, true)
+    }
+}
+
+/// Converts the given pixel between straight and premultiplied alpha through its raw bytes, where
+/// the last channel is alpha. This is the default implementation of [`Alpha::premultiply`] and
+/// [`Alpha::unpremultiply`].
+#[allow(clippy::cast_possible_truncation)]
+fn convert_alpha_bytes<P: Pixel>(pixel: P, unpremultiply: bool) -> P {
+    let color_type = P::COLOR_TYPE;
+    if !color_type.has_alpha() || color_type.is_paletted() || color_type.is_dynamic() {
+        return pixel;
+    }
+
+    let convert = |value: u32, alpha: u32, max: u32| {
+        if unpremultiply {
+            unpremultiply_component(value, alpha, max)
+        } else {
+            premultiply_component(value, alpha, max)
+        }
+    };
+    let mut bytes = pixel.as_bytes().as_ref().to_vec();
+    let colors_len = bytes.len() - bytes.len() / color_type.channels();
+    let (colors, alpha) = bytes.split_at_mut(colors_len);
+
+    match *alpha {
+        [a] => {
+            for value in colors {
+                *value = convert(u32::from(*value), u32::from(a), 255) as u8;
+            }
+        }
+        [a0, a1] => {
+            let a = u32::from(u16::from_be_bytes([a0, a1]));
+            for chunk in colors.chunks_exact_mut(2) {
+                let value = u32::from(u16::from_be_bytes([chunk[0], chunk[1]]));
+                chunk.copy_from_slice(&(convert(value, a, 65535) as u16).to_be_bytes());
+            }
+        }
+        [a0, a1, a2, a3] => {
+            let a = f32::from_be_bytes([a0, a1, a2, a3]);
+            for chunk in colors.chunks_exact_mut(4) {
+                let value = f32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                let value = match (unpremultiply, a > 0.0) {
+                    (false, _) => value * a,
+                    (true, true) => value / a,
+                    (true, false) => 0.0,
+                };
+                chunk.copy_from_slice(&value.to_be_bytes());
+            }
+        }
+        _ => return pixel,
+    }
+    P::from_bytes(&bytes)
+}
+
+/// Multiplies the given color component by the given alpha, where both have the given maximum
+/// value.
+const fn premultiply_component(value: u32, alpha: u32, max: u32) -> u32 {
+    (value * alpha + max / 2) / max
+}
+
+/// Divides the given premultiplied color component by the given alpha, where both have the given
+/// maximum value.
+fn unpremultiply_component(value: u32, alpha: u32, max: u32) -> u32 {
+    if alpha == 0 {
+        return 0;
+    }
+
+    ((value * max + alpha / 2) / alpha).min(max)
 }
 
 /// A pixel type that does and stores nothing. This pixel type is useless and will behave weirdly
@@ -294,6 +430,14 @@ impl Alpha for NoOp {
     fn with_alpha(self, _alpha: u8) -> Self {
         Self
     }
+
+    fn premultiply(self) -> Self {
+        Self
+    }
+
+    fn unpremultiply(self) -> Self {
+        Self
+    }
 }
 
 impl TrueColor for NoOp {
@@ -422,10 +566,23 @@ impl Pixel for BitPixel {
         }
     }
 
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        Luma::from(self)
+            .blend_with_alpha(Luma::from(other), mode, alpha)
+            .into()
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        Luma::from(self)
+            .composite_with_alpha(Luma::from(other), operator, alpha)
+            .into()
+    }
+
     fn from_dynamic(dynamic: Dynamic) -> Self {
         match dynamic {
             Dynamic::BitPixel(value) => value,
             Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
             Dynamic::Rgb(value) => value.into(),
             Dynamic::Rgba(value) => value.into(),
         }
@@ -464,55 +621,46 @@ macro_rules! scale_subpixels {
         if $src_depth == $target_depth {
             Cow::from($data)
         } else {
-            if !$src_depth.is_power_of_two() {
+            if !$src_depth.is_power_of_two() || $src_depth > 16 {
                 return Err(DecodingError(format!(
-                    "source depth {} is not a power of two",
+                    "source depth {} is not a power of two up to 16",
                     $src_depth
                 )));
             }
             debug_assert!(
-                $target_depth.is_power_of_two(),
-                "target depth {} is not a power of two",
+                $target_depth.is_power_of_two() && $target_depth <= 16,
+                "target depth {} is not a power of two up to 16",
                 $target_depth,
             );
 
-            if $src_depth <= 8 && $target_depth <= 8 {
-                Cow::from(if $src_depth < $target_depth {
-                    let scale = $target_depth / $src_depth;
-                    $data.iter().map(|n| *n * scale).collect::<Vec<_>>()
-                } else {
-                    let scale = $src_depth / $target_depth;
-                    $data.iter().map(|n| *n / scale).collect::<Vec<_>>()
-                })
-            } else if $src_depth < $target_depth {
-                let scale = $target_depth as usize / $src_depth as usize;
-                let mut result = Vec::with_capacity($data.len() * scale);
-
-                for n in $data {
-                    result.extend((*n as usize * scale).to_be_bytes());
-                }
-
-                Cow::from(result)
-            } else {
-                let scale = $src_depth as usize / $target_depth as usize;
-                let mut result = Vec::with_capacity($data.len() / scale);
-
-                for chunk in $data.chunks_exact(scale) {
-                    let sum = chunk
-                        .iter()
-                        .rev()
-                        .enumerate()
-                        .map(|(i, &x)| (x as usize) << (8 * i))
-                        .sum::<usize>();
-                    result.push((sum / scale) as u8);
-                }
-
-                Cow::from(result)
-            }
+            Cow::from(rescale_subpixels($src_depth, $target_depth, $data))
         }
     }};
 }
 
+/// Rescales every subpixel in the given data from the source bit depth to the target bit depth.
+///
+/// 16-bit subpixels are represented as two big-endian bytes, while subpixels of any other depth
+/// are represented as a single byte each.
+#[allow(clippy::cast_possible_truncation)]
+fn rescale_subpixels(src_depth: u8, target_depth: u8, data: &[u8]) -> Vec<u8> {
+    let src_max = (1_u32 << src_depth) - 1;
+    let target_max = (1_u32 << target_depth) - 1;
+    let scale = |n: u32| (n.min(src_max) * target_max + src_max / 2) / src_max;
+
+    if src_depth == 16 {
+        data.chunks_exact(2)
+            .map(|n| scale(u32::from(u16::from_be_bytes([n[0], n[1]]))) as u8)
+            .collect()
+    } else if target_depth == 16 {
+        data.iter()
+            .flat_map(|&n| (scale(u32::from(n)) as u16).to_be_bytes())
+            .collect()
+    } else {
+        data.iter().map(|&n| scale(u32::from(n)) as u8).collect()
+    }
+}
+
 macro_rules! propagate_data {
     ($data:expr, $expected:expr) => {{
         if $data.len() < $expected {
@@ -583,22 +731,39 @@ impl Pixel for Luma {
         [self.0]
     }
 
-    #[allow(clippy::cast_lossless)]
     fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
-        let alpha = alpha as f32 / 255.;
-        let base_l = self.0 as f32 / 255.;
-        let overlay_l = other.0 as f32 / 255.;
+        let alpha = f32::from(alpha) / 255.;
+        let l = f32::from(other.0).mul_add(alpha, f32::from(self.0) * (1. - alpha));
+
+        Self(l.round() as u8)
+    }
+
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        let [l, ..] = blend::composite8(
+            [self.0, self.0, self.0, 255],
+            [other.0, other.0, other.0, 255],
+            mode,
+            alpha,
+        );
+
+        Self(l)
+    }
 
-        let a_diff = 1. - alpha;
-        let a = a_diff.mul_add(255., alpha);
-        let l = (a_diff * 255.).mul_add(base_l, alpha * overlay_l) / a;
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        let [l, ..] = composite::composite8(
+            [self.0, self.0, self.0, 255],
+            [other.0, other.0, other.0, 255],
+            operator,
+            alpha,
+        );
 
-        Self((l * 255.) as u8)
+        Self(l)
     }
 
     fn from_dynamic(dynamic: Dynamic) -> Self {
         match dynamic {
             Dynamic::Luma(value) => value,
+            Dynamic::LumaA(value) => value.into(),
             Dynamic::BitPixel(value) => value.into(),
             Dynamic::Rgb(value) => value.into(),
             Dynamic::Rgba(value) => value.into(),
@@ -646,6 +811,237 @@ impl Luma {
     }
 }
 
+/// Represents a luminance pixel with an additional alpha channel, for transparency.
+///
+/// This is the grayscale counterpart of [`Rgba`] and only takes up two bytes per pixel.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LumaA {
+    /// The luminance component of the pixel.
+    pub l: u8,
+    /// The alpha component of the pixel.
+    pub a: u8,
+}
+
+impl Pixel for LumaA {
+    const COLOR_TYPE: ColorType = ColorType::LumaA;
+    const BIT_DEPTH: u8 = 8;
+
+    type Subpixel = u8;
+    type Color = Self;
+    type Data = [u8; 2];
+
+    fn map_subpixels<F, A>(self, f: F, a: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            l: f(self.l),
+            a: a(self.a),
+        }
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        propagate_palette!(palette, data);
+        let data = scale_subpixels!(bit_depth, Self::BIT_DEPTH, data);
+
+        match color_type {
+            ColorType::Luma => {
+                propagate_data!(data, 1);
+                Ok(Self::new(data[0], 255))
+            }
+            ColorType::LumaA => {
+                propagate_data!(data, 2);
+                Ok(Self::new(data[0], data[1]))
+            }
+            ColorType::Rgb => {
+                propagate_data!(data, 3);
+                Ok(Rgb::new(data[0], data[1], data[2]).into())
+            }
+            ColorType::Rgba => {
+                propagate_data!(data, 4);
+                Ok(Rgba::new(data[0], data[1], data[2], data[3]).into())
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            l: bytes[0],
+            a: bytes[1],
+        }
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        [self.l, self.a]
+    }
+
+    #[allow(clippy::cast_lossless)]
+    fn merge(self, other: Self) -> Self {
+        // Optimize for common cases
+        if other.a == 255 {
+            return other;
+        } else if other.a == 0 {
+            return self;
+        }
+
+        let (base_l, base_a) = (self.l as f32 / 255., self.a as f32 / 255.);
+        let (overlay_l, overlay_a) = (other.l as f32 / 255., other.a as f32 / 255.);
+
+        let a_diff = 1. - overlay_a;
+        let a = a_diff.mul_add(base_a, overlay_a);
+        let l = (a_diff * base_a).mul_add(base_l, overlay_a * overlay_l) / a;
+
+        Self {
+            l: (l * 255.) as u8,
+            a: (a * 255.) as u8,
+        }
+    }
+
+    #[allow(clippy::cast_lossless)]
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        self.merge(other.with_alpha((other.a as f32 * (alpha as f32 / 255.)) as u8))
+    }
+
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        let [l, _, _, a] = blend::composite8(
+            [self.l, self.l, self.l, self.a],
+            [other.l, other.l, other.l, other.a],
+            mode,
+            alpha,
+        );
+
+        Self { l, a }
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        let [l, _, _, a] = composite::composite8(
+            [self.l, self.l, self.l, self.a],
+            [other.l, other.l, other.l, other.a],
+            operator,
+            alpha,
+        );
+
+        Self { l, a }
+    }
+
+    fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
+        match mode {
+            OverlayMode::Replace => other.with_alpha(alpha),
+            OverlayMode::Merge => self.merge_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend_with_alpha(other, mode, alpha),
+            OverlayMode::Composite(operator) => self.composite_with_alpha(other, operator, alpha),
+        }
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        match dynamic {
+            Dynamic::LumaA(value) => value,
+            Dynamic::Luma(value) => value.into(),
+            Dynamic::BitPixel(value) => value.into(),
+            Dynamic::Rgb(value) => value.into(),
+            Dynamic::Rgba(value) => value.into(),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Alpha for LumaA {
+    fn alpha(&self) -> u8 {
+        self.a
+    }
+
+    fn with_alpha(mut self, alpha: u8) -> Self {
+        self.a = alpha;
+        self
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn premultiply(self) -> Self {
+        Self {
+            l: premultiply_component(self.l.into(), self.a.into(), 255) as u8,
+            ..self
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn unpremultiply(self) -> Self {
+        Self {
+            l: unpremultiply_component(self.l.into(), self.a.into(), 255) as u8,
+            ..self
+        }
+    }
+}
+
+impl Not for LumaA {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            l: !self.l,
+            a: !self.a,
+        }
+    }
+}
+
+impl IntoVector<2> for LumaA {
+    type Element = u8;
+
+    fn into_vector(self) -> Vector<2, Self::Element> {
+        Vector::new([self.l, self.a])
+    }
+}
+
+impl FromVector<2> for LumaA {
+    type Element = u8;
+
+    fn from_vector(vector: Vector<2, Self::Element>) -> Self {
+        Self {
+            l: vector[0],
+            a: vector[1],
+        }
+    }
+}
+
+impl LumaA {
+    /// Creates a new LumaA pixel with the given luminance and alpha values.
+    #[must_use]
+    pub const fn new(l: u8, a: u8) -> Self {
+        Self { l, a }
+    }
+
+    /// Creates an opaque pixel from a [`Luma`] pixel.
+    #[must_use]
+    pub const fn from_luma(Luma(l): Luma) -> Self {
+        Self::new(l, 255)
+    }
+
+    /// Creates a completely transparent pixel.
+    #[must_use]
+    pub const fn transparent() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Creates an opaque black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0, 255)
+    }
+
+    /// Creates an opaque white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(255, 255)
+    }
+}
+
 /// Represents an RGB pixel.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Rgb {
@@ -725,12 +1121,25 @@ impl Pixel for Rgb {
             .into()
     }
 
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        Rgba::from_rgb(self)
+            .blend_with_alpha(Rgba::from_rgb(other), mode, alpha)
+            .into()
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        Rgba::from_rgb(self)
+            .composite_with_alpha(Rgba::from_rgb(other), operator, alpha)
+            .into()
+    }
+
     fn from_dynamic(dynamic: Dynamic) -> Self {
         match dynamic {
             Dynamic::Rgb(value) => value,
             Dynamic::Rgba(value) => value.into(),
             Dynamic::BitPixel(value) => value.into(),
             Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
         }
     }
 
@@ -1005,18 +1414,1226 @@ impl Pixel for Rgba {
         self.merge(other.with_alpha((other.a as f32 * (alpha as f32 / 255.)) as u8))
     }
 
-    fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
-        match mode {
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        let [r, g, b, a] = blend::composite8(
+            [self.r, self.g, self.b, self.a],
+            [other.r, other.g, other.b, other.a],
+            mode,
+            alpha,
+        );
+
+        Self { r, g, b, a }
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        let [r, g, b, a] = composite::composite8(
+            [self.r, self.g, self.b, self.a],
+            [other.r, other.g, other.b, other.a],
+            operator,
+            alpha,
+        );
+
+        Self { r, g, b, a }
+    }
+
+    fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
+        match mode {
+            OverlayMode::Replace => other.with_alpha(alpha),
+            OverlayMode::Merge => self.merge_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend_with_alpha(other, mode, alpha),
+            OverlayMode::Composite(operator) => self.composite_with_alpha(other, operator, alpha),
+        }
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        match dynamic {
+            Dynamic::Rgba(value) => value,
+            Dynamic::Rgb(value) => value.into(),
+            Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
+            Dynamic::BitPixel(value) => value.into(),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Alpha for Rgba {
+    fn alpha(&self) -> u8 {
+        self.a
+    }
+
+    fn with_alpha(mut self, alpha: u8) -> Self {
+        self.a = alpha;
+        self
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn premultiply(self) -> Self {
+        let multiply = |c: u8| premultiply_component(c.into(), self.a.into(), 255) as u8;
+
+        Self {
+            r: multiply(self.r),
+            g: multiply(self.g),
+            b: multiply(self.b),
+            a: self.a,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn unpremultiply(self) -> Self {
+        let divide = |c: u8| unpremultiply_component(c.into(), self.a.into(), 255) as u8;
+
+        Self {
+            r: divide(self.r),
+            g: divide(self.g),
+            b: divide(self.b),
+            a: self.a,
+        }
+    }
+}
+
+impl Rgba {
+    /// Creates a new RGBA pixel.
+    #[must_use]
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates an opaque pixel from an RGB pixel.
+    #[must_use]
+    pub const fn from_rgb(Rgb { r, g, b }: Rgb) -> Self {
+        Self::new(r, g, b, 255)
+    }
+
+    /// Parses an RGBA pixel from a hex string.
+    ///
+    /// The hex value can be in one of the following formats:
+    /// - RRGGBBAA
+    /// - RRGGBB
+    /// - RGBA
+    /// - RGB
+    ///
+    /// These can be optionally padded with #, for example "#FF0000" is the same as as "FF0000".
+    ///
+    /// # Note
+    /// This is a relatively expensive operation. Use [`Rgba::from_u32`] if you know the hex value
+    /// (i.e. if you are defining a constant), since you can write a `u32` using an integer literal
+    /// of radix 16, e.g. `0xff0000ff`.
+    ///
+    /// # Errors
+    /// * Received a malformed hex code.
+    ///
+    /// # See Also
+    /// * [`Rgba::from_u32`] for parsing from an integer (better for known hex values).
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+        match hex.len() {
+            3 | 6 => Rgb::from_hex(hex).map(Self::from_rgb),
+            len @ (4 | 8) => {
+                let hex = if len == 4 {
+                    let mut expanded = String::with_capacity(8);
+
+                    for c in hex.chars() {
+                        expanded.push(c);
+                        expanded.push(c);
+                    }
+
+                    expanded
+                } else {
+                    hex.to_string()
+                };
+
+                let err = |_| InvalidHexCode(hex.to_string());
+
+                Ok(Self {
+                    r: u8::from_str_radix(&hex[0..2], 16).map_err(err)?,
+                    g: u8::from_str_radix(&hex[2..4], 16).map_err(err)?,
+                    b: u8::from_str_radix(&hex[4..6], 16).map_err(err)?,
+                    a: u8::from_str_radix(&hex[6..8], 16).map_err(err)?,
+                })
+            }
+            _ => Err(InvalidHexCode(hex.to_string())),
+        }
+    }
+
+    /// Resolves the RGBA pixel from a 32-bit integer (0xRRGGBBAA).
+    ///
+    /// Consider using [`Rgb::from_u32`] and then [`TrueColor::into_rgba`] if you have a
+    /// conventional 24-bit RGB integer, as this method will not properly handle that.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ril::prelude::*;
+    /// const RED: Rgba = Rgba::from_u32(0xff0000ff);
+    /// assert_eq!(RED, Rgba::new(255, 0, 0, 255));
+    /// ```
+    #[must_use]
+    pub const fn from_u32(value: u32) -> Self {
+        Self {
+            r: (value >> 24) as u8,
+            g: (value >> 16) as u8,
+            b: (value >> 8) as u8,
+            a: value as u8,
+        }
+    }
+
+    /// Creates a completely transparent pixel.
+    #[must_use]
+    pub const fn transparent() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+
+    /// Creates an opaque black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0, 0, 0, 255)
+    }
+
+    /// Creates an opaque white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(255, 255, 255, 255)
+    }
+}
+
+impl Not for Rgba {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: !self.r,
+            g: !self.g,
+            b: !self.b,
+            a: !self.a,
+        }
+    }
+}
+
+impl IntoVector<4> for Rgba {
+    type Element = u8;
+
+    fn into_vector(self) -> Vector<4, Self::Element> {
+        Vector::new([self.r, self.g, self.b, self.a])
+    }
+}
+
+impl FromVector<4> for Rgba {
+    type Element = u8;
+
+    fn from_vector(vector: Vector<4, Self::Element>) -> Self {
+        Self {
+            r: vector[0],
+            g: vector[1],
+            b: vector[2],
+            a: vector[3],
+        }
+    }
+}
+
+/// Widens an 8-bit subpixel into a 16-bit subpixel.
+const fn widen(value: u8) -> u16 {
+    value as u16 * 257
+}
+
+/// Narrows a 16-bit subpixel into an 8-bit subpixel, rounding to the nearest value.
+#[allow(clippy::cast_possible_truncation)]
+const fn narrow(value: u16) -> u8 {
+    ((value as u32 + 128) / 257) as u8
+}
+
+macro_rules! propagate_data16 {
+    ($data:expr, $expected:expr) => {{
+        if $data.len() < $expected * 2 {
+            return Err(DecodingError(format!(
+                "malformed pixel data for {}: expected at least {} 16-bit component(s) but \
+                received {} byte(s)",
+                std::any::type_name::<Self>(),
+                $expected,
+                $data.len(),
+            )));
+        }
+    }};
+}
+
+/// Reads the big-endian 16-bit subpixel at the given index.
+fn subpixel16(data: &[u8], index: usize) -> u16 {
+    u16::from_be_bytes([data[index * 2], data[index * 2 + 1]])
+}
+
+/// Represents a 16-bit luminance pixel. This is the 16-bit counterpart of [`Luma`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Luma16(
+    /// The luminance value of the pixel, between 0 and 65535.
+    pub u16,
+);
+
+impl Pixel for Luma16 {
+    const COLOR_TYPE: ColorType = ColorType::Luma;
+    const BIT_DEPTH: u8 = 16;
+
+    type Subpixel = u16;
+    type Color = Self;
+    type Data = [u8; 2];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self(f(self.0))
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        propagate_palette!(palette, data);
+
+        let data = scale_subpixels!(bit_depth, Self::BIT_DEPTH, data);
+        propagate_data16!(data, 1);
+
+        match color_type {
+            ColorType::Luma | ColorType::LumaA => Ok(Self(subpixel16(&data, 0))),
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self(subpixel16(bytes, 0))
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        self.0.to_be_bytes()
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        let alpha = f32::from(alpha) / 255.;
+        let l = f32::from(other.0).mul_add(alpha, f32::from(self.0) * (1. - alpha));
+
+        Self(l.round() as u16)
+    }
+
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        let [l, ..] = blend::composite16(
+            [self.0, self.0, self.0, u16::MAX],
+            [other.0, other.0, other.0, u16::MAX],
+            mode,
+            alpha,
+        );
+
+        Self(l)
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        let [l, ..] = composite::composite16(
+            [self.0, self.0, self.0, u16::MAX],
+            [other.0, other.0, other.0, u16::MAX],
+            operator,
+            alpha,
+        );
+
+        Self(l)
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        match dynamic {
+            Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
+            Dynamic::BitPixel(value) => value.into(),
+            Dynamic::Rgb(value) => value.into(),
+            Dynamic::Rgba(value) => value.into(),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Not for Luma16 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(!self.0)
+    }
+}
+
+impl IntoVector<1> for Luma16 {
+    type Element = u16;
+
+    fn into_vector(self) -> Vector<1, Self::Element> {
+        Vector::new([self.0])
+    }
+}
+
+impl FromVector<1> for Luma16 {
+    type Element = u16;
+
+    fn from_vector(vector: Vector<1, Self::Element>) -> Self {
+        Self(vector[0])
+    }
+}
+
+impl Luma16 {
+    /// Creates a new 16-bit Luma pixel with the given luminance value.
+    #[must_use]
+    pub const fn new(l: u16) -> Self {
+        Self(l)
+    }
+
+    /// Returns the luminance value of the pixel.
+    #[must_use]
+    pub const fn value(&self) -> u16 {
+        self.0
+    }
+}
+
+/// Represents an RGB pixel with 16 bits per channel. This is the 16-bit counterpart of [`Rgb`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb16 {
+    /// The red component of the pixel.
+    pub r: u16,
+    /// The green component of the pixel.
+    pub g: u16,
+    /// The blue component of the pixel.
+    pub b: u16,
+}
+
+impl Pixel for Rgb16 {
+    const COLOR_TYPE: ColorType = ColorType::Rgb;
+    const BIT_DEPTH: u8 = 16;
+
+    type Subpixel = u16;
+    type Color = Self;
+    type Data = [u8; 6];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+        }
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        propagate_palette!(palette, data);
+        let data = scale_subpixels!(bit_depth, Self::BIT_DEPTH, data);
+
+        match color_type {
+            ColorType::Rgb | ColorType::Rgba => {
+                propagate_data16!(data, 3);
+                Ok(Self::from_bytes(&data))
+            }
+            ColorType::Luma | ColorType::LumaA => {
+                propagate_data16!(data, 1);
+                let l = subpixel16(&data, 0);
+
+                Ok(Self { r: l, g: l, b: l })
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            r: subpixel16(bytes, 0),
+            g: subpixel16(bytes, 1),
+            b: subpixel16(bytes, 2),
+        }
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let ([r0, r1], [g0, g1], [b0, b1]) = (
+            self.r.to_be_bytes(),
+            self.g.to_be_bytes(),
+            self.b.to_be_bytes(),
+        );
+
+        [r0, r1, g0, g1, b0, b1]
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        Rgba16::from_rgb(self)
+            .merge_with_alpha(Rgba16::from_rgb(other), alpha)
+            .into()
+    }
+
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        Rgba16::from_rgb(self)
+            .blend_with_alpha(Rgba16::from_rgb(other), mode, alpha)
+            .into()
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        Rgba16::from_rgb(self)
+            .composite_with_alpha(Rgba16::from_rgb(other), operator, alpha)
+            .into()
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        match dynamic {
+            Dynamic::Rgb(value) => value.into(),
+            Dynamic::Rgba(value) => value.into(),
+            Dynamic::BitPixel(value) => value.into(),
+            Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Not for Rgb16 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: !self.r,
+            g: !self.g,
+            b: !self.b,
+        }
+    }
+}
+
+impl IntoVector<3> for Rgb16 {
+    type Element = u16;
+
+    fn into_vector(self) -> Vector<3, Self::Element> {
+        Vector::new([self.r, self.g, self.b])
+    }
+}
+
+impl FromVector<3> for Rgb16 {
+    type Element = u16;
+
+    fn from_vector(vector: Vector<3, Self::Element>) -> Self {
+        Self {
+            r: vector[0],
+            g: vector[1],
+            b: vector[2],
+        }
+    }
+}
+
+impl Rgb16 {
+    /// Creates a new 16-bit RGB pixel.
+    #[must_use]
+    pub const fn new(r: u16, g: u16, b: u16) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a completely black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    /// Creates a completely white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(u16::MAX, u16::MAX, u16::MAX)
+    }
+}
+
+/// Represents an RGBA pixel with 16 bits per channel. This is the 16-bit counterpart of [`Rgba`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgba16 {
+    /// The red component of the pixel.
+    pub r: u16,
+    /// The green component of the pixel.
+    pub g: u16,
+    /// The blue component of the pixel.
+    pub b: u16,
+    /// The alpha component of the pixel.
+    pub a: u16,
+}
+
+impl Pixel for Rgba16 {
+    const COLOR_TYPE: ColorType = ColorType::Rgba;
+    const BIT_DEPTH: u8 = 16;
+
+    type Subpixel = u16;
+    type Color = Self;
+    type Data = [u8; 8];
+
+    fn map_subpixels<F, A>(self, f: F, a: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+            a: a(self.a),
+        }
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        propagate_palette!(palette, data);
+        let data = scale_subpixels!(bit_depth, Self::BIT_DEPTH, data);
+
+        match color_type {
+            ColorType::Rgb => {
+                propagate_data16!(data, 3);
+                Ok(Self::from_rgb(Rgb16::from_bytes(&data)))
+            }
+            ColorType::Rgba => {
+                propagate_data16!(data, 4);
+                Ok(Self::from_bytes(&data))
+            }
+            ColorType::Luma => {
+                propagate_data16!(data, 1);
+                let l = subpixel16(&data, 0);
+
+                Ok(Self::new(l, l, l, u16::MAX))
+            }
+            ColorType::LumaA => {
+                propagate_data16!(data, 2);
+                let l = subpixel16(&data, 0);
+
+                Ok(Self::new(l, l, l, subpixel16(&data, 1)))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            r: subpixel16(bytes, 0),
+            g: subpixel16(bytes, 1),
+            b: subpixel16(bytes, 2),
+            a: subpixel16(bytes, 3),
+        }
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let ([r0, r1], [g0, g1], [b0, b1], [a0, a1]) = (
+            self.r.to_be_bytes(),
+            self.g.to_be_bytes(),
+            self.b.to_be_bytes(),
+            self.a.to_be_bytes(),
+        );
+
+        [r0, r1, g0, g1, b0, b1, a0, a1]
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn merge(self, other: Self) -> Self {
+        const MAX: f32 = u16::MAX as f32;
+
+        // Optimize for common cases
+        if other.a == u16::MAX {
+            return other;
+        } else if other.a == 0 {
+            return self;
+        }
+
+        let base_a = f32::from(self.a) / MAX;
+        let overlay_a = f32::from(other.a) / MAX;
+
+        let a_diff = 1. - overlay_a;
+        let a = a_diff.mul_add(base_a, overlay_a);
+        let a_ratio = a_diff * base_a;
+
+        let blend = |base: u16, overlay: u16| {
+            let value = a_ratio.mul_add(f32::from(base), overlay_a * f32::from(overlay)) / a;
+            value.round() as u16
+        };
+
+        Self {
+            r: blend(self.r, other.r),
+            g: blend(self.g, other.g),
+            b: blend(self.b, other.b),
+            a: (a * MAX).round() as u16,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        self.merge(Self {
+            a: (f32::from(other.a) * (f32::from(alpha) / 255.)).round() as u16,
+            ..other
+        })
+    }
+
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        let [r, g, b, a] = blend::composite16(
+            [self.r, self.g, self.b, self.a],
+            [other.r, other.g, other.b, other.a],
+            mode,
+            alpha,
+        );
+
+        Self { r, g, b, a }
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        let [r, g, b, a] = composite::composite16(
+            [self.r, self.g, self.b, self.a],
+            [other.r, other.g, other.b, other.a],
+            operator,
+            alpha,
+        );
+
+        Self { r, g, b, a }
+    }
+
+    fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
+        match mode {
+            OverlayMode::Replace => other.with_alpha(alpha),
+            OverlayMode::Merge => self.merge_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend_with_alpha(other, mode, alpha),
+            OverlayMode::Composite(operator) => self.composite_with_alpha(other, operator, alpha),
+        }
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        match dynamic {
+            Dynamic::Rgba(value) => value.into(),
+            Dynamic::Rgb(value) => value.into(),
+            Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
+            Dynamic::BitPixel(value) => value.into(),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Alpha for Rgba16 {
+    /// Returns the alpha value of the pixel, narrowed to 8 bits. Use the `a` field directly to
+    /// access the full 16-bit value.
+    fn alpha(&self) -> u8 {
+        narrow(self.a)
+    }
+
+    fn with_alpha(mut self, alpha: u8) -> Self {
+        self.a = widen(alpha);
+        self
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn premultiply(self) -> Self {
+        let max = u16::MAX.into();
+        let multiply = |c: u16| premultiply_component(c.into(), self.a.into(), max) as u16;
+
+        Self {
+            r: multiply(self.r),
+            g: multiply(self.g),
+            b: multiply(self.b),
+            a: self.a,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn unpremultiply(self) -> Self {
+        let max = u16::MAX.into();
+        let divide = |c: u16| unpremultiply_component(c.into(), self.a.into(), max) as u16;
+
+        Self {
+            r: divide(self.r),
+            g: divide(self.g),
+            b: divide(self.b),
+            a: self.a,
+        }
+    }
+}
+
+impl Rgba16 {
+    /// Creates a new 16-bit RGBA pixel.
+    #[must_use]
+    pub const fn new(r: u16, g: u16, b: u16, a: u16) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates an opaque pixel from a 16-bit RGB pixel.
+    #[must_use]
+    pub const fn from_rgb(Rgb16 { r, g, b }: Rgb16) -> Self {
+        Self::new(r, g, b, u16::MAX)
+    }
+
+    /// Creates a completely transparent pixel.
+    #[must_use]
+    pub const fn transparent() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+
+    /// Creates an opaque black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0, 0, 0, u16::MAX)
+    }
+
+    /// Creates an opaque white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(u16::MAX, u16::MAX, u16::MAX, u16::MAX)
+    }
+}
+
+impl Not for Rgba16 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: !self.r,
+            g: !self.g,
+            b: !self.b,
+            a: !self.a,
+        }
+    }
+}
+
+impl IntoVector<4> for Rgba16 {
+    type Element = u16;
+
+    fn into_vector(self) -> Vector<4, Self::Element> {
+        Vector::new([self.r, self.g, self.b, self.a])
+    }
+}
+
+impl FromVector<4> for Rgba16 {
+    type Element = u16;
+
+    fn from_vector(vector: Vector<4, Self::Element>) -> Self {
+        Self {
+            r: vector[0],
+            g: vector[1],
+            b: vector[2],
+            a: vector[3],
+        }
+    }
+}
+
+/// Converts a gamma-encoded sRGB component between 0.0 and 1.0 into linear light.
+#[must_use]
+pub(crate) fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light component between 0.0 and 1.0 into a gamma-encoded sRGB component.
+#[must_use]
+pub(crate) fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055f32.mul_add(value.powf(1. / 2.4), -0.055)
+    }
+}
+
+/// Quantizes a floating-point component between 0.0 and 1.0 into an integer component with the
+/// given maximum value, clamping it if it is out of range.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn quantize_component(value: f32, max: f32) -> u32 {
+    (value.clamp(0., 1.) * max).round() as u32
+}
+
+macro_rules! propagate_data32 {
+    ($data:expr, $expected:expr) => {{
+        if $data.len() < $expected * 4 {
+            return Err(DecodingError(format!(
+                "malformed pixel data for {}: expected at least {} 32-bit component(s) but \
+                received {} byte(s)",
+                std::any::type_name::<Self>(),
+                $expected,
+                $data.len(),
+            )));
+        }
+    }};
+}
+
+/// Reads the big-endian 32-bit floating-point subpixel at the given index.
+fn subpixel32(data: &[u8], index: usize) -> f32 {
+    let i = index * 4;
+    f32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]])
+}
+
+/// Implements `PartialEq`, `Eq`, and `Hash` for floating-point pixels by comparing the bit
+/// representations of their components.
+macro_rules! impl_float_eq {
+    ($t:ident: $($field:ident)+) => {
+        impl PartialEq for $t {
+            fn eq(&self, other: &Self) -> bool {
+                $(self.$field.to_bits() == other.$field.to_bits())&&+
+            }
+        }
+
+        impl Eq for $t {}
+
+        impl Hash for $t {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                $(self.$field.to_bits().hash(state);)+
+            }
+        }
+    };
+}
+
+/// Represents an RGB pixel with 32-bit floating-point channels in linear light.
+///
+/// Components are nominally between `0.0` and `1.0`, although values outside of this range are
+/// preserved, which allows for high dynamic range images and lossless intermediate results when
+/// chaining many operations. Values are only clamped when converting into an integer pixel type.
+///
+/// Unlike [`Rgb`], the components are **not** gamma-encoded. Conversions to and from [`Rgb`] (and
+/// other integer pixel types) apply the sRGB transfer function.
+///
+/// # Equality
+/// Equality and hashing compare the bit representation of each component, so `NaN` is equal to
+/// itself while `0.0` and `-0.0` are not equal.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RgbF32 {
+    /// The red component of the pixel.
+    pub r: f32,
+    /// The green component of the pixel.
+    pub g: f32,
+    /// The blue component of the pixel.
+    pub b: f32,
+}
+
+impl_float_eq!(RgbF32: r g b);
+
+impl Pixel for RgbF32 {
+    const COLOR_TYPE: ColorType = ColorType::Rgb;
+    const BIT_DEPTH: u8 = 32;
+
+    type Subpixel = f32;
+    type Color = Self;
+    type Data = [u8; 12];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+        }
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        propagate_palette!(palette, data);
+        // Integer data is gamma-encoded, so it is converted through the sRGB transfer function
+        if bit_depth != Self::BIT_DEPTH {
+            return Rgb16::from_raw_parts(color_type, bit_depth, data).map(Into::into);
+        }
+
+        match color_type {
+            ColorType::Rgb | ColorType::Rgba => {
+                propagate_data32!(data, 3);
+                Ok(Self::from_bytes(data))
+            }
+            ColorType::Luma | ColorType::LumaA => {
+                propagate_data32!(data, 1);
+                let l = subpixel32(data, 0);
+
+                Ok(Self::new(l, l, l))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            r: subpixel32(bytes, 0),
+            g: subpixel32(bytes, 1),
+            b: subpixel32(bytes, 2),
+        }
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let mut bytes = [0; 12];
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip([self.r, self.g, self.b]) {
+            chunk.copy_from_slice(&value.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        RgbaF32::from_rgb(self)
+            .merge_with_alpha(RgbaF32::from_rgb(other), alpha)
+            .into()
+    }
+
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        RgbaF32::from_rgb(self)
+            .blend_with_alpha(RgbaF32::from_rgb(other), mode, alpha)
+            .into()
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        RgbaF32::from_rgb(self)
+            .composite_with_alpha(RgbaF32::from_rgb(other), operator, alpha)
+            .into()
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        match dynamic {
+            Dynamic::Rgb(value) => value.into(),
+            Dynamic::Rgba(value) => value.into(),
+            Dynamic::BitPixel(value) => value.into(),
+            Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Not for RgbF32 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: 1. - self.r,
+            g: 1. - self.g,
+            b: 1. - self.b,
+        }
+    }
+}
+
+impl IntoVector<3> for RgbF32 {
+    type Element = f32;
+
+    fn into_vector(self) -> Vector<3, Self::Element> {
+        Vector::new([self.r, self.g, self.b])
+    }
+}
+
+impl FromVector<3> for RgbF32 {
+    type Element = f32;
+
+    fn from_vector(vector: Vector<3, Self::Element>) -> Self {
+        Self {
+            r: vector[0],
+            g: vector[1],
+            b: vector[2],
+        }
+    }
+}
+
+impl RgbF32 {
+    /// Creates a new floating-point RGB pixel from linear light components.
+    #[must_use]
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a completely black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0., 0., 0.)
+    }
+
+    /// Creates a completely white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(1., 1., 1.)
+    }
+}
+
+/// Represents an RGBA pixel with 32-bit floating-point channels in linear light.
+///
+/// See [`RgbF32`] for more information. The alpha component is linear and is not affected by
+/// the sRGB transfer function when converting to and from integer pixel types.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RgbaF32 {
+    /// The red component of the pixel.
+    pub r: f32,
+    /// The green component of the pixel.
+    pub g: f32,
+    /// The blue component of the pixel.
+    pub b: f32,
+    /// The alpha component of the pixel.
+    pub a: f32,
+}
+
+impl_float_eq!(RgbaF32: r g b a);
+
+impl Pixel for RgbaF32 {
+    const COLOR_TYPE: ColorType = ColorType::Rgba;
+    const BIT_DEPTH: u8 = 32;
+
+    type Subpixel = f32;
+    type Color = Self;
+    type Data = [u8; 16];
+
+    fn map_subpixels<F, A>(self, f: F, a: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+            a: a(self.a),
+        }
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        propagate_palette!(palette, data);
+        // Integer data is gamma-encoded, so it is converted through the sRGB transfer function
+        if bit_depth != Self::BIT_DEPTH {
+            return Rgba16::from_raw_parts(color_type, bit_depth, data).map(Into::into);
+        }
+
+        match color_type {
+            ColorType::Rgb => {
+                propagate_data32!(data, 3);
+                Ok(Self::from_rgb(RgbF32::from_bytes(data)))
+            }
+            ColorType::Rgba => {
+                propagate_data32!(data, 4);
+                Ok(Self::from_bytes(data))
+            }
+            ColorType::Luma => {
+                propagate_data32!(data, 1);
+                let l = subpixel32(data, 0);
+
+                Ok(Self::new(l, l, l, 1.))
+            }
+            ColorType::LumaA => {
+                propagate_data32!(data, 2);
+                let l = subpixel32(data, 0);
+
+                Ok(Self::new(l, l, l, subpixel32(data, 1)))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            r: subpixel32(bytes, 0),
+            g: subpixel32(bytes, 1),
+            b: subpixel32(bytes, 2),
+            a: subpixel32(bytes, 3),
+        }
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let mut bytes = [0; 16];
+        for (chunk, value) in bytes
+            .chunks_exact_mut(4)
+            .zip([self.r, self.g, self.b, self.a])
+        {
+            chunk.copy_from_slice(&value.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn merge(self, other: Self) -> Self {
+        // Optimize for common cases
+        if other.a >= 1. {
+            return other;
+        } else if other.a <= 0. {
+            return self;
+        }
+
+        let a_diff = 1. - other.a;
+        let a = a_diff.mul_add(self.a, other.a);
+        let a_ratio = a_diff * self.a;
+        let blend = |base: f32, overlay: f32| a_ratio.mul_add(base, other.a * overlay) / a;
+
+        Self {
+            r: blend(self.r, other.r),
+            g: blend(self.g, other.g),
+            b: blend(self.b, other.b),
+            a,
+        }
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        self.merge(Self {
+            a: other.a * (f32::from(alpha) / 255.),
+            ..other
+        })
+    }
+
+    /// Blend modes are only defined for colors between `0.0` and `1.0`, so colors outside of this
+    /// range, such as HDR colors, are clamped into it unless the mode is [`BlendMode::Normal`].
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        let clamp = |c: f32| {
+            if mode == BlendMode::Normal {
+                c
+            } else {
+                c.clamp(0.0, 1.0)
+            }
+        };
+        let [r, g, b, a] = blend::composite(
+            [clamp(self.r), clamp(self.g), clamp(self.b), self.a],
+            [
+                clamp(other.r),
+                clamp(other.g),
+                clamp(other.b),
+                other.a * (f32::from(alpha) / 255.),
+            ],
+            mode,
+        );
+
+        Self { r, g, b, a }
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        let [r, g, b, a] = composite::composite(
+            [self.r, self.g, self.b, self.a],
+            [other.r, other.g, other.b, other.a],
+            operator,
+            f32::from(alpha) / 255.,
+        );
+
+        Self { r, g, b, a }
+    }
+
+    fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
+        match mode {
             OverlayMode::Replace => other.with_alpha(alpha),
             OverlayMode::Merge => self.merge_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend_with_alpha(other, mode, alpha),
+            OverlayMode::Composite(operator) => self.composite_with_alpha(other, operator, alpha),
         }
     }
 
     fn from_dynamic(dynamic: Dynamic) -> Self {
         match dynamic {
-            Dynamic::Rgba(value) => value,
+            Dynamic::Rgba(value) => value.into(),
             Dynamic::Rgb(value) => value.into(),
             Dynamic::Luma(value) => value.into(),
+            Dynamic::LumaA(value) => value.into(),
             Dynamic::BitPixel(value) => value.into(),
         }
     }
@@ -1024,145 +2641,97 @@ impl Pixel for Rgba {
     force_into_impl!();
 }
 
-impl Alpha for Rgba {
+impl Alpha for RgbaF32 {
+    /// Returns the alpha value of the pixel, quantized to 8 bits. Use the `a` field directly to
+    /// access the full floating-point value.
+    #[allow(clippy::cast_possible_truncation)]
     fn alpha(&self) -> u8 {
-        self.a
+        quantize_component(self.a, 255.) as u8
     }
 
     fn with_alpha(mut self, alpha: u8) -> Self {
-        self.a = alpha;
+        self.a = f32::from(alpha) / 255.;
         self
     }
-}
-
-impl Rgba {
-    /// Creates a new RGBA pixel.
-    #[must_use]
-    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
-        Self { r, g, b, a }
-    }
 
-    /// Creates an opaque pixel from an RGB pixel.
-    #[must_use]
-    pub const fn from_rgb(Rgb { r, g, b }: Rgb) -> Self {
-        Self::new(r, g, b, 255)
+    fn premultiply(self) -> Self {
+        Self {
+            r: self.r * self.a,
+            g: self.g * self.a,
+            b: self.b * self.a,
+            a: self.a,
+        }
     }
 
-    /// Parses an RGBA pixel from a hex string.
-    ///
-    /// The hex value can be in one of the following formats:
-    /// - RRGGBBAA
-    /// - RRGGBB
-    /// - RGBA
-    /// - RGB
-    ///
-    /// These can be optionally padded with #, for example "#FF0000" is the same as as "FF0000".
-    ///
-    /// # Note
-    /// This is a relatively expensive operation. Use [`Rgba::from_u32`] if you know the hex value
-    /// (i.e. if you are defining a constant), since you can write a `u32` using an integer literal
-    /// of radix 16, e.g. `0xff0000ff`.
-    ///
-    /// # Errors
-    /// * Received a malformed hex code.
-    ///
-    /// # See Also
-    /// * [`Rgba::from_u32`] for parsing from an integer (better for known hex values).
-    pub fn from_hex(hex: &str) -> Result<Self> {
-        let hex = hex.strip_prefix('#').unwrap_or(hex);
-
-        match hex.len() {
-            3 | 6 => Rgb::from_hex(hex).map(Self::from_rgb),
-            len @ (4 | 8) => {
-                let hex = if len == 4 {
-                    let mut expanded = String::with_capacity(8);
-
-                    for c in hex.chars() {
-                        expanded.push(c);
-                        expanded.push(c);
-                    }
-
-                    expanded
-                } else {
-                    hex.to_string()
-                };
-
-                let err = |_| InvalidHexCode(hex.to_string());
+    fn unpremultiply(self) -> Self {
+        if self.a <= 0. {
+            return Self::default();
+        }
 
-                Ok(Self {
-                    r: u8::from_str_radix(&hex[0..2], 16).map_err(err)?,
-                    g: u8::from_str_radix(&hex[2..4], 16).map_err(err)?,
-                    b: u8::from_str_radix(&hex[4..6], 16).map_err(err)?,
-                    a: u8::from_str_radix(&hex[6..8], 16).map_err(err)?,
-                })
-            }
-            _ => Err(InvalidHexCode(hex.to_string())),
+        Self {
+            r: self.r / self.a,
+            g: self.g / self.a,
+            b: self.b / self.a,
+            a: self.a,
         }
     }
+}
 
-    /// Resolves the RGBA pixel from a 32-bit integer (0xRRGGBBAA).
-    ///
-    /// Consider using [`Rgb::from_u32`] and then [`TrueColor::into_rgba`] if you have a
-    /// conventional 24-bit RGB integer, as this method will not properly handle that.
-    ///
-    /// # Examples
-    /// ```
-    /// # use ril::prelude::*;
-    /// const RED: Rgba = Rgba::from_u32(0xff0000ff);
-    /// assert_eq!(RED, Rgba::new(255, 0, 0, 255));
-    /// ```
+impl RgbaF32 {
+    /// Creates a new floating-point RGBA pixel from linear light components.
     #[must_use]
-    pub const fn from_u32(value: u32) -> Self {
-        Self {
-            r: (value >> 24) as u8,
-            g: (value >> 16) as u8,
-            b: (value >> 8) as u8,
-            a: value as u8,
-        }
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates an opaque pixel from a floating-point RGB pixel.
+    #[must_use]
+    pub const fn from_rgb(RgbF32 { r, g, b }: RgbF32) -> Self {
+        Self::new(r, g, b, 1.)
     }
 
     /// Creates a completely transparent pixel.
     #[must_use]
     pub const fn transparent() -> Self {
-        Self::new(0, 0, 0, 0)
+        Self::new(0., 0., 0., 0.)
     }
 
     /// Creates an opaque black pixel.
     #[must_use]
     pub const fn black() -> Self {
-        Self::new(0, 0, 0, 255)
+        Self::new(0., 0., 0., 1.)
     }
 
     /// Creates an opaque white pixel.
     #[must_use]
     pub const fn white() -> Self {
-        Self::new(255, 255, 255, 255)
+        Self::new(1., 1., 1., 1.)
     }
 }
 
-impl Not for Rgba {
+impl Not for RgbaF32 {
     type Output = Self;
 
     fn not(self) -> Self::Output {
         Self {
-            r: !self.r,
-            g: !self.g,
-            b: !self.b,
-            a: !self.a,
+            r: 1. - self.r,
+            g: 1. - self.g,
+            b: 1. - self.b,
+            a: 1. - self.a,
         }
     }
 }
 
-impl IntoVector<4> for Rgba {
-    type Element = u8;
+impl IntoVector<4> for RgbaF32 {
+    type Element = f32;
 
     fn into_vector(self) -> Vector<4, Self::Element> {
         Vector::new([self.r, self.g, self.b, self.a])
     }
 }
 
-impl FromVector<4> for Rgba {
-    type Element = u8;
+impl FromVector<4> for RgbaF32 {
+    type Element = f32;
 
     fn from_vector(vector: Vector<4, Self::Element>) -> Self {
         Self {
@@ -1303,6 +2872,7 @@ impl From<DynamicSubpixel> for usize {
 pub enum Dynamic {
     BitPixel(BitPixel),
     Luma(Luma),
+    LumaA(LumaA),
     Rgb(Rgb),
     Rgba(Rgba),
 }
@@ -1327,6 +2897,7 @@ impl Pixel for Dynamic {
     fn color_type(&self) -> ColorType {
         match self {
             Self::BitPixel(_) | Self::Luma(_) => ColorType::Luma,
+            Self::LumaA(_) => ColorType::LumaA,
             Self::Rgb(_) => ColorType::Rgb,
             Self::Rgba(_) => ColorType::Rgba,
         }
@@ -1356,6 +2927,7 @@ impl Pixel for Dynamic {
         match self {
             Self::BitPixel(pixel) => Self::BitPixel(subpixel!(pixel, Bool)),
             Self::Luma(pixel) => Self::Luma(subpixel!(pixel, U8)),
+            Self::LumaA(pixel) => Self::LumaA(subpixel!(pixel, U8)),
             Self::Rgb(pixel) => Self::Rgb(subpixel!(pixel, U8)),
             Self::Rgba(pixel) => Self::Rgba(subpixel!(pixel, U8)),
         }
@@ -1376,10 +2948,14 @@ impl Pixel for Dynamic {
             let data = scale_subpixels!(bit_depth, Self::BIT_DEPTH, data);
 
             match color_type {
-                ColorType::Luma | ColorType::LumaA => {
+                ColorType::Luma => {
                     propagate_data!(data, 1);
                     Self::Luma(Luma(data[0]))
                 }
+                ColorType::LumaA => {
+                    propagate_data!(data, 2);
+                    Self::LumaA(LumaA::new(data[0], data[1]))
+                }
                 ColorType::Rgb => {
                     propagate_data!(data, 3);
                     Self::Rgb(Rgb {
@@ -1405,6 +2981,7 @@ impl Pixel for Dynamic {
     fn from_bytes(bytes: &[u8]) -> Self {
         match bytes.len() {
             1 => Self::Luma(Pixel::from_bytes(bytes)),
+            2 => Self::LumaA(Pixel::from_bytes(bytes)),
             3 => Self::Rgb(Pixel::from_bytes(bytes)),
             4 => Self::Rgba(Pixel::from_bytes(bytes)),
             _ => panic!("Invalid pixel data length"),
@@ -1415,6 +2992,7 @@ impl Pixel for Dynamic {
         match self {
             Self::BitPixel(pixel) => pixel.as_bytes().to_vec(),
             Self::Luma(pixel) => pixel.as_bytes().to_vec(),
+            Self::LumaA(pixel) => pixel.as_bytes().to_vec(),
             Self::Rgb(pixel) => pixel.as_bytes().to_vec(),
             Self::Rgba(pixel) => pixel.as_bytes().to_vec(),
         }
@@ -1428,6 +3006,9 @@ impl Pixel for Dynamic {
             (Self::Luma(pixel), Self::Luma(other)) => {
                 Self::Luma(pixel.merge_with_alpha(other, alpha))
             }
+            (Self::LumaA(pixel), Self::LumaA(other)) => {
+                Self::LumaA(pixel.merge_with_alpha(other, alpha))
+            }
             (Self::Rgb(pixel), Self::Rgb(other)) => Self::Rgb(pixel.merge_with_alpha(other, alpha)),
             (Self::Rgba(pixel), Self::Rgba(other)) => {
                 Self::Rgba(pixel.merge_with_alpha(other, alpha))
@@ -1436,6 +3017,48 @@ impl Pixel for Dynamic {
         }
     }
 
+    fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+        match (self, other) {
+            (Self::BitPixel(pixel), Self::BitPixel(other)) => {
+                Self::BitPixel(pixel.blend_with_alpha(other, mode, alpha))
+            }
+            (Self::Luma(pixel), Self::Luma(other)) => {
+                Self::Luma(pixel.blend_with_alpha(other, mode, alpha))
+            }
+            (Self::LumaA(pixel), Self::LumaA(other)) => {
+                Self::LumaA(pixel.blend_with_alpha(other, mode, alpha))
+            }
+            (Self::Rgb(pixel), Self::Rgb(other)) => {
+                Self::Rgb(pixel.blend_with_alpha(other, mode, alpha))
+            }
+            (Self::Rgba(pixel), Self::Rgba(other)) => {
+                Self::Rgba(pixel.blend_with_alpha(other, mode, alpha))
+            }
+            _ => panic!("Cannot overlay two foreign pixel types"),
+        }
+    }
+
+    fn composite_with_alpha(self, other: Self, operator: CompositeOperator, alpha: u8) -> Self {
+        match (self, other) {
+            (Self::BitPixel(pixel), Self::BitPixel(other)) => {
+                Self::BitPixel(pixel.composite_with_alpha(other, operator, alpha))
+            }
+            (Self::Luma(pixel), Self::Luma(other)) => {
+                Self::Luma(pixel.composite_with_alpha(other, operator, alpha))
+            }
+            (Self::LumaA(pixel), Self::LumaA(other)) => {
+                Self::LumaA(pixel.composite_with_alpha(other, operator, alpha))
+            }
+            (Self::Rgb(pixel), Self::Rgb(other)) => {
+                Self::Rgb(pixel.composite_with_alpha(other, operator, alpha))
+            }
+            (Self::Rgba(pixel), Self::Rgba(other)) => {
+                Self::Rgba(pixel.composite_with_alpha(other, operator, alpha))
+            }
+            _ => panic!("Cannot overlay two foreign pixel types"),
+        }
+    }
+
     fn from_dynamic(dynamic: Dynamic) -> Self {
         dynamic
     }
@@ -1450,6 +3073,7 @@ impl Not for Dynamic {
         match self {
             Self::BitPixel(pixel) => Self::BitPixel(!pixel),
             Self::Luma(pixel) => Self::Luma(!pixel),
+            Self::LumaA(pixel) => Self::LumaA(!pixel),
             Self::Rgb(pixel) => Self::Rgb(!pixel),
             Self::Rgba(pixel) => Self::Rgba(!pixel),
         }
@@ -1459,6 +3083,7 @@ impl Not for Dynamic {
 impl Alpha for Dynamic {
     fn alpha(&self) -> u8 {
         match self {
+            Self::LumaA(pixel) => pixel.alpha(),
             Self::Rgba(pixel) => pixel.alpha(),
             _ => 255,
         }
@@ -1466,10 +3091,27 @@ impl Alpha for Dynamic {
 
     fn with_alpha(self, alpha: u8) -> Self {
         match self {
+            Self::LumaA(pixel) => Self::LumaA(pixel.with_alpha(alpha)),
             Self::Rgba(pixel) => Self::Rgba(pixel.with_alpha(alpha)),
             pixel => pixel,
         }
     }
+
+    fn premultiply(self) -> Self {
+        match self {
+            Self::LumaA(pixel) => Self::LumaA(pixel.premultiply()),
+            Self::Rgba(pixel) => Self::Rgba(pixel.premultiply()),
+            pixel => pixel,
+        }
+    }
+
+    fn unpremultiply(self) -> Self {
+        match self {
+            Self::LumaA(pixel) => Self::LumaA(pixel.unpremultiply()),
+            Self::Rgba(pixel) => Self::Rgba(pixel.unpremultiply()),
+            pixel => pixel,
+        }
+    }
 }
 
 impl Dynamic {
@@ -1490,6 +3132,7 @@ macro_rules! impl_dynamic {
                     match pixel {
                         Dynamic::BitPixel(pixel) => pixel.into(),
                         Dynamic::Luma(pixel) => pixel.into(),
+                        Dynamic::LumaA(pixel) => pixel.into(),
                         Dynamic::Rgb(pixel) => pixel.into(),
                         Dynamic::Rgba(pixel) => pixel.into(),
                     }
@@ -1505,7 +3148,7 @@ macro_rules! impl_dynamic {
     };
 }
 
-impl_dynamic!(BitPixel, Luma, Rgb, Rgba);
+impl_dynamic!(BitPixel, Luma, LumaA, Rgb, Rgba);
 
 impl From<Rgb> for BitPixel {
     fn from(rgb: Rgb) -> Self {
@@ -1552,7 +3195,11 @@ impl From<Luma> for BitPixel {
 impl From<Rgb> for Luma {
     fn from(Rgb { r, g, b }: Rgb) -> Self {
         #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-        Self(f32::from(b).mul_add(0.114, f32::from(r).mul_add(0.299, f32::from(g) * 0.587)) as u8)
+        Self(
+            f32::from(b)
+                .mul_add(0.114, f32::from(r).mul_add(0.299, f32::from(g) * 0.587))
+                .round() as u8,
+        )
     }
 }
 
@@ -1591,6 +3238,225 @@ impl From<Rgb> for Rgba {
     }
 }
 
+impl From<LumaA> for Luma {
+    fn from(LumaA { l, .. }: LumaA) -> Self {
+        Self(l)
+    }
+}
+
+impl From<Luma> for LumaA {
+    fn from(luma: Luma) -> Self {
+        Self::from_luma(luma)
+    }
+}
+
+impl From<LumaA> for Rgba {
+    fn from(LumaA { l, a }: LumaA) -> Self {
+        Self::new(l, l, l, a)
+    }
+}
+
+impl From<Rgba> for LumaA {
+    fn from(rgba: Rgba) -> Self {
+        Self::new(Luma::from(rgba).value(), rgba.a)
+    }
+}
+
+impl From<Luma> for Luma16 {
+    fn from(Luma(l): Luma) -> Self {
+        Self(widen(l))
+    }
+}
+
+impl From<Luma16> for Luma {
+    fn from(Luma16(l): Luma16) -> Self {
+        Self(narrow(l))
+    }
+}
+
+impl From<Rgb> for Rgb16 {
+    fn from(Rgb { r, g, b }: Rgb) -> Self {
+        Self::new(widen(r), widen(g), widen(b))
+    }
+}
+
+impl From<Rgb16> for Rgb {
+    fn from(Rgb16 { r, g, b }: Rgb16) -> Self {
+        Self::new(narrow(r), narrow(g), narrow(b))
+    }
+}
+
+impl From<Rgba> for Rgba16 {
+    fn from(Rgba { r, g, b, a }: Rgba) -> Self {
+        Self::new(widen(r), widen(g), widen(b), widen(a))
+    }
+}
+
+impl From<Rgba16> for Rgba {
+    fn from(Rgba16 { r, g, b, a }: Rgba16) -> Self {
+        Self::new(narrow(r), narrow(g), narrow(b), narrow(a))
+    }
+}
+
+impl From<Rgb16> for Luma16 {
+    fn from(Rgb16 { r, g, b }: Rgb16) -> Self {
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Self(
+            f32::from(b)
+                .mul_add(0.114, f32::from(r).mul_add(0.299, f32::from(g) * 0.587))
+                .round() as u16,
+        )
+    }
+}
+
+impl From<Rgba16> for Luma16 {
+    fn from(rgba: Rgba16) -> Self {
+        Rgb16::from(rgba).into()
+    }
+}
+
+impl From<Luma16> for Rgb16 {
+    fn from(Luma16(l): Luma16) -> Self {
+        Self { r: l, g: l, b: l }
+    }
+}
+
+impl From<Luma16> for Rgba16 {
+    fn from(Luma16(l): Luma16) -> Self {
+        Self::new(l, l, l, u16::MAX)
+    }
+}
+
+impl From<Rgba16> for Rgb16 {
+    fn from(Rgba16 { r, g, b, .. }: Rgba16) -> Self {
+        Self { r, g, b }
+    }
+}
+
+impl From<Rgb16> for Rgba16 {
+    fn from(rgb: Rgb16) -> Self {
+        Self::from_rgb(rgb)
+    }
+}
+
+/// Implements `From` conversions that go through an intermediate pixel type, i.e. `A => B => C`
+/// implements `From<A> for C` by converting `A` into `B` first.
+macro_rules! impl_from_via {
+    ($($src:ty => $via:ty => $tgt:ty),+ $(,)?) => {
+        $(
+            impl From<$src> for $tgt {
+                fn from(pixel: $src) -> Self {
+                    <$via>::from(pixel).into()
+                }
+            }
+        )+
+    };
+}
+
+// Convert to 16-bit first and narrow last to retain as much precision as possible
+impl_from_via!(
+    Rgb => Rgb16 => Luma16,
+    Rgba => Rgba16 => Luma16,
+    Luma => Luma16 => Rgb16,
+    Rgba => Rgba16 => Rgb16,
+    Luma => Luma16 => Rgba16,
+    Rgb => Rgb16 => Rgba16,
+    Rgb16 => Luma16 => Luma,
+    Rgba16 => Luma16 => Luma,
+    Luma16 => Rgb16 => Rgb,
+    Rgba16 => Rgb16 => Rgb,
+    Luma16 => Rgba16 => Rgba,
+    Rgb16 => Rgba16 => Rgba,
+    BitPixel => Luma => Luma16,
+    BitPixel => Rgb => Rgb16,
+    BitPixel => Rgba => Rgba16,
+    Luma16 => Luma => BitPixel,
+    Rgb16 => Rgb => BitPixel,
+    Rgba16 => Rgba => BitPixel,
+);
+
+/// Implements `From` conversions between integer pixel types and floating-point pixel types,
+/// applying the sRGB transfer function to color components but not to alpha.
+macro_rules! impl_float_conversions {
+    ($int:ident <=> $float:ident, $t:ty, $max:expr; $($color:ident)+ $(; $alpha:ident)?) => {
+        impl From<$int> for $float {
+            fn from(pixel: $int) -> Self {
+                Self {
+                    $($color: srgb_to_linear(f32::from(pixel.$color) / $max),)+
+                    $($alpha: f32::from(pixel.$alpha) / $max,)?
+                }
+            }
+        }
+
+        impl From<$float> for $int {
+            #[allow(clippy::cast_possible_truncation)]
+            fn from(pixel: $float) -> Self {
+                Self {
+                    $($color: quantize_component(linear_to_srgb(pixel.$color), $max) as $t,)+
+                    $($alpha: quantize_component(pixel.$alpha, $max) as $t,)?
+                }
+            }
+        }
+    };
+}
+
+impl_float_conversions!(Rgb <=> RgbF32, u8, 255.; r g b);
+impl_float_conversions!(Rgba <=> RgbaF32, u8, 255.; r g b; a);
+impl_float_conversions!(Rgb16 <=> RgbF32, u16, 65535.; r g b);
+impl_float_conversions!(Rgba16 <=> RgbaF32, u16, 65535.; r g b; a);
+
+impl From<RgbaF32> for RgbF32 {
+    fn from(RgbaF32 { r, g, b, .. }: RgbaF32) -> Self {
+        Self { r, g, b }
+    }
+}
+
+impl From<RgbF32> for RgbaF32 {
+    fn from(rgb: RgbF32) -> Self {
+        Self::from_rgb(rgb)
+    }
+}
+
+impl_from_via!(
+    Rgba => RgbaF32 => RgbF32,
+    Rgb => RgbF32 => RgbaF32,
+    RgbF32 => RgbaF32 => Rgba,
+    RgbaF32 => RgbF32 => Rgb,
+    Rgba16 => RgbaF32 => RgbF32,
+    Rgb16 => RgbF32 => RgbaF32,
+    RgbF32 => RgbaF32 => Rgba16,
+    RgbaF32 => RgbF32 => Rgb16,
+    Luma => Rgb => RgbF32,
+    Luma => Rgba => RgbaF32,
+    Luma16 => Rgb16 => RgbF32,
+    Luma16 => Rgba16 => RgbaF32,
+    BitPixel => Rgb => RgbF32,
+    BitPixel => Rgba => RgbaF32,
+    RgbF32 => Rgb => Luma,
+    RgbaF32 => Rgba => Luma,
+    RgbF32 => Rgb16 => Luma16,
+    RgbaF32 => Rgba16 => Luma16,
+    RgbF32 => Rgb => BitPixel,
+    RgbaF32 => Rgba => BitPixel,
+);
+
+impl_from_via!(
+    LumaA => Rgba => Rgb,
+    Rgb => Luma => LumaA,
+    LumaA => Luma => BitPixel,
+    BitPixel => Luma => LumaA,
+    LumaA => Luma => Luma16,
+    Luma16 => Luma => LumaA,
+    LumaA => Rgb => Rgb16,
+    Rgb16 => Rgb => LumaA,
+    LumaA => Rgba => Rgba16,
+    Rgba16 => Rgba => LumaA,
+    LumaA => Rgb => RgbF32,
+    RgbF32 => Rgb => LumaA,
+    LumaA => Rgba => RgbaF32,
+    RgbaF32 => Rgba => LumaA,
+);
+
 /// A trait representing all pixels that can be safely represented as either RGB or RGBA true color.
 pub trait TrueColor: Pixel {
     /// Returns the pixel as an (r, g, b) tuple.
@@ -1674,11 +3540,12 @@ where
     }
 }
 
-/// A trait representing a paletted pixel. [`Pixel::Subpixel`] is the type of the palette index.
+/// A trait representing a paletted pixel. [`Pixel::Subpixel`] is the type of the palette index,
+/// which is currently always a `u8`.
 ///
 /// The generic lifetime parameter `'p` represents the lifetime of a palette the type will hold a
 /// reference to.
-pub trait Paletted<'p>: Pixel
+pub trait Paletted<'p>: Pixel<Subpixel = u8>
 where
     Self: 'p,
 {
@@ -1703,7 +3570,7 @@ where
     fn color(&self) -> Self::Color {
         *self
             .palette()
-            .get(self.palette_index().into())
+            .get(usize::from(self.palette_index()))
             .expect("invalid palette index")
     }
 
@@ -1716,7 +3583,9 @@ where
     /// # Safety
     /// * The palette index must be valid.
     unsafe fn color_unchecked(&self) -> Self::Color {
-        *self.palette().get_unchecked(self.palette_index().into())
+        *self
+            .palette()
+            .get_unchecked(usize::from(self.palette_index()))
     }
 }
 
@@ -1839,6 +3708,25 @@ macro_rules! impl_palette8 {
                 try_palette!(self, "merged", |color| color == target)
             }
 
+            fn blend_with_alpha(self, other: Self, mode: BlendMode, alpha: u8) -> Self {
+                let target = &self.color().blend_with_alpha(other.color(), mode, alpha);
+
+                try_palette!(self, "blended", |color| color == target)
+            }
+
+            fn composite_with_alpha(
+                self,
+                other: Self,
+                operator: CompositeOperator,
+                alpha: u8,
+            ) -> Self {
+                let target = &self
+                    .color()
+                    .composite_with_alpha(other.color(), operator, alpha);
+
+                try_palette!(self, "composited", |color| color == target)
+            }
+
             fn from_dynamic(_dynamic: Dynamic) -> Self {
                 todo!("implement dynamic palettes")
             }
@@ -1873,7 +3761,7 @@ macro_rules! impl_palette8 {
             }
         }
 
-        impl_palette_cast!($name<'_>: Rgb Rgba Luma BitPixel Dynamic);
+        impl_palette_cast!($name<'_>: Rgb Rgba Luma LumaA Rgb16 Rgba16 Luma16 RgbF32 RgbaF32 BitPixel Dynamic);
     }
 }
 
@@ -1,12 +1,69 @@
 //! Quantizes unpaletted pixel data to paletted data by quantizing the colors into a palette.
 
 #[cfg_attr(not(feature = "quantize"), allow(unused_imports))]
-use crate::{Pixel, TrueColor};
+use crate::{
+    dither::{self, Dither},
+    Image, Pixel, Rgba, TrueColor,
+};
 use std::collections::HashMap;
 
 #[cfg(feature = "quantize")]
 use color_quant::NeuQuant;
 
+/// The algorithm used to compute the palette of a lossy quantization.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuantizeAlgorithm {
+    /// The NeuQuant neural network algorithm provided by the `color_quant` crate. This is the
+    /// default when the `quantize` feature is enabled, and takes [`Quantizer::quality`] into
+    /// account.
+    #[cfg(feature = "quantize")]
+    NeuQuant,
+    /// The median cut algorithm, which repeatedly splits the group of colors with the widest
+    /// range in half. This is the default when the `quantize` feature is disabled.
+    MedianCut,
+    /// The octree algorithm, which groups colors by the leading bits of their channels and merges
+    /// the least common groups.
+    Octree,
+    /// Xiaolin Wu's algorithm, which repeatedly splits the group of colors with the most variance
+    /// such that the variance of both halves is minimized. This usually produces the best
+    /// palettes of the pure Rust algorithms.
+    Wu,
+    /// Use the given palette instead of computing one, e.g. a brand palette or
+    /// [`Self::web_safe`]. Pixels are mapped to the closest color in the palette.
+    ///
+    /// The palette must not be empty and must have at most 256 colors. It is used as-is, i.e.
+    /// [`Quantizer::palette_size`] is disregarded and the palette is never refined.
+    Fixed(Vec<Rgba>),
+}
+
+impl Default for QuantizeAlgorithm {
+    #[cfg(feature = "quantize")]
+    fn default() -> Self {
+        Self::NeuQuant
+    }
+
+    #[cfg(not(feature = "quantize"))]
+    fn default() -> Self {
+        Self::MedianCut
+    }
+}
+
+impl QuantizeAlgorithm {
+    /// A fixed palette of the 216 web-safe colors, where each channel is a multiple of `0x33`.
+    #[must_use]
+    pub fn web_safe() -> Self {
+        let levels = (0..6).map(|level| level * 0x33);
+
+        Self::Fixed(
+            levels
+                .clone()
+                .flat_map(|r| levels.clone().map(move |g| (r, g)))
+                .flat_map(|(r, g)| levels.clone().map(move |b| Rgba::new(r, g, b, 255)))
+                .collect(),
+        )
+    }
+}
+
 /// Configuration options regarding behavior of quantization.
 #[derive(Clone, Debug)]
 pub struct Quantizer {
@@ -29,6 +86,18 @@ pub struct Quantizer {
     /// image, where `n` is the amount of pixels in the image. This isn't very significant, but
     /// it's worth noting.
     pub fallback_to_lossless: bool,
+    /// The dithering method used when mapping colors to the palette of a lossy quantization.
+    /// Lossless quantization never dithers since every color is kept exactly. Defaults to
+    /// [`Dither::None`].
+    pub dither: Dither,
+    /// The algorithm used to compute the palette of a lossy quantization. Defaults to
+    /// [`QuantizeAlgorithm::NeuQuant`] if the `quantize` feature is enabled, and
+    /// [`QuantizeAlgorithm::MedianCut`] otherwise.
+    pub algorithm: QuantizeAlgorithm,
+    /// The number of k-means iterations used to refine the palette computed by the median cut,
+    /// octree, and Wu algorithms, which moves each palette entry to the average of the colors
+    /// closest to it. Refinement stops early once the palette converges. Defaults to `0`.
+    pub kmeans_iterations: u32,
 }
 
 impl Default for Quantizer {
@@ -38,6 +107,9 @@ impl Default for Quantizer {
             gif_optimization: false,
             quality: 20,
             fallback_to_lossless: true,
+            dither: Dither::None,
+            algorithm: QuantizeAlgorithm::default(),
+            kmeans_iterations: 0,
         }
     }
 }
@@ -88,29 +160,102 @@ impl Quantizer {
         self
     }
 
+    /// Sets the dithering method used when mapping colors to the palette of a lossy
+    /// quantization. Dithering reduces banding in gradients at the cost of adding noise.
+    #[must_use]
+    pub const fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Sets the algorithm used to compute the palette of a lossy quantization.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// use ril::{QuantizeAlgorithm, Quantizer};
+    ///
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::<Rgb>::open("sample.png")?;
+    /// let quantizer = Quantizer::new()
+    ///     .with_algorithm(QuantizeAlgorithm::web_safe())
+    ///     .with_dither(Dither::FloydSteinberg);
+    /// let web_safe: Image<PalettedRgb> = image.quantize_with(&quantizer)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_algorithm(mut self, algorithm: QuantizeAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the number of k-means iterations used to refine the palette computed by the median
+    /// cut, octree, and Wu algorithms. More iterations produce more accurate colors at the cost
+    /// of speed.
+    #[must_use]
+    pub const fn with_kmeans_iterations(mut self, iterations: u32) -> Self {
+        self.kmeans_iterations = iterations;
+        self
+    }
+
     /// Quantizes the given pixels to a palette of the given size. Returns `(palette, image_data)`.
     ///
     /// # Behavior
-    /// * If the `quantize` feature is disabled (enabled by default), this function will simply
-    ///   run the [`quantize_simple`] function (lossless) and return `Err` if there are more unique
-    ///   colors than the palette size.
-    /// * Otherwise, this function will always favor a lossy quantization algorithm unless the
+    /// * If the algorithm is [`QuantizeAlgorithm::Fixed`], pixels are always mapped to the given
+    ///   palette.
+    /// * Otherwise, this function will always favor the lossy quantization algorithm unless the
     ///   `fallback_to_lossless` option is set to `true` (which is the default) and the amount of
     ///   unique colors in the image is less than the desired palette size.
     ///
+    /// Since the pixels have no dimensions, they are dithered as if they were a single row. Use
+    /// [`Self::quantize_image`] to dither in two dimensions.
+    ///
     /// # Errors
-    /// * There are more unique colors than the palette size, and the `quantize` feature is disabled
+    /// * An error occurred while quantizing the pixels
+    ///
+    /// # Panics
+    /// * The algorithm is [`QuantizeAlgorithm::Fixed`] and its palette is empty or has more than
+    ///   256 colors
     pub fn quantize<P: TrueColor>(
         &self,
         pixels: impl AsRef<[P]>,
     ) -> crate::Result<(Vec<P>, Vec<u8>)> {
-        #[cfg(feature = "quantize")]
-        {
-            quantize_lossy(pixels, self)
-        }
-        #[cfg(not(feature = "quantize"))]
-        {
-            quantize_simple(pixels, self)
+        let pixels = pixels.as_ref();
+        self.quantize_rows(pixels, pixels.len())
+    }
+
+    /// Quantizes the pixels of the given image to a palette of the given size. Returns
+    /// `(palette, image_data)`.
+    ///
+    /// This behaves like [`Self::quantize`], except that dithering takes the dimensions of the
+    /// image into account.
+    ///
+    /// # Errors
+    /// * An error occurred while quantizing the pixels
+    ///
+    /// # Panics
+    /// * The algorithm is [`QuantizeAlgorithm::Fixed`] and its palette is empty or has more than
+    ///   256 colors
+    pub fn quantize_image<P: TrueColor>(
+        &self,
+        image: &Image<P>,
+    ) -> crate::Result<(Vec<P>, Vec<u8>)> {
+        self.quantize_rows(&image.data, image.width() as usize)
+    }
+
+    fn quantize_rows<P: TrueColor>(
+        &self,
+        pixels: &[P],
+        width: usize,
+    ) -> crate::Result<(Vec<P>, Vec<u8>)> {
+        match self.algorithm {
+            #[cfg(feature = "quantize")]
+            QuantizeAlgorithm::NeuQuant => lossy(pixels, width, self),
+            QuantizeAlgorithm::Fixed(ref palette) => {
+                Ok(quantize_fixed(pixels, width, palette, self))
+            }
+            _ => quantize_palette(pixels, width, self),
         }
     }
 }
@@ -171,6 +316,16 @@ pub fn quantize_lossy<P: TrueColor>(
     config: &Quantizer,
 ) -> crate::Result<(Vec<P>, Vec<u8>)> {
     let pixels = pixels.as_ref();
+    lossy(pixels, pixels.len(), config)
+}
+
+/// Quantizes pixels laid out in rows of the given width using the NeuQuant algorithm.
+#[cfg(feature = "quantize")]
+fn lossy<P: TrueColor>(
+    pixels: &[P],
+    width: usize,
+    config: &Quantizer,
+) -> crate::Result<(Vec<P>, Vec<u8>)> {
     if config.fallback_to_lossless {
         let count = pixels.windows(2).filter(|win| win[0] != win[1]).count() + 1;
         if count <= config.palette_size {
@@ -185,12 +340,24 @@ pub fn quantize_lossy<P: TrueColor>(
 
     #[allow(clippy::cast_lossless)]
     let quantizer = NeuQuant::new(31 - config.quality as i32, config.palette_size, &pixels);
-    let palette = quantizer
-        .color_map_rgba()
+    let colors = quantizer.color_map_rgba();
+    let palette = colors
         .chunks_exact(4)
         .map(|chunk| P::from_rgba_tuple((chunk[0], chunk[1], chunk[2], chunk[3])))
         .collect();
 
+    if config.dither != Dither::None {
+        let as_floats = |chunk: &[u8]| [0, 1, 2, 3].map(|c| f32::from(chunk[c]));
+        let indices = dither::dither(
+            &pixels.chunks_exact(4).map(as_floats).collect::<Vec<_>>(),
+            width,
+            &colors.chunks_exact(4).map(as_floats).collect::<Vec<_>>(),
+            config.dither,
+        );
+
+        return Ok((palette, indices.into_iter().map(|i| i as u8).collect()));
+    }
+
     Ok((
         palette,
         (0..pixels.len())
@@ -200,6 +367,485 @@ pub fn quantize_lossy<P: TrueColor>(
     ))
 }
 
+/// Maps the given pixels to the given fixed palette.
+fn quantize_fixed<P: TrueColor>(
+    pixels: &[P],
+    width: usize,
+    palette: &[Rgba],
+    config: &Quantizer,
+) -> (Vec<P>, Vec<u8>) {
+    assert!(
+        !palette.is_empty() && palette.len() <= 256,
+        "fixed palettes must have between 1 and 256 colors"
+    );
+
+    let colors = pixels
+        .iter()
+        .map(|pixel| premultiply(pixel.as_rgba_tuple()).map(f32::from))
+        .collect::<Vec<_>>();
+    let entries = palette
+        .iter()
+        .map(|color| premultiply(color.as_rgba_tuple()).map(f32::from))
+        .collect::<Vec<_>>();
+    let indices = dither::dither(&colors, width, &entries, config.dither);
+
+    (
+        palette
+            .iter()
+            .map(|color| P::from_rgba_tuple(color.as_rgba_tuple()))
+            .collect(),
+        indices.into_iter().map(|i| i as u8).collect(),
+    )
+}
+
+/// Quantizes the given pixels with one of the pure Rust algorithms.
+fn quantize_palette<P: TrueColor>(
+    pixels: &[P],
+    width: usize,
+    config: &Quantizer,
+) -> crate::Result<(Vec<P>, Vec<u8>)> {
+    let colors = pixels
+        .iter()
+        .map(|pixel| {
+            let (r, g, b, a) = pixel.as_rgba_tuple();
+            if config.gif_optimization {
+                (r, g, b, if a == 0 { 0 } else { 255 })
+            } else {
+                (r, g, b, a)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if config.fallback_to_lossless {
+        let mut unique = pixels.iter().map(P::as_rgba_tuple).collect::<Vec<_>>();
+        unique.sort_unstable();
+        unique.dedup();
+        if unique.len() <= config.palette_size {
+            return quantize_simple(pixels, config);
+        }
+    }
+
+    // Fully transparent pixels all share a single palette entry
+    let colors = colors.into_iter().map(premultiply).collect::<Vec<_>>();
+    let mut histogram = HashMap::new();
+    for color in colors.iter().filter(|color| color[3] > 0) {
+        *histogram.entry(*color).or_insert(0) += 1;
+    }
+    let transparent = colors.iter().any(|color| color[3] == 0);
+    let size = config
+        .palette_size
+        .min(256)
+        .saturating_sub(usize::from(transparent))
+        .max(1);
+
+    let mut entries = histogram.into_iter().collect::<Vec<Entry>>();
+    entries.sort_unstable();
+    let exact = entries.len() <= size;
+
+    let mut palette = if exact {
+        entries
+            .iter()
+            .map(|(color, _)| color.map(f32::from))
+            .collect()
+    } else {
+        let mut palette = match config.algorithm {
+            QuantizeAlgorithm::Octree => octree(&entries, size),
+            QuantizeAlgorithm::Wu => wu(&entries, size),
+            _ => median_cut(&mut entries, size),
+        };
+        refine(&entries, &mut palette, config.kmeans_iterations);
+        palette
+    };
+    if transparent || palette.is_empty() {
+        palette.push([0.0; 4]);
+    }
+
+    // Exact palettes need no dithering
+    let dither = if exact { Dither::None } else { config.dither };
+    let indices = dither::dither(
+        &colors
+            .iter()
+            .map(|color| color.map(f32::from))
+            .collect::<Vec<_>>(),
+        width,
+        &palette,
+        dither,
+    );
+
+    Ok((
+        palette
+            .into_iter()
+            .map(|color| P::from_rgba_tuple(unpremultiply(color)))
+            .collect(),
+        indices.into_iter().map(|i| i as u8).collect(),
+    ))
+}
+
+/// A color in premultiplied RGBA space. Palettes are computed in this space so that the color of
+/// translucent pixels matters less the more transparent they are.
+type Color = [f32; 4];
+
+/// A unique premultiplied color along with the number of pixels with that color.
+type Entry = ([u8; 4], u32);
+
+fn premultiply((r, g, b, a): (u8, u8, u8, u8)) -> [u8; 4] {
+    let scale = |c: u8| ((u16::from(c) * u16::from(a) + 127) / 255) as u8;
+
+    [scale(r), scale(g), scale(b), a]
+}
+
+fn unpremultiply(color: Color) -> (u8, u8, u8, u8) {
+    let alpha = color[3].round().clamp(0.0, 255.0);
+    if alpha < 1.0 {
+        return (0, 0, 0, 0);
+    }
+    let scale = |c: f32| (c * 255.0 / alpha).round().clamp(0.0, 255.0) as u8;
+
+    (
+        scale(color[0]),
+        scale(color[1]),
+        scale(color[2]),
+        alpha as u8,
+    )
+}
+
+/// The average color of the given entries, weighed by their number of pixels.
+fn mean(entries: &[Entry]) -> Color {
+    let (sum, count) = entries
+        .iter()
+        .fold(([0.0_f64; 4], 0.0), |(mut sum, count), &(color, n)| {
+            for c in 0..4 {
+                sum[c] += f64::from(color[c]) * f64::from(n);
+            }
+            (sum, count + f64::from(n))
+        });
+
+    sum.map(|s| (s / count) as f32)
+}
+
+fn distance(a: &Color, b: &Color) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum()
+}
+
+/// Repeatedly splits the box of colors with the widest range at the median of its widest channel.
+fn median_cut(entries: &mut [Entry], size: usize) -> Vec<Color> {
+    let widest = |entries: &[Entry]| {
+        (0..4)
+            .map(|c| {
+                let (min, max) = entries.iter().fold((u8::MAX, 0), |(min, max), entry| {
+                    (min.min(entry.0[c]), max.max(entry.0[c]))
+                });
+                (c, max - min)
+            })
+            .max_by_key(|&(_, extent)| extent)
+            .unwrap_or((0, 0))
+    };
+
+    // Each box is a range of entries, which are sorted along the channel the box was split at
+    let mut boxes = vec![(0, entries.len())];
+    while boxes.len() < size {
+        let candidate = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, (start, end))| end - start > 1)
+            .map(|(i, &(start, end))| (i, widest(&entries[start..end])))
+            .max_by_key(|&(_, (_, extent))| extent);
+        let (i, channel) = match candidate {
+            Some((i, (channel, _))) => (i, channel),
+            None => break,
+        };
+
+        let (start, end) = boxes[i];
+        let colors = &mut entries[start..end];
+        colors.sort_unstable_by_key(|entry| entry.0[channel]);
+
+        // Split at the weighted median, keeping at least one color on each side
+        let total = colors.iter().map(|entry| u64::from(entry.1)).sum::<u64>();
+        let mut count = 0;
+        let split = colors
+            .iter()
+            .position(|entry| {
+                count += u64::from(entry.1);
+                count * 2 >= total
+            })
+            .map_or(1, |i| i + 1)
+            .clamp(1, colors.len() - 1);
+
+        boxes[i] = (start, start + split);
+        boxes.push((start + split, end));
+    }
+
+    boxes
+        .into_iter()
+        .map(|(start, end)| mean(&entries[start..end]))
+        .collect()
+}
+
+/// Builds a tree where each level splits colors by the next bit of each channel, then merges the
+/// least populated nodes, starting with the deepest, until there are few enough leaves.
+fn octree(entries: &[Entry], size: usize) -> Vec<Color> {
+    struct Node {
+        prefix: [u8; 4],
+        count: u64,
+        sum: [u64; 4],
+    }
+
+    let mut leaves = entries
+        .iter()
+        .map(|&(color, n)| Node {
+            prefix: color,
+            count: u64::from(n),
+            sum: color.map(|c| u64::from(c) * u64::from(n)),
+        })
+        .collect::<Vec<_>>();
+
+    for _ in 0..8 {
+        if leaves.len() <= size {
+            break;
+        }
+
+        let mut parents = HashMap::<[u8; 4], Vec<Node>>::new();
+        for leaf in leaves.drain(..) {
+            parents
+                .entry(leaf.prefix.map(|c| c >> 1))
+                .or_default()
+                .push(leaf);
+        }
+        let mut parents = parents.into_iter().collect::<Vec<_>>();
+        parents.sort_unstable_by_key(|(prefix, children)| {
+            (
+                children.iter().map(|child| child.count).sum::<u64>(),
+                *prefix,
+            )
+        });
+
+        // Merge the least populated parents into single leaves. Once there are few enough
+        // leaves, the children of the remaining parents are kept as they are.
+        let mut count = parents
+            .iter()
+            .map(|(_, children)| children.len())
+            .sum::<usize>();
+        for (prefix, children) in parents {
+            if count <= size {
+                leaves.extend(children);
+                continue;
+            }
+            count -= children.len() - 1;
+            leaves.push(children.into_iter().fold(
+                Node {
+                    prefix,
+                    count: 0,
+                    sum: [0; 4],
+                },
+                |mut parent, child| {
+                    parent.count += child.count;
+                    for c in 0..4 {
+                        parent.sum[c] += child.sum[c];
+                    }
+                    parent
+                },
+            ));
+        }
+    }
+
+    leaves
+        .into_iter()
+        .map(|leaf| leaf.sum.map(|s| s as f32 / leaf.count as f32))
+        .collect()
+}
+
+/// Xiaolin Wu's quantizer, which repeatedly splits the box of colors with the largest variance
+/// where the variance of the two halves is minimized, using cumulative moments of a coarse
+/// histogram to compute the statistics of any box in constant time.
+fn wu(entries: &[Entry], size: usize) -> Vec<Color> {
+    type Moments = (f64, [f64; 4], f64);
+    type Box = ([usize; 4], [usize; 4]);
+
+    // Colors are binned into 32 levels per channel. Alpha uses 8 levels when it varies, since a
+    // fourth dimension of 32 levels would take up too much memory.
+    let uniform_alpha = entries.windows(2).all(|w| w[0].0[3] == w[1].0[3]);
+    let bits = [5, 5, 5, if uniform_alpha { 0 } else { 3 }];
+    let sides = bits.map(|b| (1_usize << b) + 1);
+    let strides = [
+        sides[1] * sides[2] * sides[3],
+        sides[2] * sides[3],
+        sides[3],
+        1,
+    ];
+
+    let mut moments = vec![(0.0, [0.0; 4], 0.0); sides.iter().product()];
+    for &(color, n) in entries {
+        let index = (0..4)
+            .map(|c| ((usize::from(color[c]) >> (8 - bits[c])) + 1) * strides[c])
+            .sum::<usize>();
+        let (weight, sum, squares) = &mut moments[index];
+        let n = f64::from(n);
+
+        *weight += n;
+        for (s, &c) in sum.iter_mut().zip(&color) {
+            *s += n * f64::from(c);
+            *squares += n * f64::from(c).powi(2);
+        }
+    }
+
+    // Accumulate along each axis so that each cell holds the moments of all cells before it
+    for axis in 0..4 {
+        for i in 0..moments.len() {
+            if (i / strides[axis]) % sides[axis] > 0 {
+                let previous = moments[i - strides[axis]];
+                let (weight, sum, squares) = &mut moments[i];
+
+                *weight += previous.0;
+                for (s, p) in sum.iter_mut().zip(previous.1) {
+                    *s += p;
+                }
+                *squares += previous.2;
+            }
+        }
+    }
+
+    // The moments of the cells in the given box, where the lower bounds are exclusive and the
+    // upper bounds are inclusive
+    let volume = |(lower, upper): &Box| -> Moments {
+        (0..16_u32).fold(
+            (0.0, [0.0; 4], 0.0),
+            |(weight, mut sum, squares), corner| {
+                let index = (0..4)
+                    .map(|c| {
+                        let bound = if corner >> c & 1 == 1 { lower } else { upper };
+                        bound[c] * strides[c]
+                    })
+                    .sum::<usize>();
+                let sign = if corner.count_ones() % 2 == 0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                let (w, s, q) = moments[index];
+
+                for c in 0..4 {
+                    sum[c] += sign * s[c];
+                }
+                (weight + sign * w, sum, squares + sign * q)
+            },
+        )
+    };
+    let magnitude = |(weight, sum, _): &Moments| sum.iter().map(|s| s * s).sum::<f64>() / weight;
+    let variance = |bounds: &Box| {
+        if (0..4).all(|c| bounds.1[c] - bounds.0[c] <= 1) {
+            return 0.0;
+        }
+        let moments = volume(bounds);
+        if moments.0 <= 0.0 {
+            return 0.0;
+        }
+        moments.2 - magnitude(&moments)
+    };
+    // Finds the axis and position to split the given box at, minimizing the variance of the
+    // two halves
+    let cut = |bounds: &Box| {
+        let whole = volume(bounds);
+        let mut best = None;
+        let mut best_score = magnitude(&whole);
+
+        for axis in 0..4 {
+            for position in bounds.0[axis] + 1..bounds.1[axis] {
+                let mut upper = bounds.1;
+                upper[axis] = position;
+                let bottom = volume(&(bounds.0, upper));
+                let top = (
+                    whole.0 - bottom.0,
+                    [0, 1, 2, 3].map(|c| whole.1[c] - bottom.1[c]),
+                    whole.2 - bottom.2,
+                );
+                if bottom.0 <= 0.0 || top.0 <= 0.0 {
+                    continue;
+                }
+
+                let score = magnitude(&bottom) + magnitude(&top);
+                if score > best_score {
+                    best_score = score;
+                    best = Some((axis, position));
+                }
+            }
+        }
+        best
+    };
+
+    let mut boxes: Vec<Box> = vec![([0; 4], sides.map(|side| side - 1))];
+    let mut variances = vec![variance(&boxes[0])];
+    let mut next = 0;
+    while boxes.len() < size {
+        if let Some((axis, position)) = cut(&boxes[next]) {
+            let (lower, upper) = boxes[next];
+            let (mut bottom, mut top) = (upper, lower);
+            bottom[axis] = position;
+            top[axis] = position;
+
+            boxes[next] = (lower, bottom);
+            boxes.push((top, upper));
+            variances[next] = variance(&boxes[next]);
+            variances.push(variance(&(top, upper)));
+        } else {
+            variances[next] = 0.0;
+        }
+
+        next = (0..boxes.len()).fold(0, |best, i| {
+            if variances[i] > variances[best] {
+                i
+            } else {
+                best
+            }
+        });
+        if variances[next] <= 0.0 {
+            break;
+        }
+    }
+
+    boxes
+        .iter()
+        .map(volume)
+        .filter(|moments| moments.0 > 0.0)
+        .map(|(weight, sum, _)| sum.map(|s| (s / weight) as f32))
+        .collect()
+}
+
+/// Refines the given palette with k-means clustering, moving each entry to the average of the
+/// colors closest to it.
+fn refine(entries: &[Entry], palette: &mut [Color], iterations: u32) {
+    for _ in 0..iterations {
+        let mut clusters = vec![([0.0_f64; 4], 0.0_f64); palette.len()];
+        for &(color, n) in entries {
+            let color = color.map(f32::from);
+            let nearest = (0..palette.len()).fold(0, |best, i| {
+                if distance(&palette[i], &color) < distance(&palette[best], &color) {
+                    i
+                } else {
+                    best
+                }
+            });
+
+            let (sum, count) = &mut clusters[nearest];
+            for c in 0..4 {
+                sum[c] += f64::from(color[c]) * f64::from(n);
+            }
+            *count += f64::from(n);
+        }
+
+        let mut converged = true;
+        for (entry, (sum, count)) in palette.iter_mut().zip(clusters) {
+            if count > 0.0 {
+                let center = sum.map(|s| (s / count) as f32);
+                converged &= distance(entry, &center) < 0.25;
+                *entry = center;
+            }
+        }
+        if converged {
+            break;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
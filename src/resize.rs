@@ -95,29 +95,57 @@ impl FilterType {
                 ColorType::Rgba => ResizePixelType::U16x4,
                 ColorType::Dynamic => unreachable!(),
             },
+            32 => match color_type {
+                ColorType::Luma | ColorType::PaletteRgb | ColorType::PaletteRgba => {
+                    ResizePixelType::F32
+                }
+                ColorType::LumaA => ResizePixelType::F32x2,
+                ColorType::Rgb => ResizePixelType::F32x3,
+                ColorType::Rgba => ResizePixelType::F32x4,
+                ColorType::Dynamic => unreachable!(),
+            },
             _ => panic!("Unsupported bit depth"),
         };
 
-        let buffer = data.iter().flat_map(P::as_bytes).collect::<Vec<_>>();
+        let mut buffer = data.iter().flat_map(P::as_bytes).collect::<Vec<_>>();
+        // 16-bit and 32-bit subpixels are represented as big-endian bytes, however the resizer
+        // expects them in native endianness.
+        let subpixel_size = P::BIT_DEPTH as usize >> 3;
+        let swap = subpixel_size > 1 && cfg!(target_endian = "little");
+        if swap {
+            swap_subpixel_bytes(&mut buffer, subpixel_size);
+        }
         // We are able to unwrap here since we validated the buffer throughout the creation of the image.
         let src =
             ImageRef::new(src_width, src_height, &buffer, pixel_type).expect("Invalid buffer size");
         let mut dest = ImageOut::new(dst_width, dst_height, pixel_type);
 
         let mut resizer = Resizer::new();
-        let options = ResizeOptions::new().resize_alg(ResizeAlg::from(*self));
+        // Colors are resized with premultiplied alpha, so that the colors of transparent pixels
+        // do not bleed into their neighbors
+        let options = ResizeOptions::new()
+            .resize_alg(ResizeAlg::from(*self))
+            .use_alpha(color_type.has_alpha());
 
         // The pixel type is the same, we can unwrap here
         resizer.resize(&src, &mut dest, Some(&options)).unwrap();
 
         let bpp = color_type.channels() * ((P::BIT_DEPTH as usize + 7) >> 3);
-        dest.into_vec()
-            .chunks_exact(bpp)
-            .map(P::from_bytes)
-            .collect()
+        let mut buffer = dest.into_vec();
+        if swap {
+            swap_subpixel_bytes(&mut buffer, subpixel_size);
+        }
+
+        buffer.chunks_exact(bpp).map(P::from_bytes).collect()
     }
 }
 
+fn swap_subpixel_bytes(buffer: &mut [u8], subpixel_size: usize) {
+    buffer
+        .chunks_exact_mut(subpixel_size)
+        .for_each(<[u8]>::reverse);
+}
+
 fn resize_tiled<P: Pixel>(data: &[P], src_width: u32, dst_width: u32, dst_height: u32) -> Vec<P> {
     let chunks = data.chunks_exact(src_width as _);
 
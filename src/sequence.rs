@@ -269,9 +269,8 @@ impl<P: Pixel> ImageSequence<P> {
     ///   is corrupt.
     /// * [`UnknownEncodingFormat`][crate::Error::UnknownEncodingFormat]: Could not infer the
     ///   encoding from the image. Try explicitly specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     pub fn from_read_inferred<'a, R: Read + Write + 'a>(
         mut bytes: R,
     ) -> Result<Box<dyn FrameIterator<P> + 'a>>
@@ -307,9 +306,8 @@ impl<P: Pixel> ImageSequence<P> {
     ///
     /// # Errors
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     pub fn from_bytes<'a>(
         format: ImageFormat,
         bytes: &'a [u8],
@@ -340,9 +338,8 @@ impl<P: Pixel> ImageSequence<P> {
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     ///   specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     pub fn from_bytes_inferred<'a>(bytes: &'a [u8]) -> Result<Box<dyn FrameIterator<P> + 'a>>
     where
         P: 'a,
@@ -382,9 +379,8 @@ impl<P: Pixel> ImageSequence<P> {
     ///
     /// # Errors
     /// * An error occured during encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No encoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     pub fn encode(&self, encoding: ImageFormat, dest: &mut impl Write) -> Result<()> {
         encoding.run_sequence_encoder(self, dest)
     }
@@ -394,9 +390,8 @@ impl<P: Pixel> ImageSequence<P> {
     ///
     /// # Errors
     /// * An error occured during encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No encoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     pub fn save(&self, encoding: ImageFormat, path: impl AsRef<Path>) -> Result<()> {
         let mut file = File::create(path).map_err(Error::IoError)?;
         self.encode(encoding, &mut file)
@@ -414,9 +409,8 @@ impl<P: Pixel> ImageSequence<P> {
     /// # Errors
     /// * Could not infer encoding format.
     /// * An error occured during encoding.
-    ///
-    /// # Panics
-    /// * No encoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No encoder implementation for the given encoding format. Make sure
+    ///   the cargo feature for the format is enabled.
     pub fn save_inferred(&self, path: impl AsRef<Path>) -> Result<()> {
         let encoding = ImageFormat::from_path(path.as_ref())?;
 
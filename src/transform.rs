@@ -0,0 +1,562 @@
+//! Geometric transformations of images, such as rotations by arbitrary angles and affine or
+//! perspective warps.
+//!
+//! Transformation matrices map coordinates of the source image into coordinates of the
+//! transformed image, where the point `(x, y)` is the center of the pixel at `(x, y)`. Images are
+//! sampled in continuous coordinates however, where the pixel at `(x, y)` covers the area from
+//! `(x, y)` to `(x + 1, y + 1)` and its center lies at `(x + 0.5, y + 0.5)`. Matrices are shifted
+//! by half a pixel to account for this.
+
+use crate::{encodings::ColorType, Dynamic, Image, Pixel};
+
+/// The interpolation algorithm used to sample pixels when geometrically transforming an image.
+///
+/// With the `resize` feature, a [`ResizeAlgorithm`][crate::ResizeAlgorithm] can be used wherever
+/// an interpolation algorithm is expected, such as in [`RotateOptions::with_interpolation`] and
+/// [`WarpOptions::with_interpolation`]. It is converted into the closest interpolation algorithm.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// Uses the value of the nearest pixel. This is the fastest, but produces jagged edges.
+    ///
+    /// This is always used for paletted images, since interpolating between palette indices
+    /// is meaningless.
+    Nearest,
+    /// Linearly interpolates between the four nearest pixels. This is the default.
+    Bilinear,
+    /// Uses Catmull-Rom cubic interpolation between the sixteen nearest pixels. This is slower
+    /// than bilinear interpolation, but produces sharper results.
+    Bicubic,
+}
+
+impl Default for Interpolation {
+    fn default() -> Self {
+        Self::Bilinear
+    }
+}
+
+#[cfg(feature = "resize")]
+impl From<crate::ResizeAlgorithm> for Interpolation {
+    /// Resolves the interpolation algorithm closest to the given resizing algorithm. Algorithms
+    /// which only make sense when resizing resolve into [`Nearest`][Self::Nearest].
+    fn from(algorithm: crate::ResizeAlgorithm) -> Self {
+        use crate::ResizeAlgorithm as A;
+
+        match algorithm {
+            A::Nearest | A::Box | A::Tile => Self::Nearest,
+            A::Bilinear | A::Hamming => Self::Bilinear,
+            A::Bicubic | A::Mitchell | A::Lanczos3 => Self::Bicubic,
+        }
+    }
+}
+
+/// How pixels outside of the bounds of an image are resolved when they are sampled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeMode<P: Pixel> {
+    /// Pixels outside of the image are the given constant pixel.
+    Constant(P),
+    /// Pixels outside of the image are the nearest pixel on the edge of the image.
+    Clamp,
+    /// The image is repeated in a tiling fashion.
+    Wrap,
+    /// The image is mirrored at its edges, repeating the edge pixels: `cba|abc|cba`.
+    Reflect,
+    /// Pixels outside of the image are skipped entirely, and the weights of the remaining pixels
+    /// are rescaled to make up for them. For example, a blur near the edge of an image only
+    /// averages the pixels within the image.
+    ///
+    /// This is the default, which is closest to how filters behaved before edge handling was
+    /// configurable.
+    Skip,
+}
+
+impl<P: Pixel> Default for EdgeMode<P> {
+    fn default() -> Self {
+        Self::Skip
+    }
+}
+
+impl<P: Pixel> EdgeMode<P> {
+    /// Resolves the given coordinate along an axis of the given length into a coordinate within
+    /// the image, or `None` if the constant pixel should be used instead or the pixel should be
+    /// skipped.
+    #[must_use]
+    pub fn resolve(&self, coord: i64, len: u32) -> Option<u32> {
+        let len = i64::from(len);
+        if (0..len).contains(&coord) {
+            return Some(coord as u32);
+        }
+
+        match self {
+            Self::Constant(_) | Self::Skip => None,
+            Self::Clamp => Some(coord.clamp(0, len - 1) as u32),
+            Self::Wrap => Some(coord.rem_euclid(len) as u32),
+            Self::Reflect => {
+                let coord = coord.rem_euclid(len * 2);
+                Some(if coord < len {
+                    coord
+                } else {
+                    len * 2 - 1 - coord
+                } as u32)
+            }
+        }
+    }
+}
+
+/// Options for rotating an image by an arbitrary angle.
+///
+/// # See Also
+/// * [`Image::rotate_by`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RotateOptions<P: Pixel> {
+    /// The interpolation algorithm used to sample pixels. Defaults to
+    /// [`Interpolation::Bilinear`].
+    pub interpolation: Interpolation,
+    /// Whether to expand the dimensions of the image to fit the entire rotated image. If this is
+    /// `false`, the image keeps its dimensions and the corners of the rotated image are cropped
+    /// off. Defaults to `false`.
+    pub expand: bool,
+    /// The pixel used to fill areas which are not covered by the rotated image. Defaults to the
+    /// default pixel value, which is transparent for pixels with an alpha channel.
+    pub fill: P,
+}
+
+impl<P: Pixel> RotateOptions<P> {
+    /// Creates new rotation options with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the interpolation algorithm used to sample pixels. With the `resize` feature, this
+    /// also accepts a [`ResizeAlgorithm`][crate::ResizeAlgorithm].
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: impl Into<Interpolation>) -> Self {
+        self.interpolation = interpolation.into();
+        self
+    }
+
+    /// Sets whether to expand the dimensions of the image to fit the entire rotated image.
+    #[must_use]
+    pub const fn with_expand(mut self, expand: bool) -> Self {
+        self.expand = expand;
+        self
+    }
+
+    /// Sets the pixel used to fill areas which are not covered by the rotated image.
+    #[must_use]
+    pub const fn with_fill(mut self, fill: P) -> Self {
+        self.fill = fill;
+        self
+    }
+}
+
+/// Options for warping an image with an affine or perspective transformation.
+///
+/// # See Also
+/// * [`Image::warp_affine`]
+/// * [`Image::warp_perspective`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WarpOptions<P: Pixel> {
+    /// The interpolation algorithm used to sample pixels. Defaults to
+    /// [`Interpolation::Bilinear`].
+    pub interpolation: Interpolation,
+    /// How pixels outside of the source image are resolved. Defaults to a transparent (or black,
+    /// for pixels without an alpha channel) [`EdgeMode::Constant`].
+    pub edge_mode: EdgeMode<P>,
+    /// The dimensions of the warped image, or `None` to keep the dimensions of the source image.
+    /// Defaults to `None`.
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl<P: Pixel> Default for WarpOptions<P> {
+    fn default() -> Self {
+        Self {
+            interpolation: Interpolation::default(),
+            edge_mode: EdgeMode::Constant(P::default()),
+            dimensions: None,
+        }
+    }
+}
+
+impl<P: Pixel> WarpOptions<P> {
+    /// Creates new warp options with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the interpolation algorithm used to sample pixels. With the `resize` feature, this
+    /// also accepts a [`ResizeAlgorithm`][crate::ResizeAlgorithm].
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: impl Into<Interpolation>) -> Self {
+        self.interpolation = interpolation.into();
+        self
+    }
+
+    /// Sets how pixels outside of the source image are resolved.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge_mode: EdgeMode<P>) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// Sets the dimensions of the warped image.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    #[must_use]
+    pub fn with_dimensions(mut self, width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "dimensions must be non-zero");
+        self.dimensions = Some((width, height));
+        self
+    }
+}
+
+/// Computes the perspective transformation (homography) which maps each of the four `from`
+/// points onto the corresponding `to` point. The resulting matrix can be passed to
+/// [`Image::warp_perspective`].
+///
+/// Returns `None` if no such transformation exists, which is the case when three or more of
+/// either set of points are collinear.
+///
+/// # Example
+/// Deskewing a photographed document into an upright 850x1100 image, given the positions of the
+/// corners of the document in the photo:
+///
+/// ```no_run
+/// # use ril::prelude::*;
+/// # use ril::transform::homography;
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::<Rgb>::open("document.jpg")?;
+/// let corners = [(112.0, 84.0), (940.0, 130.0), (1010.0, 1250.0), (60.0, 1190.0)];
+/// let target = [(0.0, 0.0), (849.0, 0.0), (849.0, 1099.0), (0.0, 1099.0)];
+///
+/// let matrix = homography(corners, target).expect("corners are degenerate");
+/// image.warp_perspective(matrix, WarpOptions::new().with_dimensions(850, 1100));
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn homography(from: [(f64, f64); 4], to: [(f64, f64); 4]) -> Option<[[f64; 3]; 3]> {
+    // Solve the linear system for the eight unknown entries of the matrix, where the last entry
+    // is fixed to 1.
+    let mut system = [[0.0; 9]; 8];
+    for (i, ((x, y), (u, v))) in from.into_iter().zip(to).enumerate() {
+        system[i * 2] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y, u];
+        system[i * 2 + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y, v];
+    }
+
+    // Gaussian elimination with partial pivoting
+    for col in 0..8 {
+        let pivot = (col..8).max_by(|&a, &b| {
+            system[a][col]
+                .abs()
+                .partial_cmp(&system[b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        if system[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        system.swap(col, pivot);
+
+        let pivot = system[col];
+        for (i, row) in system.iter_mut().enumerate() {
+            if i != col {
+                let factor = row[col] / pivot[col];
+                for (value, p) in row.iter_mut().zip(pivot).skip(col) {
+                    *value -= factor * p;
+                }
+            }
+        }
+    }
+
+    let h = |i: usize| system[i][8] / system[i][i];
+    Some([[h(0), h(1), h(2)], [h(3), h(4), h(5)], [h(6), h(7), 1.0]])
+}
+
+/// Inverts the given 3x3 matrix, returning `None` if it is singular.
+pub(crate) fn invert_matrix(m: [[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let [[a, b, c], [d, e, f], [g, h, i]] = m;
+    let (x, y, z) = (e * i - f * h, f * g - d * i, d * h - e * g);
+    let det = a * x + b * y + c * z;
+    if det.abs() < f64::EPSILON {
+        return None;
+    }
+
+    Some([
+        [x / det, (c * h - b * i) / det, (b * f - c * e) / det],
+        [y / det, (a * i - c * g) / det, (c * d - a * f) / det],
+        [z / det, (b * g - a * h) / det, (a * e - b * d) / det],
+    ])
+}
+
+/// Samples pixels of an image at continuous coordinates, where the center of the pixel at
+/// `(x, y)` lies at `(x + 0.5, y + 0.5)`. Coordinates outside of the image are resolved through
+/// the given [`EdgeMode`].
+///
+/// Subpixels are interpolated in premultiplied alpha, so fully transparent pixels do not bleed
+/// their color into their neighbors.
+pub(crate) struct Sampler<'a, P: Pixel> {
+    image: &'a Image<P>,
+    interpolation: Interpolation,
+    edge_mode: EdgeMode<P>,
+    channels: usize,
+    subpixel_size: usize,
+    alpha: bool,
+    max: f32,
+    samples: Vec<f32>,
+    fill_samples: Vec<f32>,
+}
+
+impl<'a, P: Pixel> Sampler<'a, P> {
+    pub fn new(
+        image: &'a Image<P>,
+        mut interpolation: Interpolation,
+        edge_mode: EdgeMode<P>,
+    ) -> Self {
+        // Dynamic pixels are interpolated as RGBA since each pixel may have a different color type
+        let (channels, alpha) = match P::COLOR_TYPE {
+            ColorType::PaletteRgb | ColorType::PaletteRgba => {
+                interpolation = Interpolation::Nearest;
+                (1, false)
+            }
+            ColorType::Dynamic => (4, true),
+            color_type => (color_type.channels(), color_type.has_alpha()),
+        };
+        let subpixel_size = match P::COLOR_TYPE {
+            ColorType::Dynamic => 1,
+            _ => (P::BIT_DEPTH as usize + 7) >> 3,
+        };
+
+        let mut sampler = Self {
+            image,
+            interpolation,
+            edge_mode,
+            channels,
+            subpixel_size,
+            alpha,
+            max: match subpixel_size {
+                1 => 255.0,
+                2 => 65535.0,
+                _ => 1.0,
+            },
+            samples: Vec::new(),
+            fill_samples: Vec::new(),
+        };
+
+        if interpolation != Interpolation::Nearest {
+            let mut samples = Vec::with_capacity(image.data.len() * channels);
+            for pixel in &image.data {
+                sampler.decode(*pixel, &mut samples);
+            }
+            sampler.samples = samples;
+
+            if let EdgeMode::Constant(fill) = edge_mode {
+                let mut fill_samples = Vec::with_capacity(channels);
+                sampler.decode(fill, &mut fill_samples);
+                sampler.fill_samples = fill_samples;
+            }
+        }
+        sampler
+    }
+
+    /// Decodes the subpixels of the given pixel, premultiplying them by alpha if necessary.
+    fn decode(&self, pixel: P, out: &mut Vec<f32>) {
+        let start = out.len();
+        let bytes = if P::COLOR_TYPE == ColorType::Dynamic {
+            pixel.as_rgba().as_bytes().to_vec()
+        } else {
+            pixel.as_bytes().as_ref().to_vec()
+        };
+
+        out.extend(
+            bytes
+                .chunks_exact(self.subpixel_size)
+                .take(self.channels)
+                .map(|subpixel| match *subpixel {
+                    [a] => f32::from(a),
+                    [a, b] => f32::from(u16::from_be_bytes([a, b])),
+                    [a, b, c, d] => f32::from_be_bytes([a, b, c, d]),
+                    _ => unreachable!("unsupported subpixel size"),
+                }),
+        );
+
+        if self.alpha {
+            let (color, alpha) = out[start..].split_at_mut(self.channels - 1);
+            let alpha = alpha[0] / self.max;
+            for c in color {
+                *c *= alpha;
+            }
+        }
+    }
+
+    /// Encodes the given premultiplied subpixels back into a pixel.
+    fn encode(&self, mut subpixels: [f32; 4]) -> P {
+        let subpixels = &mut subpixels[..self.channels];
+
+        if self.alpha {
+            let (color, alpha) = subpixels.split_at_mut(self.channels - 1);
+            let alpha = &mut alpha[0];
+            *alpha = alpha.clamp(0.0, self.max);
+
+            let factor = if *alpha > 0.0 { self.max / *alpha } else { 0.0 };
+            for c in color {
+                *c *= factor;
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(self.channels * self.subpixel_size);
+        for &subpixel in subpixels.iter() {
+            match self.subpixel_size {
+                1 => bytes.push(subpixel.round().clamp(0.0, self.max) as u8),
+                2 => bytes.extend((subpixel.round().clamp(0.0, self.max) as u16).to_be_bytes()),
+                // floating-point subpixels are left unclamped to allow for HDR values
+                _ => bytes.extend(subpixel.to_be_bytes()),
+            }
+        }
+
+        if P::COLOR_TYPE == ColorType::Dynamic {
+            P::from_dynamic(Dynamic::from_bytes(&bytes))
+        } else {
+            P::from_bytes(&bytes)
+        }
+    }
+
+    /// Resolves the given pixel coordinates into an index into the image data, or `None` if the
+    /// constant edge pixel should be used instead.
+    fn resolve(&self, x: i64, y: i64) -> Option<usize> {
+        let x = self.edge_mode.resolve(x, self.image.width())?;
+        let y = self.edge_mode.resolve(y, self.image.height())?;
+
+        Some(y as usize * self.image.width() as usize + x as usize)
+    }
+
+    /// Returns the samples of the pixel at the given coordinates, or `None` if it is skipped.
+    fn tap(&self, x: i64, y: i64) -> Option<&[f32]> {
+        match self.resolve(x, y) {
+            Some(index) => Some(&self.samples[index * self.channels..(index + 1) * self.channels]),
+            None if self.edge_mode == EdgeMode::Skip => None,
+            None => Some(&self.fill_samples),
+        }
+    }
+
+    fn fill(&self) -> P {
+        match self.edge_mode {
+            EdgeMode::Constant(fill) => fill,
+            _ => P::default(),
+        }
+    }
+
+    /// Samples the image at the given continuous coordinates.
+    pub fn sample(&self, x: f64, y: f64) -> P {
+        let (width, height) = (
+            f64::from(self.image.width()),
+            f64::from(self.image.height()),
+        );
+
+        // e.g. points mapped behind the camera of a perspective transformation
+        if !x.is_finite() || !y.is_finite() {
+            return self.fill();
+        }
+
+        if self.interpolation == Interpolation::Nearest {
+            return self
+                .resolve(x.floor() as i64, y.floor() as i64)
+                .map_or_else(|| self.fill(), |index| self.image.data[index]);
+        }
+
+        // Pixels entirely outside of the interpolation window sample only the constant pixel
+        let margin = if self.interpolation == Interpolation::Bicubic {
+            2.0
+        } else {
+            1.0
+        };
+        if matches!(self.edge_mode, EdgeMode::Constant(_) | EdgeMode::Skip)
+            && (x <= -margin + 0.5
+                || y <= -margin + 0.5
+                || x >= width + margin - 0.5
+                || y >= height + margin - 0.5)
+        {
+            return self.fill();
+        }
+
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = ((x - x0) as f32, (y - y0) as f32);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let mut out = [0.0; 4];
+        let mut covered = 0.0;
+        let mut accumulate = |tx: i64, ty: i64, weight: f32| {
+            if weight != 0.0 {
+                if let Some(samples) = self.tap(tx, ty) {
+                    for (o, s) in out.iter_mut().zip(samples) {
+                        *o += s * weight;
+                    }
+                    covered += weight;
+                }
+            }
+        };
+
+        match self.interpolation {
+            Interpolation::Bilinear => {
+                accumulate(x0, y0, (1.0 - fx) * (1.0 - fy));
+                accumulate(x0 + 1, y0, fx * (1.0 - fy));
+                accumulate(x0, y0 + 1, (1.0 - fx) * fy);
+                accumulate(x0 + 1, y0 + 1, fx * fy);
+            }
+            _ => {
+                let (wx, wy) = (catmull_rom_weights(fx), catmull_rom_weights(fy));
+                for (j, wy) in wy.into_iter().enumerate() {
+                    for (i, wx) in wx.into_iter().enumerate() {
+                        accumulate(x0 + i as i64 - 1, y0 + j as i64 - 1, wx * wy);
+                    }
+                }
+            }
+        }
+
+        if self.edge_mode == EdgeMode::Skip && covered != 1.0 {
+            if covered.abs() < f32::EPSILON {
+                return self.fill();
+            }
+            for o in &mut out {
+                *o /= covered;
+            }
+        }
+        self.encode(out)
+    }
+}
+
+/// Returns the weights of the four Catmull-Rom taps surrounding a point at offset `t` between the
+/// second and third taps.
+fn catmull_rom_weights(t: f32) -> [f32; 4] {
+    let (t2, t3) = (t * t, t * t * t);
+
+    [
+        0.5 * (-t3 + 2.0 * t2 - t),
+        0.5 * (3.0 * t3 - 5.0 * t2 + 2.0),
+        0.5 * (-3.0 * t3 + 4.0 * t2 + t),
+        0.5 * (t3 - t2),
+    ]
+}
+
+/// Renders an image of the given dimensions, where each output pixel samples the source image at
+/// the position returned by `map` for the center of the output pixel.
+pub(crate) fn remap<P: Pixel>(
+    image: &Image<P>,
+    width: u32,
+    height: u32,
+    interpolation: Interpolation,
+    edge_mode: EdgeMode<P>,
+    map: impl Fn(f64, f64) -> (f64, f64),
+) -> Vec<P> {
+    let sampler = Sampler::new(image, interpolation, edge_mode);
+
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let (sx, sy) = map(f64::from(x) + 0.5, f64::from(y) + 0.5);
+            sampler.sample(sx, sy)
+        })
+        .collect()
+}
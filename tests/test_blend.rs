@@ -0,0 +1,110 @@
+use ril::prelude::*;
+
+const BASE: Rgba = Rgba::new(200, 100, 50, 255);
+const OVERLAY: Rgba = Rgba::new(100, 150, 255, 255);
+
+#[test]
+fn test_separable_blend_modes() {
+    let blend = |mode| BASE.blend(OVERLAY, mode);
+
+    assert_eq!(blend(BlendMode::Normal), OVERLAY);
+    assert_eq!(blend(BlendMode::Multiply), Rgba::new(78, 59, 50, 255));
+    assert_eq!(blend(BlendMode::Screen), Rgba::new(222, 191, 255, 255));
+    assert_eq!(blend(BlendMode::Darken), Rgba::new(100, 100, 50, 255));
+    assert_eq!(blend(BlendMode::Lighten), Rgba::new(200, 150, 255, 255));
+    assert_eq!(blend(BlendMode::Difference), Rgba::new(100, 50, 205, 255));
+
+    // Multiplying by white and screening with black leave the base unchanged
+    assert_eq!(BASE.blend(Rgba::white(), BlendMode::Multiply), BASE);
+    assert_eq!(BASE.blend(Rgba::black(), BlendMode::Screen), BASE);
+    // Overlay is hard light with the layers swapped
+    assert_eq!(
+        BASE.blend(OVERLAY, BlendMode::Overlay),
+        OVERLAY.blend(BASE, BlendMode::HardLight),
+    );
+}
+
+#[test]
+fn test_non_separable_blend_modes() {
+    let gray = Rgba::new(128, 128, 128, 255);
+    let red = Rgba::new(255, 0, 0, 255);
+
+    // Gray has no hue or saturation, so taking the color of red tints it while keeping its
+    // luminosity
+    let tinted = gray.blend(red, BlendMode::Color);
+    assert!(tinted.r > tinted.g && tinted.g == tinted.b);
+    assert!((i32::from(Luma::from(tinted).value()) - 128).abs() <= 2);
+
+    // Taking the luminosity or saturation of gray removes the color of red
+    let desaturated = red.blend(gray, BlendMode::Saturation);
+    assert_eq!(
+        (desaturated.r, desaturated.g),
+        (desaturated.g, desaturated.b)
+    );
+    let hue = gray.blend(red, BlendMode::Hue);
+    assert_eq!((hue.r, hue.g), (hue.g, hue.b));
+}
+
+#[test]
+fn test_blend_alpha() {
+    // A transparent overlay leaves the base unchanged, regardless of the blend mode
+    let transparent = Rgba::new(0, 0, 0, 0);
+    assert_eq!(BASE.blend(transparent, BlendMode::Difference), BASE);
+    assert_eq!(BASE.blend_with_alpha(OVERLAY, BlendMode::Multiply, 0), BASE);
+
+    // A half transparent overlay is halfway between the base and the blended color
+    let half = BASE.blend_with_alpha(OVERLAY, BlendMode::Multiply, 128);
+    assert_eq!(half, Rgba::new(139, 79, 50, 255));
+
+    // Over a transparent base, the overlay is used as is
+    assert_eq!(transparent.blend(OVERLAY, BlendMode::Multiply), OVERLAY);
+}
+
+#[test]
+fn test_blend_pixel_types() {
+    assert_eq!(
+        Luma::new(200).blend(Luma::new(100), BlendMode::Multiply),
+        Luma::new(78)
+    );
+    assert_eq!(
+        Rgb::new(200, 100, 50).overlay(
+            Rgb::new(100, 150, 255),
+            OverlayMode::Blend(BlendMode::Screen)
+        ),
+        Rgb::new(222, 191, 255)
+    );
+    assert_eq!(
+        Rgba16::new(u16::MAX, 0, u16::MAX, u16::MAX).blend(
+            Rgba16::new(u16::MAX / 2, u16::MAX, u16::MAX / 2, u16::MAX),
+            BlendMode::Darken,
+        ),
+        Rgba16::new(u16::MAX / 2, 0, u16::MAX / 2, u16::MAX)
+    );
+
+    // HDR colors are clamped before blending, but kept when blending normally
+    let hdr = RgbaF32::new(4.0, 0.5, -1.0, 1.0);
+    assert_eq!(
+        RgbaF32::new(0.5, 0.5, 0.5, 1.0).blend(hdr, BlendMode::Screen),
+        RgbaF32::new(1.0, 0.75, 0.5, 1.0)
+    );
+    assert_eq!(RgbaF32::black().blend(hdr, BlendMode::Normal), hdr);
+}
+
+#[test]
+fn test_blend_draw() {
+    let mode = OverlayMode::Blend(BlendMode::Multiply);
+
+    let mut image = Image::new(8, 8, BASE);
+    image.paste(0, 0, &Image::new(4, 8, OVERLAY));
+    assert_eq!(image.pixel(0, 0), &OVERLAY);
+
+    let mut image = Image::new(8, 8, BASE);
+    image.draw(&Paste::new(&Image::new(4, 8, OVERLAY)).with_overlay_mode(mode));
+    assert_eq!(image.pixel(0, 0), &Rgba::new(78, 59, 50, 255));
+    assert_eq!(image.pixel(4, 0), &BASE);
+
+    let mut image = Image::new(8, 8, BASE).with_overlay_mode(mode);
+    image.draw(&Rectangle::from_bounding_box(4, 0, 8, 8).with_fill(OVERLAY));
+    assert_eq!(image.pixel(0, 0), &BASE);
+    assert_eq!(image.pixel(4, 0), &Rgba::new(78, 59, 50, 255));
+}
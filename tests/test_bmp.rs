@@ -0,0 +1,158 @@
+use ril::encodings::bmp::{BmpEncoder, BmpEncoderOptions};
+use ril::prelude::*;
+
+fn gradient() -> Image<Rgb> {
+    Image::from_fn(37, 19, |x, y| Rgb::new(x as u8 * 6, y as u8 * 13, 128))
+}
+
+#[test]
+fn test_bmp_rgb_roundtrip() -> ril::Result<()> {
+    let image = gradient();
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::Bmp, &mut buffer)?;
+
+    assert_eq!(ImageFormat::infer_encoding(&buffer), ImageFormat::Bmp);
+    let decoded = Image::<Rgb>::from_bytes_inferred(&buffer)?;
+    assert_eq!(decoded.dimensions(), (37, 19));
+    assert_eq!(decoded.data, image.data);
+
+    image.save_inferred("tests/out/bmp_encode_output.bmp")
+}
+
+#[test]
+fn test_bmp_rgba_roundtrip() -> ril::Result<()> {
+    let image = Image::from_fn(16, 9, |x, y| {
+        Rgba::new(x as u8 * 16, y as u8 * 28, 64, x as u8 * 17)
+    });
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::Bmp, &mut buffer)?;
+
+    let decoded = Image::<Rgba>::from_bytes(ImageFormat::Bmp, &buffer)?;
+    assert_eq!(decoded.data, image.data);
+    Ok(())
+}
+
+#[test]
+fn test_bmp_paletted_rle_roundtrip() -> ril::Result<()> {
+    let image = Image::<PalettedRgb>::from_paletted_pixels(
+        5,
+        vec![Rgb::white(), Rgb::black(), Rgb::new(255, 0, 0)],
+        vec![0, 0, 0, 1, 2, 2, 2, 2, 2, 1, 0, 1, 0, 1, 0],
+    );
+    let mut buffer = Vec::new();
+    let metadata =
+        EncoderMetadata::from(&image).with_config(BmpEncoderOptions::new().with_rle(true));
+    let mut encoder = BmpEncoder::new(&mut buffer, metadata)?;
+    encoder.add_frame(&image)?;
+    encoder.finish()?;
+
+    let decoded = Image::<PalettedRgb>::from_bytes(ImageFormat::Bmp, &buffer)?;
+    assert_eq!(decoded.palette(), image.palette());
+    assert_eq!(
+        decoded.data.iter().map(|p| p.index).collect::<Vec<_>>(),
+        image.data.iter().map(|p| p.index).collect::<Vec<_>>(),
+    );
+
+    let flattened = Image::<Rgb>::from_bytes(ImageFormat::Bmp, &buffer)?;
+    assert_eq!(flattened.pixel(4, 0), &Rgb::new(255, 0, 0));
+    Ok(())
+}
+
+#[test]
+fn test_bmp_bitpixel_roundtrip() -> ril::Result<()> {
+    let image = Image::from_fn(13, 3, |x, y| BitPixel::new((x + y) % 3 == 0));
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::Bmp, &mut buffer)?;
+
+    let decoded = Image::<BitPixel>::from_bytes(ImageFormat::Bmp, &buffer)?;
+    assert_eq!(decoded.data, image.data);
+    Ok(())
+}
+
+/// Builds a BMP file with a `BITMAPINFOHEADER` around the given palette and pixel data.
+fn build_bmp(
+    width: i32,
+    height: i32,
+    bpp: u16,
+    compression: u32,
+    extra: &[u8],
+    data: &[u8],
+) -> Vec<u8> {
+    let offset = 14 + 40 + extra.len() as u32;
+    let mut out = b"BM".to_vec();
+    out.extend((offset + data.len() as u32).to_le_bytes());
+    out.extend([0; 4]);
+    out.extend(offset.to_le_bytes());
+    out.extend(40_u32.to_le_bytes());
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    out.extend(1_u16.to_le_bytes());
+    out.extend(bpp.to_le_bytes());
+    out.extend(compression.to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out.extend([0; 8]);
+    // colors used, only meaningful when `extra` is a palette
+    out.extend((extra.len() as u32 / 4).to_le_bytes());
+    out.extend([0; 4]);
+    out.extend(extra);
+    out.extend(data);
+    out
+}
+
+#[test]
+fn test_bmp_rle4_decode() -> ril::Result<()> {
+    // palette: black, red
+    let palette = [0, 0, 0, 0, 0, 0, 255, 0];
+    let data = [
+        // bottom row: 3 pixels alternating 1, 0, 1 then end of line
+        3, 0x10, 0, 0,
+        // top row: absolute run of 3 pixels 0, 1, 1 (padded to a word) then end of bitmap
+        0, 3, 0x01, 0x10, 0, 1,
+    ];
+    let bytes = build_bmp(3, 2, 4, 2, &palette, &data);
+
+    let image = Image::<Rgb>::from_bytes(ImageFormat::Bmp, bytes)?;
+    let red = Rgb::new(255, 0, 0);
+    assert_eq!(
+        image.data,
+        vec![Rgb::black(), red, red, red, Rgb::black(), red]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bmp_16bit_top_down_decode() -> ril::Result<()> {
+    // RGB555, two pixels per row (no padding needed), stored top-down
+    let pixels: [u16; 4] = [0x7C00, 0x03E0, 0x001F, 0x7FFF];
+    let data = pixels
+        .iter()
+        .flat_map(|p| p.to_le_bytes())
+        .collect::<Vec<_>>();
+    let bytes = build_bmp(2, -2, 16, 0, &[], &data);
+
+    let image = Image::<Rgb>::from_bytes(ImageFormat::Bmp, bytes)?;
+    assert_eq!(
+        image.data,
+        vec![
+            Rgb::new(255, 0, 0),
+            Rgb::new(0, 255, 0),
+            Rgb::new(0, 0, 255),
+            Rgb::white(),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bmp_oversized_dimensions() {
+    // A header claiming a huge image with almost no pixel data must be rejected rather than
+    // attempting to allocate the image
+    for bits in [8, 24] {
+        let palette = if bits == 8 { vec![0; 8] } else { Vec::new() };
+        let bytes = build_bmp(0x7FFF_FFFF, 0x7FFF_FFFF, bits, 0, &palette, &[0; 16]);
+        assert!(matches!(
+            Image::<Rgb>::from_bytes(ImageFormat::Bmp, bytes),
+            Err(ril::Error::DecodingError(_))
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+use ril::prelude::*;
+
+const SOURCE: Rgba = Rgba::new(255, 0, 0, 128);
+const DESTINATION: Rgba = Rgba::new(0, 0, 255, 255);
+
+#[test]
+fn test_composite_operators() {
+    let composite = |operator| DESTINATION.composite(SOURCE, operator);
+    let transparent = Rgba::transparent();
+
+    assert_eq!(composite(CompositeOperator::Clear), transparent);
+    assert_eq!(composite(CompositeOperator::Source), SOURCE);
+    assert_eq!(composite(CompositeOperator::Destination), DESTINATION);
+    assert_eq!(
+        composite(CompositeOperator::SourceOver),
+        Rgba::new(128, 0, 127, 255)
+    );
+    assert_eq!(composite(CompositeOperator::DestinationOver), DESTINATION);
+    assert_eq!(composite(CompositeOperator::SourceIn), SOURCE);
+    assert_eq!(
+        composite(CompositeOperator::DestinationIn),
+        Rgba::new(0, 0, 255, 128)
+    );
+    assert_eq!(composite(CompositeOperator::SourceOut), transparent);
+    assert_eq!(
+        composite(CompositeOperator::DestinationOut),
+        Rgba::new(0, 0, 255, 127)
+    );
+    assert_eq!(
+        composite(CompositeOperator::SourceAtop),
+        Rgba::new(128, 0, 127, 255)
+    );
+    assert_eq!(
+        composite(CompositeOperator::DestinationAtop),
+        Rgba::new(0, 0, 255, 128)
+    );
+    assert_eq!(composite(CompositeOperator::Xor), Rgba::new(0, 0, 255, 127));
+
+    // Over a transparent destination, only operators which keep the source outside of the
+    // destination keep anything at all
+    let composite = |operator| transparent.composite(SOURCE, operator);
+    assert_eq!(composite(CompositeOperator::SourceOver), SOURCE);
+    assert_eq!(composite(CompositeOperator::SourceOut), SOURCE);
+    assert_eq!(composite(CompositeOperator::Xor), SOURCE);
+    assert_eq!(composite(CompositeOperator::SourceIn), transparent);
+    assert_eq!(composite(CompositeOperator::SourceAtop), transparent);
+}
+
+#[test]
+fn test_composite_alpha_representations() {
+    let source = [1.0, 0.5, 0.0, 0.5];
+    let destination = [0.0, 0.0, 1.0, 0.8];
+    let premultiply = |[r, g, b, a]: [f32; 4]| [r * a, g * a, b * a, a];
+
+    for operator in [
+        CompositeOperator::SourceOver,
+        CompositeOperator::SourceAtop,
+        CompositeOperator::DestinationOut,
+        CompositeOperator::Xor,
+    ] {
+        let straight = premultiply(operator.composite(source, destination));
+        let premultiplied =
+            operator.composite_premultiplied(premultiply(source), premultiply(destination));
+
+        for (a, b) in straight.iter().zip(premultiplied) {
+            assert!(
+                (a - b).abs() < 1e-5,
+                "{operator}: {straight:?} != {premultiplied:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_composite_image() {
+    let mut image = Image::new(8, 8, DESTINATION);
+    let mut stencil = Image::new(4, 4, Rgba::transparent());
+    stencil.draw(&Rectangle::from_bounding_box(0, 0, 2, 4).with_fill(Rgba::black()));
+
+    // Cut the stencil out of the image, leaving pixels outside of the stencil untouched
+    image.composite(2, 2, &stencil, CompositeOperator::DestinationOut);
+    assert_eq!(image.pixel(2, 2), &Rgba::transparent());
+    assert_eq!(image.pixel(4, 2), &DESTINATION);
+    assert_eq!(image.pixel(0, 0), &DESTINATION);
+
+    // Masked out pixels are also left untouched
+    let mut image = Image::new(8, 8, DESTINATION);
+    let mut mask = Image::new(4, 4, BitPixel::on());
+    mask.set_pixel(0, 0, BitPixel::off());
+    image.draw(
+        &Paste::new(&stencil)
+            .with_mask(&mask)
+            .with_overlay_mode(OverlayMode::Composite(CompositeOperator::Clear)),
+    );
+    assert_eq!(image.pixel(0, 0), &DESTINATION);
+    assert_eq!(image.pixel(0, 1), &Rgba::transparent());
+
+    // Pixels without alpha are treated as opaque
+    let mut image = Image::new(2, 1, Luma::new(200));
+    image.composite(
+        1,
+        0,
+        &Image::new(1, 1, Luma::new(100)),
+        CompositeOperator::Clear,
+    );
+    assert_eq!(image.data, [Luma::new(200), Luma::new(0)]);
+}
+
+#[test]
+fn test_composite_entity() {
+    let mut stencil = Image::new(4, 4, Rgba::transparent());
+    stencil.draw(&Rectangle::from_bounding_box(0, 0, 2, 4).with_fill(Rgba::black()));
+
+    // Bounded composites only affect the pixels covered by the source
+    let mut image = Image::new(8, 8, DESTINATION);
+    image.draw(&Composite::new(&stencil, CompositeOperator::DestinationIn).with_position(2, 2));
+    assert_eq!(image.pixel(2, 2), &DESTINATION);
+    assert_eq!(image.pixel(4, 2), &Rgba::transparent());
+    assert_eq!(image.pixel(0, 0), &DESTINATION);
+
+    // Unbounded composites treat the rest of the destination as covered by a transparent source
+    let mut image = Image::new(8, 8, DESTINATION);
+    let mut mask = Image::new(4, 4, BitPixel::on());
+    mask.set_pixel(0, 0, BitPixel::off());
+    image.draw(
+        &Composite::new(&stencil, CompositeOperator::DestinationIn)
+            .with_position(2, 2)
+            .with_mask(&mask)
+            .with_unbounded(true),
+    );
+    assert_eq!(image.pixel(2, 3), &DESTINATION);
+    assert_eq!(image.pixel(4, 2), &Rgba::transparent());
+    assert_eq!(image.pixel(0, 0), &Rgba::transparent());
+    // Masked out pixels are left untouched
+    image.draw(
+        &Composite::new(&stencil, CompositeOperator::Clear)
+            .with_position(2, 2)
+            .with_mask(&mask),
+    );
+    assert_eq!(image.pixel(2, 2), &DESTINATION);
+    assert_eq!(image.pixel(2, 3), &Rgba::transparent());
+
+    // Pixels without alpha cannot be transparent, so unbounded composites stay bounded
+    let mut image = Image::new(2, 1, Luma::new(200));
+    image.draw(
+        &Composite::new(&Image::new(1, 1, Luma::new(100)), CompositeOperator::Clear)
+            .with_unbounded(true),
+    );
+    assert_eq!(image.data, [Luma::new(0), Luma::new(200)]);
+}
@@ -0,0 +1,108 @@
+use ril::prelude::*;
+use ril::Quantizer;
+
+const METHODS: [Dither; 8] = [
+    Dither::FloydSteinberg,
+    Dither::Atkinson,
+    Dither::JarvisJudiceNinke,
+    Dither::Sierra,
+    Dither::Bayer2,
+    Dither::Bayer4,
+    Dither::Bayer8,
+    Dither::BlueNoise,
+];
+
+/// The average brightness of each 16x16 block of the image, between `0.0` and `255.0`.
+fn block_means(image: &Image<Rgb>) -> Vec<f64> {
+    (0..image.height() / 16)
+        .flat_map(|by| (0..image.width() / 16).map(move |bx| (bx, by)))
+        .map(|(bx, by)| {
+            let sum = (0..16)
+                .flat_map(|y| (0..16).map(move |x| (bx * 16 + x, by * 16 + y)))
+                .map(|(x, y)| {
+                    let pixel = image.pixel(x, y);
+                    f64::from(pixel.r) + f64::from(pixel.g) + f64::from(pixel.b)
+                })
+                .sum::<f64>();
+            sum / (16.0 * 16.0 * 3.0)
+        })
+        .collect()
+}
+
+#[test]
+fn test_dither_bitpixel() {
+    let gradient = Image::from_fn(128, 32, |x, _| Luma::new((x * 2) as u8));
+    assert_eq!(
+        gradient.dither(Dither::None).data,
+        gradient.clone().convert::<BitPixel>().data
+    );
+
+    let expected = block_means(&gradient.clone().convert());
+    for method in METHODS {
+        let dithered = gradient.dither(method);
+        // Dithering preserves the average brightness of each region, except that Atkinson
+        // dithering discards some of the error in very dark and very bright regions
+        let tolerance = if method == Dither::Atkinson {
+            32.0
+        } else {
+            24.0
+        };
+        let means = block_means(&dithered.clone().convert());
+        for (mean, expected) in means.iter().zip(&expected) {
+            assert!(
+                (mean - expected).abs() < tolerance,
+                "{method:?}: {mean} != {expected}"
+            );
+        }
+        assert!(dithered.data.iter().any(|p| p.value()));
+        assert!(dithered.data.iter().any(|p| !p.value()));
+    }
+
+    // Uniform black and white images stay uniform
+    for method in METHODS {
+        let black = Image::new(16, 16, Luma::new(0)).dither(method);
+        assert!(black.data.iter().all(|p| !p.value()), "{method:?}");
+        let white = Image::new(16, 16, Luma::new(255)).dither(method);
+        assert!(white.data.iter().all(|p| p.value()), "{method:?}");
+    }
+}
+
+#[test]
+fn test_dither_quantize() -> ril::Result<()> {
+    let gradient = Image::from_fn(128, 64, |x, y| {
+        Rgb::new((x * 2) as u8, (y * 4) as u8, 255 - (x + y) as u8)
+    });
+    let expected = block_means(&gradient);
+    let error = |image: Image<PalettedRgb>| {
+        block_means(&image.flatten_palette())
+            .iter()
+            .zip(&expected)
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f64>()
+    };
+
+    let quantizer = Quantizer::new().with_palette_size(8);
+    let plain = gradient.clone().quantize_with(&quantizer)?;
+    assert!(plain.palette().unwrap().len() <= 256);
+    let plain_error = error(plain);
+
+    for method in METHODS {
+        let dithered = gradient
+            .clone()
+            .quantize_with(&quantizer.clone().with_dither(method))?;
+        let dithered_error = error(dithered);
+        assert!(
+            dithered_error < plain_error,
+            "{method:?}: {dithered_error} >= {plain_error}"
+        );
+    }
+
+    // Lossless quantization keeps every color exactly, so there is nothing to dither
+    let few = Image::from_fn(8, 8, |x, _| Rgb::new(x as u8 * 30, 0, 0));
+    let lossless = few
+        .clone()
+        .quantize_with::<PalettedRgb>(&Quantizer::new().with_dither(Dither::Bayer4))?;
+    assert_eq!(lossless.flatten_palette().data, few.data);
+
+    Ok(())
+}
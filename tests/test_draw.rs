@@ -0,0 +1,256 @@
+use ril::prelude::*;
+use std::f32::consts::PI;
+
+const ON: Luma = Luma::new(255);
+const OFF: Luma = Luma::new(0);
+
+fn count(image: &Image<Luma>) -> usize {
+    image.data.iter().filter(|&&pixel| pixel == ON).count()
+}
+
+#[test]
+fn test_path_fill() {
+    // A square made of straight lines covers exactly the pixels inside of it
+    let mut image = Image::new(16, 16, OFF);
+    image.draw(
+        &Path::new()
+            .move_to(2.0, 2.0)
+            .line_to(10.0, 2.0)
+            .line_to(10.0, 10.0)
+            .line_to(2.0, 10.0)
+            .close()
+            .with_fill(ON),
+    );
+    assert_eq!(count(&image), 64);
+    assert_eq!(image.pixel(2, 2), &ON);
+    assert_eq!(image.pixel(9, 9), &ON);
+    assert_eq!(image.pixel(10, 10), &OFF);
+
+    // A full circle has about the area of a circle, even though it is not closed
+    let mut image = Image::new(64, 64, OFF);
+    image.draw(
+        &Path::new()
+            .arc(32.0, 32.0, 20.0, 0.0, 2.0 * PI)
+            .with_fill(ON),
+    );
+    let area = 400.0 * PI;
+    assert!((count(&image) as f32 - area).abs() < area * 0.02);
+
+    // Shapes partially outside of the image are clipped
+    let mut image = Image::new(8, 8, OFF);
+    image.draw(
+        &Path::new()
+            .move_to(-10.0, -10.0)
+            .line_to(4.0, -10.0)
+            .line_to(4.0, 20.0)
+            .line_to(-10.0, 20.0)
+            .with_fill(ON),
+    );
+    assert_eq!(count(&image), 32);
+}
+
+#[test]
+fn test_path_curves() {
+    let mut image = Image::new(32, 32, OFF);
+    image.draw(
+        &Path::new()
+            .move_to(0.0, 32.0)
+            .quad_to(16.0, -32.0, 32.0, 32.0)
+            .with_fill(ON),
+    );
+    // The apex of the curve is at y = 0
+    assert_eq!(image.pixel(16, 1), &ON);
+    assert_eq!(image.pixel(2, 4), &OFF);
+
+    let mut image = Image::new(32, 32, OFF);
+    image.draw(
+        &Path::new()
+            .move_to(0.0, 32.0)
+            .cubic_to(0.0, 0.0, 32.0, 0.0, 32.0, 32.0)
+            .with_fill(ON),
+    );
+    // The apex of the curve is at y = 8
+    assert_eq!(image.pixel(16, 8), &ON);
+    assert_eq!(image.pixel(16, 7), &OFF);
+}
+
+#[test]
+fn test_path_fill_rule() {
+    // Two overlapping squares wound in the same direction
+    let path = Path::new()
+        .move_to(0.0, 0.0)
+        .line_to(6.0, 0.0)
+        .line_to(6.0, 6.0)
+        .line_to(0.0, 6.0)
+        .close()
+        .move_to(2.0, 2.0)
+        .line_to(8.0, 2.0)
+        .line_to(8.0, 8.0)
+        .line_to(2.0, 8.0)
+        .close()
+        .with_fill(ON);
+
+    let mut image = Image::new(8, 8, OFF);
+    image.draw(&path);
+    assert_eq!(count(&image), 36 + 36 - 16);
+    assert_eq!(image.pixel(4, 4), &ON);
+
+    let mut image = Image::new(8, 8, OFF);
+    image.draw(&path.with_fill_rule(FillRule::EvenOdd));
+    assert_eq!(count(&image), 36 + 36 - 32);
+    assert_eq!(image.pixel(4, 4), &OFF);
+}
+
+#[test]
+fn test_path_border() {
+    let square = || {
+        Path::<Luma>::new()
+            .move_to(4.0, 4.0)
+            .line_to(12.0, 4.0)
+            .line_to(12.0, 12.0)
+            .line_to(4.0, 12.0)
+            .close()
+    };
+    let border = |position| Border::new(ON, 2).with_position(position);
+
+    let mut image = Image::new(16, 16, OFF);
+    image.draw(&square().with_border(border(BorderPosition::Center)));
+    assert_eq!(image.pixel(3, 8), &ON);
+    assert_eq!(image.pixel(4, 8), &ON);
+    assert_eq!(image.pixel(2, 8), &OFF);
+    assert_eq!(image.pixel(5, 8), &OFF);
+    // Joins are rounded, so only the pixel nearest to the corner is covered
+    assert_eq!(image.pixel(3, 3), &ON);
+    assert_eq!(image.pixel(2, 2), &OFF);
+    assert_eq!(image.pixel(8, 8), &OFF);
+
+    let mut image = Image::new(16, 16, OFF);
+    image.draw(&square().with_border(border(BorderPosition::Inset)));
+    assert_eq!((image.pixel(4, 8), image.pixel(5, 8)), (&ON, &ON));
+    assert_eq!((image.pixel(3, 8), image.pixel(6, 8)), (&OFF, &OFF));
+
+    let mut image = Image::new(16, 16, OFF);
+    image.draw(&square().with_border(border(BorderPosition::Outset)));
+    assert_eq!((image.pixel(2, 8), image.pixel(3, 8)), (&ON, &ON));
+    assert_eq!((image.pixel(1, 8), image.pixel(4, 8)), (&OFF, &OFF));
+
+    // Open paths are stroked without being closed
+    let mut image = Image::new(16, 16, OFF);
+    image.draw(
+        &Path::<Luma>::new()
+            .move_to(2.0, 8.0)
+            .line_to(14.0, 8.0)
+            .with_border(Border::new(ON, 2).with_position(BorderPosition::Center)),
+    );
+    assert_eq!(count(&image), 24);
+}
+
+#[test]
+fn test_antialiased_coverage() {
+    // Edges that lie halfway through pixels cover them partially
+    let mut image = Image::new(8, 8, OFF);
+    image.draw(
+        &Path::new()
+            .move_to(2.5, 2.5)
+            .line_to(6.5, 2.5)
+            .line_to(6.5, 6.5)
+            .line_to(2.5, 6.5)
+            .close()
+            .with_fill(ON)
+            .with_antialiased(true),
+    );
+    assert_eq!(image.pixel(4, 4), &ON);
+    assert_eq!(image.pixel(2, 4), &Luma::new(128));
+    assert_eq!(image.pixel(6, 4), &Luma::new(128));
+    assert_eq!(image.pixel(2, 2), &Luma::new(64));
+    assert_eq!(image.pixel(1, 1), &OFF);
+
+    // The same shape without antialiasing only covers pixels which are at least half covered
+    let mut image = Image::new(8, 8, OFF);
+    image.draw(
+        &Path::new()
+            .move_to(2.5, 2.5)
+            .line_to(6.5, 2.5)
+            .line_to(6.5, 6.5)
+            .line_to(2.5, 6.5)
+            .close()
+            .with_fill(ON),
+    );
+    assert_eq!(image.pixel(2, 4), &ON);
+    assert_eq!(image.pixel(2, 2), &OFF);
+}
+
+#[test]
+fn test_antialiased_shapes() {
+    // Rectangles aligned to pixels are the same whether or not they are antialiased
+    let rectangle = Rectangle::from_bounding_box(2, 3, 12, 9)
+        .with_fill(ON)
+        .with_border(Border::new(Luma::new(100), 2));
+    let mut aliased = Image::new(16, 16, OFF);
+    aliased.draw(&rectangle);
+    let mut antialiased = Image::new(16, 16, OFF);
+    antialiased.draw(&rectangle.clone().with_antialiased(true));
+    assert_eq!(aliased.data, antialiased.data);
+
+    // Rotating a square by a right angle results in the same square
+    let mut rotated = Image::new(16, 16, OFF);
+    rotated.draw(
+        &Rectangle::square(6, (4, 4))
+            .with_fill(ON)
+            .with_rotation(PI / 2.0),
+    );
+    let mut square = Image::new(16, 16, OFF);
+    square.draw(&Rectangle::square(6, (4, 4)).with_fill(ON));
+    assert_eq!(rotated.data, square.data);
+
+    // Rounded corners leave the corners of the rectangle uncovered
+    let mut image = Image::new(16, 16, OFF);
+    image.draw(
+        &Rectangle::square(12, (2, 2))
+            .with_fill(ON)
+            .with_corner_radius(4)
+            .with_antialiased(true),
+    );
+    assert_eq!(image.pixel(2, 2), &OFF);
+    assert_eq!(image.pixel(2, 8), &ON);
+    assert!(image.pixel(3, 3).value() > 0 && image.pixel(3, 3).value() < 255);
+
+    // Antialiased ellipses cover about the area of the ellipse, with partially covered edges
+    let mut image = Image::new(32, 32, OFF);
+    image.draw(
+        &Ellipse::circle(16, 16, 10)
+            .with_fill(ON)
+            .with_antialiased(true),
+    );
+    let area = image
+        .data
+        .iter()
+        .map(|p| f32::from(p.value()) / 255.0)
+        .sum::<f32>();
+    let expected = 10.5 * 10.5 * PI;
+    assert!((area - expected).abs() < expected * 0.02);
+    assert!(image.data.iter().any(|p| p.value() > 0 && p.value() < 255));
+
+    // Antialiased polygons have partially covered edges
+    let mut image = Image::new(16, 16, OFF);
+    image.draw(
+        &Polygon::from_vertices([(1, 1), (14, 4), (6, 14)])
+            .with_fill(ON)
+            .with_antialiased(true),
+    );
+    assert_eq!(image.pixel(7, 6), &ON);
+    assert!(image.data.iter().any(|p| p.value() > 0 && p.value() < 255));
+
+    // Antialiasing only defaults the overlay mode to merging, keeping any mode set beforehand
+    let mode = OverlayMode::Blend(BlendMode::Multiply);
+    let rectangle = Rectangle::<Luma>::square(4, (0, 0)).with_overlay_mode(mode);
+    assert_eq!(rectangle.with_antialiased(true).overlay, Some(mode));
+    let ellipse = Ellipse::<Luma>::circle(4, 4, 2).with_overlay_mode(mode);
+    assert_eq!(ellipse.with_antialiased(true).overlay, Some(mode));
+    let path = Path::<Luma>::new().with_overlay_mode(mode);
+    assert_eq!(path.with_antialiased(true).overlay, Some(mode));
+    assert_eq!(
+        Path::<Luma>::new().with_antialiased(true).overlay,
+        Some(OverlayMode::Merge)
+    );
+}
@@ -1,5 +1,7 @@
 use ril::encodings::gif::GifEncoder;
+use ril::filter::Filter;
 use ril::prelude::*;
+use ril::MorphologyOperation;
 
 #[test]
 fn test_filter() -> ril::Result<()> {
@@ -25,3 +27,556 @@ fn test_filter() -> ril::Result<()> {
     image.save_inferred("tests/out/convolution_output.png")?;
     Ok(())
 }
+
+#[test]
+fn test_float_pipeline() {
+    let image = Image::from_fn(32, 32, |x, y| Rgb::new(x as u8 * 8, y as u8 * 8, 200));
+    let mut float = image.convert::<RgbF32>();
+
+    float.apply_filter(&Convolution::<3, 3, _, _>::box_blur());
+    float.resize(16, 16, ResizeAlgorithm::Bilinear);
+    float.invert();
+
+    let output = float.convert::<Rgb>();
+    assert_eq!(output.dimensions(), (16, 16));
+    // inverting happens in linear light, so the result is not simply `255 - 200`
+    let expected = Rgb::from(!RgbF32::from(Rgb::new(0, 0, 200))).b;
+    assert_eq!(output.pixel(8, 8).b, expected);
+}
+
+#[test]
+fn test_high_precision_adjustments() {
+    let float = Image::new(2, 2, RgbaF32::new(0.25, 0.5, 0.75, 0.5));
+    assert_eq!(
+        float.clone().brightened(0.5).pixel(0, 0),
+        &RgbaF32::new(0.75, 1.0, 1.25, 0.5)
+    );
+    assert_eq!(
+        float.darkened(0.5).pixel(0, 0),
+        &RgbaF32::new(-0.25, 0.0, 0.25, 0.5)
+    );
+
+    let deep = Image::new(2, 2, Rgb16::new(1000, 60000, 300));
+    assert_eq!(
+        deep.clone().brightened(10000).pixel(0, 0),
+        &Rgb16::new(11000, 65535, 10300)
+    );
+    assert_eq!(
+        deep.clone().darkened(500).pixel(0, 0),
+        &Rgb16::new(500, 59500, 0)
+    );
+
+    // A full turn keeps the full precision of the pixel instead of rounding to 8 bits
+    let pixel = *deep.hue_rotated(360).pixel(0, 0);
+    assert!(pixel.r.abs_diff(1000) <= 1 && pixel.g.abs_diff(60000) <= 1);
+
+    let rotated = Image::new(1, 1, RgbaF32::new(0.0, 0.0, 0.5, 0.25)).hue_rotated(120);
+    let pixel = rotated.pixel(0, 0);
+    assert!(pixel.r > pixel.b && pixel.a == 0.25);
+}
+
+#[test]
+fn test_gaussian_blur() {
+    let blur = SeparableConvolution::<1, Luma>::gaussian_blur(1.5);
+    assert_eq!(blur.dimensions(), (11, 11));
+    assert!((blur.horizontal.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // A single bright pixel is spread symmetrically around its position
+    let image = Image::from_fn(21, 21, |x, y| {
+        Luma::new(if (x, y) == (10, 10) { 255 } else { 0 })
+    });
+    let blurred = image.filtered(&blur);
+    assert!(blurred.pixel(10, 10).value() < 255);
+    assert_eq!(blurred.pixel(8, 10), blurred.pixel(12, 10));
+    assert_eq!(blurred.pixel(8, 10), blurred.pixel(10, 8));
+    assert!(blurred.pixel(9, 10).value() > blurred.pixel(8, 10).value());
+}
+
+#[test]
+fn test_filter_presets() {
+    // A vertical step edge from black to white
+    let image = Image::from_fn(16, 16, |x, _| Luma::new(if x < 8 { 64 } else { 192 }));
+
+    let unsharp_mask = UnsharpMask::<1, Luma>::new(1.0, 1.0).with_threshold(1.0);
+    let sharpened = image.clone().filtered(&unsharp_mask);
+    assert!(sharpened.pixel(7, 8).value() < 64);
+    assert!(sharpened.pixel(8, 8).value() > 192);
+    assert_eq!(sharpened.pixel(2, 8).value(), 64);
+
+    let sharpened = image
+        .clone()
+        .filtered(&Convolution::<3, 3, 1, Luma>::sharpen());
+    assert_eq!(sharpened.pixel(7, 8).value(), 0);
+    assert_eq!(sharpened.pixel(8, 8).value(), 255);
+    // Integer kernels produce the same result
+    let filter = Convolution::<3, 3, 1, Luma, Luma, i32>::sharpen();
+    assert!(image.clone().filtered(&filter).data == sharpened.data);
+
+    for edges in [
+        image.clone().filtered(&EdgeDetection::<1, Luma>::sobel()),
+        image.clone().filtered(&EdgeDetection::<1, Luma>::scharr()),
+        image.clone().filtered(&EdgeDetection::<1, Luma>::prewitt()),
+    ] {
+        assert_eq!(edges.pixel(7, 8).value(), 128);
+        assert_eq!(edges.pixel(8, 8).value(), 128);
+        assert_eq!(edges.pixel(4, 8).value(), 0);
+    }
+
+    let motion_blur = DynamicConvolution::<1, Luma>::motion_blur(5, 0.0);
+    let blurred = image.filtered(&motion_blur);
+    // (64 * 2 + 192 * 3) / 5
+    assert_eq!(blurred.pixel(8, 8).value(), 140);
+}
+
+#[test]
+fn test_masked_filter() {
+    let image = Image::from_fn(16, 16, |x, y| Luma::new(((x + y) % 2 * 255) as u8));
+    let mask = Image::from_fn(16, 16, |x, _| BitPixel::new(x < 8));
+
+    let blur = SeparableConvolution::<1, Luma>::gaussian_blur(1.0);
+    let filtered = image.clone().filtered(&ril::filter::Mask::new(blur, &mask));
+
+    assert_ne!(filtered.pixel(4, 4), image.pixel(4, 4));
+    assert_eq!(filtered.pixel(12, 4), image.pixel(12, 4));
+}
+
+/// Asserts that applying the filter to the whole image matches applying it one pixel at a time,
+/// bypassing any whole-image optimizations. Floating-point kernels may differ by rounding error.
+fn assert_matches_per_pixel<F>(image: &Image<Rgb>, filter: &F, tolerance: u8)
+where
+    F: ril::filter::Filter<Input = Rgb, Output = Rgb> + std::fmt::Debug,
+{
+    let width = image.width();
+    let filtered = image.clone().filtered(filter);
+
+    for (i, (&pixel, actual)) in image.data.iter().zip(filtered.data).enumerate() {
+        let (x, y) = (i as u32 % width, i as u32 / width);
+        let expected = filter.apply_pixel(image, x, y, pixel);
+        let within = |a: u8, b: u8| a.abs_diff(b) <= tolerance;
+
+        assert!(
+            within(actual.r, expected.r)
+                && within(actual.g, expected.g)
+                && within(actual.b, expected.b),
+            "{actual:?} != {expected:?} at ({x}, {y}) with {filter:?}"
+        );
+    }
+}
+
+#[test]
+fn test_convolution_engine() {
+    let image = Image::from_fn(67, 41, |x, y| {
+        Rgb::new((x * 7 + y * 3) as u8, (x * y) as u8, (x ^ y) as u8 * 4)
+    });
+
+    // Separable kernels
+    let filter = Convolution::<3, 3, 3, Rgb, Rgb, i32>::new([[1, 2, 1], [2, 4, 2], [1, 2, 1]]);
+    assert_matches_per_pixel(&image, &filter, 0);
+    assert_matches_per_pixel(&image, &Convolution::<7, 7, 3, Rgb>::box_blur(), 1);
+    assert_matches_per_pixel(
+        &image,
+        &SeparableConvolution::<3, Rgb>::gaussian_blur(2.0),
+        1,
+    );
+    assert_matches_per_pixel(&image, &EdgeDetection::<3, Rgb>::sobel(), 1);
+
+    // Non-separable and non-square kernels
+    let filter = Convolution::<3, 3, 3, Rgb, Rgb, i32>::new([[0, -1, 0], [-1, 5, -1], [0, -1, 0]]);
+    assert_matches_per_pixel(&image, &filter, 0);
+    assert_matches_per_pixel(
+        &image,
+        &DynamicConvolution::<3, Rgb>::motion_blur(9, 30.0),
+        1,
+    );
+    let filter = DynamicConvolution::new((1..=15).map(|i| f64::from(i) / 120.0).collect(), 5);
+    assert_matches_per_pixel(&image, &filter, 1);
+}
+
+#[test]
+fn test_filter_edge_modes() {
+    let image = Image::from_fn(67, 41, |x, y| {
+        Rgb::new((x * 7 + y * 3) as u8, (x * y) as u8, (x ^ y) as u8 * 4)
+    });
+
+    for edge_mode in [
+        EdgeMode::Constant(Rgb::new(255, 0, 128)),
+        EdgeMode::Clamp,
+        EdgeMode::Wrap,
+        EdgeMode::Reflect,
+        EdgeMode::Skip,
+    ] {
+        let filter = Convolution::<5, 5, 3, Rgb>::box_blur().with_edge_mode(edge_mode);
+        assert_matches_per_pixel(&image, &filter, 1);
+        let filter = DynamicConvolution::<3, Rgb>::motion_blur(7, 45.0).with_edge_mode(edge_mode);
+        assert_matches_per_pixel(&image, &filter, 1);
+        let filter = EdgeDetection::<3, Rgb>::prewitt().with_edge_mode(edge_mode);
+        assert_matches_per_pixel(&image, &filter, 1);
+    }
+
+    // Blurring a uniform image only darkens its borders with a black constant edge
+    let image = Image::new(16, 16, Luma::new(200));
+    let blur = SeparableConvolution::<1, Luma>::gaussian_blur(2.0);
+    for edge_mode in [
+        EdgeMode::Clamp,
+        EdgeMode::Wrap,
+        EdgeMode::Reflect,
+        EdgeMode::Skip,
+    ] {
+        let blurred = image
+            .clone()
+            .filtered(&blur.clone().with_edge_mode(edge_mode));
+        assert!(blurred.data.iter().all(|p| p.value().abs_diff(200) <= 1));
+    }
+    let blurred = image.filtered(&blur.with_edge_mode(EdgeMode::Constant(Luma::new(0))));
+    assert!(blurred.pixel(0, 0).value() < 100);
+
+    // Wrapping carries pixels over to the opposite edge, making tiles seamless
+    let image = Image::from_fn(16, 16, |x, _| Luma::new(if x == 15 { 255 } else { 0 }));
+    let blur = Convolution::<3, 3, 1, Luma>::box_blur();
+    let skipped = image.clone().filtered(&blur.clone());
+    let wrapped = image
+        .clone()
+        .filtered(&blur.clone().with_edge_mode(EdgeMode::Wrap));
+    assert_eq!(skipped.pixel(0, 8).value(), 0);
+    assert_eq!(wrapped.pixel(0, 8).value(), 85);
+
+    // Pixels outside of the image are skipped by default
+    assert_eq!(EdgeMode::<Luma>::default(), EdgeMode::Skip);
+    let clamped = image.filtered(&blur.with_edge_mode(EdgeMode::Clamp));
+    assert_eq!(skipped.pixel(15, 8).value(), 127);
+    assert_eq!(clamped.pixel(15, 8).value(), 170);
+}
+
+#[test]
+fn test_morphology() {
+    // A 10x10 square with a hole in it, along with a speck of noise
+    let mask = Image::from_fn(20, 20, |x, y| {
+        let square = (5..15).contains(&x) && (5..15).contains(&y) && (x, y) != (9, 9);
+        BitPixel::new(square || (x, y) == (2, 2))
+    });
+    let count = |image: &Image<BitPixel>| image.data.iter().filter(|p| p.value()).count();
+    let apply = |operation| {
+        mask.clone().filtered(&Morphology::<1, BitPixel>::new(
+            operation,
+            StructuringElement::square(3),
+        ))
+    };
+
+    let eroded = apply(MorphologyOperation::Erode);
+    assert_eq!(count(&eroded), 8 * 8 - 9);
+    let dilated = apply(MorphologyOperation::Dilate);
+    assert_eq!(count(&dilated), 12 * 12 + 9);
+
+    // Opening removes the speck, and closing fills the hole
+    let opened = apply(MorphologyOperation::Open);
+    assert!(!opened.pixel(2, 2).value());
+    assert!(opened.pixel(5, 5).value());
+    let closed = apply(MorphologyOperation::Close);
+    assert_eq!(count(&closed), 10 * 10 + 1);
+
+    let gradient = apply(MorphologyOperation::Gradient);
+    assert!(gradient.pixel(4, 4).value() && gradient.pixel(5, 5).value());
+    assert!(!gradient.pixel(7, 7).value());
+    let top_hat = apply(MorphologyOperation::TopHat);
+    assert_eq!(count(&top_hat), 1);
+    let black_hat = apply(MorphologyOperation::BlackHat);
+    assert!(black_hat.pixel(9, 9).value());
+    assert_eq!(count(&black_hat), 1);
+
+    // The cleaned up mask can be used to paste images
+    let mut image = Image::new(20, 20, Rgb::black());
+    image.paste_with_mask(0, 0, &Image::new(20, 20, Rgb::white()), &opened);
+    assert_eq!(image.pixel(2, 2), &Rgb::black());
+    assert_eq!(image.pixel(10, 10), &Rgb::white());
+
+    // Color images are processed per channel
+    let image = Image::from_fn(37, 23, |x, y| {
+        Rgb::new((x * 7 + y * 3) as u8, (x * y) as u8, (x ^ y) as u8 * 4)
+    });
+    let custom = Image::from_fn(3, 2, |x, y| BitPixel::new(x != y));
+    for element in [
+        StructuringElement::rectangle(5, 3),
+        StructuringElement::cross(3, 5),
+        StructuringElement::ellipse(5, 5),
+        StructuringElement::from_image(&custom).with_center(0, 1),
+    ] {
+        for operation in [
+            MorphologyOperation::Erode,
+            MorphologyOperation::Dilate,
+            MorphologyOperation::Open,
+            MorphologyOperation::Close,
+            MorphologyOperation::Gradient,
+            MorphologyOperation::TopHat,
+            MorphologyOperation::BlackHat,
+        ] {
+            for edge_mode in [
+                EdgeMode::Constant(Rgb::new(255, 0, 128)),
+                EdgeMode::Clamp,
+                EdgeMode::Wrap,
+                EdgeMode::Reflect,
+                EdgeMode::Skip,
+            ] {
+                let filter =
+                    Morphology::<3, Rgb>::new(operation, element.clone()).with_edge_mode(edge_mode);
+                assert_matches_per_pixel(&image, &filter, 0);
+            }
+        }
+    }
+
+    let ellipse = StructuringElement::ellipse(5, 5);
+    assert!(ellipse.contains(2, 0) && ellipse.contains(1, 1));
+    assert!(!ellipse.contains(0, 0));
+}
+
+#[test]
+fn test_rank_filters() {
+    // Salt and pepper noise is removed entirely by a median filter
+    let noisy = Image::from_fn(32, 32, |x, y| match (x * 7 + y * 13) % 17 {
+        0 => Luma::new(255),
+        1 => Luma::new(0),
+        _ => Luma::new(100),
+    });
+    let denoised = noisy.clone().filtered(&RankFilter::<1, Luma>::median(3, 3));
+    assert!(denoised.data.iter().all(|p| p.value() == 100));
+
+    // Within a masked region only
+    let mask = Image::from_fn(32, 32, |x, _| BitPixel::new(x < 16));
+    let filter = ril::filter::Mask::new(RankFilter::<1, Luma>::median(3, 3), &mask);
+    let denoised = noisy.clone().filtered(&filter);
+    assert!(denoised.data.iter().step_by(32).all(|p| p.value() == 100));
+    assert!(denoised.data.iter().any(|p| p.value() != 100));
+
+    // The minimum and maximum are equivalent to erosion and dilation
+    let element = StructuringElement::rectangle(5, 3);
+    assert_eq!(
+        noisy
+            .clone()
+            .filtered(&RankFilter::<1, Luma>::minimum(5, 3))
+            .data,
+        noisy
+            .clone()
+            .filtered(&Morphology::erode(element.clone()).with_edge_mode(EdgeMode::Clamp))
+            .data,
+    );
+    assert_eq!(
+        noisy
+            .clone()
+            .filtered(&RankFilter::<1, Luma>::maximum(5, 3))
+            .data,
+        noisy
+            .filtered(&Morphology::dilate(element).with_edge_mode(EdgeMode::Clamp))
+            .data,
+    );
+
+    let image = Image::from_fn(37, 23, |x, y| {
+        Rgb::new((x * 7 + y * 3) as u8, (x * y) as u8, (x ^ y) as u8 * 4)
+    });
+    for percentile in [0.0, 0.3, 0.5, 1.0] {
+        for edge_mode in [
+            EdgeMode::Constant(Rgb::new(255, 0, 128)),
+            EdgeMode::Clamp,
+            EdgeMode::Wrap,
+            EdgeMode::Reflect,
+            EdgeMode::Skip,
+        ] {
+            let filter = RankFilter::<3, Rgb>::new(4, 5, percentile).with_edge_mode(edge_mode);
+            assert_matches_per_pixel(&image, &filter, 0);
+        }
+    }
+
+    // 16-bit subpixels
+    let image = Image::from_fn(23, 17, |x, y| Luma16::new((x * 2003 + y * 5741) as u16));
+    let filter = RankFilter::<1, Luma16>::median(5, 5);
+    let filtered = image.clone().filtered(&filter);
+    for (i, (&pixel, actual)) in image.data.iter().zip(filtered.data).enumerate() {
+        let (x, y) = (i as u32 % 23, i as u32 / 23);
+        assert_eq!(
+            actual,
+            filter.apply_pixel(&image, x, y, pixel),
+            "({x}, {y})"
+        );
+    }
+}
+
+#[test]
+fn test_bilateral_filter() {
+    // Uniform regions are left untouched
+    let image = Image::new(16, 16, Rgb::new(10, 150, 255));
+    let filter = BilateralFilter::<3, Rgb>::new(2.0, 25.0);
+    assert_eq!(image.clone().filtered(&filter).data, image.data);
+
+    // Noise is smoothed out while the edge is preserved
+    let image = Image::from_fn(32, 16, |x, y| {
+        let noise = ((x * 7 + y * 13) % 5) as u8 * 2;
+        Luma::new(if x < 16 { 40 + noise } else { 200 + noise })
+    });
+    let smoothed = image
+        .clone()
+        .filtered(&BilateralFilter::<1, Luma>::new(2.0, 20.0));
+    let blurred = image
+        .clone()
+        .filtered(&SeparableConvolution::<1, Luma>::gaussian_blur(2.0));
+
+    assert!((40..=48).contains(&smoothed.pixel(15, 8).value()));
+    assert!((200..=208).contains(&smoothed.pixel(16, 8).value()));
+    assert!(blurred.pixel(15, 8).value() > 80);
+    let spread = |image: &Image<Luma>| {
+        let values = (0..12).map(|x| image.pixel(x, 8).value());
+        values.clone().max().unwrap() - values.min().unwrap()
+    };
+    assert!(spread(&smoothed) < spread(&image));
+
+    let image = Image::from_fn(37, 23, |x, y| {
+        Rgb::new((x * 7 + y * 3) as u8, (x * y) as u8, (x ^ y) as u8 * 4)
+    });
+    for edge_mode in [EdgeMode::Constant(Rgb::black()), EdgeMode::Reflect] {
+        let filter = BilateralFilter::<3, Rgb>::new(1.5, 40.0).with_edge_mode(edge_mode);
+        assert_matches_per_pixel(&image, &filter, 0);
+    }
+}
+
+#[test]
+fn test_premultiplied_alpha() {
+    // Opaque red next to transparent green, whose color should not bleed into the red
+    let image = Image::from_fn(16, 4, |x, _| {
+        if x < 8 {
+            Rgba::new(255, 0, 0, 255)
+        } else {
+            Rgba::new(0, 255, 0, 0)
+        }
+    });
+    let assert_no_fringes = |output: &Image<Rgba>, context: &str| {
+        for pixel in output.data.iter().filter(|pixel| pixel.a > 0) {
+            assert!(pixel.r >= 254 && pixel.g <= 1, "{pixel:?} with {context}");
+        }
+        let edge = output.pixel(output.width() / 2, 0).a;
+        assert!(edge > 0 && edge < 255, "edge alpha {edge} with {context}");
+    };
+
+    assert_no_fringes(
+        &image
+            .clone()
+            .filtered(&Convolution::<5, 5, 4, Rgba>::box_blur()),
+        "box blur",
+    );
+    assert_no_fringes(
+        &image
+            .clone()
+            .filtered(&SeparableConvolution::<4, Rgba>::gaussian_blur(2.0)),
+        "gaussian blur",
+    );
+    assert_no_fringes(
+        &image
+            .clone()
+            .filtered(&DynamicConvolution::<4, Rgba>::motion_blur(9, 0.0)),
+        "motion blur",
+    );
+
+    let mut resized = image.clone();
+    resized.resize(8, 2, ResizeAlgorithm::Bilinear);
+    assert_no_fringes(&resized, "resize");
+
+    // The colors of translucent pixels are preserved exactly by integer kernels, while fully
+    // transparent pixels become transparent black
+    let image = Image::from_fn(8, 8, |x, y| Rgba::new(200, 100, 50, (x * 8 + y) as u8));
+    let identity = Convolution::<3, 3, 4, Rgba, Rgba, i32>::new([[0, 0, 0], [0, 1, 0], [0, 0, 0]]);
+    let expected = image
+        .data
+        .iter()
+        .map(|&pixel| {
+            if pixel.a == 0 {
+                Rgba::transparent()
+            } else {
+                pixel
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(image.clone().filtered(&identity).data, expected);
+    assert_eq!(
+        identity.apply_pixel(&image, 3, 5, *image.pixel(3, 5)),
+        expected[43]
+    );
+
+    // Converting to premultiplied alpha and back
+    let mut image = Image::new(1, 1, Rgba::new(200, 100, 50, 128));
+    image.premultiply();
+    assert_eq!(image.pixel(0, 0), &Rgba::new(100, 50, 25, 128));
+    image.unpremultiply();
+    assert_eq!(image.pixel(0, 0), &Rgba::new(199, 100, 50, 128));
+}
+
+/// A pixel type which relies on the default implementations of `Alpha`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct CustomRgba16(Rgba16);
+
+impl std::ops::Not for CustomRgba16 {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl ril::pixel::MaybeSealed for CustomRgba16 {}
+
+impl Pixel for CustomRgba16 {
+    const COLOR_TYPE: ColorType = ColorType::Rgba;
+    const BIT_DEPTH: u8 = 16;
+
+    type Subpixel = u16;
+    type Color = Self;
+    type Data = <Rgba16 as Pixel>::Data;
+
+    fn map_subpixels<F, A>(self, f: F, a: A) -> Self
+    where
+        F: Fn(u16) -> u16,
+        A: Fn(u16) -> u16,
+    {
+        Self(self.0.map_subpixels(f, a))
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self(Rgba16::from_bytes(bytes))
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        self.0.as_bytes()
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        Self(self.0.merge_with_alpha(other.0, alpha))
+    }
+
+    fn as_rgb(&self) -> Rgb {
+        self.0.as_rgb()
+    }
+
+    fn as_rgba(&self) -> Rgba {
+        self.0.as_rgba()
+    }
+}
+
+impl Alpha for CustomRgba16 {
+    fn alpha(&self) -> u8 {
+        self.0.alpha()
+    }
+
+    fn with_alpha(self, alpha: u8) -> Self {
+        Self(self.0.with_alpha(alpha))
+    }
+}
+
+#[test]
+fn test_default_premultiplied_alpha() {
+    for pixel in [
+        Rgba16::new(65535, 32768, 1000, 65535),
+        Rgba16::new(40000, 20000, 300, 12345),
+        Rgba16::new(500, 65535, 0, 1),
+        Rgba16::new(1234, 5678, 9012, 0),
+    ] {
+        let custom = CustomRgba16(pixel);
+        assert_eq!(custom.premultiply().0, pixel.premultiply());
+        assert_eq!(custom.unpremultiply().0, pixel.unpremultiply());
+    }
+}
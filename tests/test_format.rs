@@ -0,0 +1,36 @@
+use ril::prelude::*;
+
+#[test]
+fn test_unsupported_format() {
+    assert!(!ImageFormat::Unknown.can_decode());
+    assert!(!ImageFormat::Unknown.can_encode());
+
+    let image = Image::new(4, 4, Rgb::white());
+    assert!(matches!(
+        image.encode(ImageFormat::Unknown, &mut Vec::new()),
+        Err(ril::Error::UnsupportedFormat(ImageFormat::Unknown)),
+    ));
+    assert!(matches!(
+        Image::<Rgb>::from_bytes(ImageFormat::Unknown, [0; 16]),
+        Err(ril::Error::UnsupportedFormat(ImageFormat::Unknown)),
+    ));
+    assert!(matches!(
+        ImageSequence::<Rgb>::from_bytes(ImageFormat::Unknown, &[0; 16]),
+        Err(ril::Error::UnsupportedFormat(ImageFormat::Unknown)),
+    ));
+}
+
+#[test]
+fn test_format_support_reflects_features() {
+    for (format, enabled) in [
+        (ImageFormat::Png, cfg!(feature = "png")),
+        (ImageFormat::Jpeg, cfg!(feature = "jpeg")),
+        (ImageFormat::Gif, cfg!(feature = "gif")),
+        (ImageFormat::Bmp, cfg!(feature = "bmp")),
+        (ImageFormat::Tiff, cfg!(feature = "tiff")),
+        (ImageFormat::WebP, cfg!(feature = "webp")),
+    ] {
+        assert_eq!(format.can_decode(), enabled, "{format:?}");
+        assert_eq!(format.can_encode(), enabled, "{format:?}");
+    }
+}
@@ -40,3 +40,45 @@ fn test_gif_palette_decode() -> ril::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_gif_dither_encode() -> ril::Result<()> {
+    use ril::encodings::gif::{GifEncoder, GifEncoderOptions};
+
+    let encode = |image: &Image<Rgb>, dither: Dither| -> ril::Result<Image<Rgb>> {
+        let mut buffer = Vec::new();
+        let metadata =
+            EncoderMetadata::from(image).with_config(GifEncoderOptions::new().with_dither(dither));
+        let mut encoder = GifEncoder::new(&mut buffer, metadata)?;
+        encoder.add_frame(image)?;
+        encoder.finish()?;
+
+        Image::from_bytes(ImageFormat::Gif, &buffer)
+    };
+
+    // Images with few colors are encoded exactly, regardless of dithering
+    let stripes = Image::from_fn(64, 64, |x, _| COLORS[x as usize % COLORS.len()]);
+    assert_eq!(encode(&stripes, Dither::FloydSteinberg)?.data, stripes.data);
+
+    // Otherwise, the quantized pixels are dithered
+    let gradient = Image::from_fn(128, 128, |x, y| {
+        Rgb::new(x as u8 * 2, y as u8 * 2, 255 - (x + y) as u8)
+    });
+    let plain = encode(&gradient, Dither::None)?;
+    let dithered = encode(&gradient, Dither::Bayer4)?;
+    assert_eq!(dithered.dimensions(), (128, 128));
+    assert_ne!(dithered.data, plain.data);
+
+    let error = |image: &Image<Rgb>| {
+        image
+            .data
+            .iter()
+            .zip(&gradient.data)
+            .map(|(a, b)| (i32::from(a.r) - i32::from(b.r)).abs())
+            .sum::<i32>() as f64
+            / image.data.len() as f64
+    };
+    assert!(error(&dithered) < 16.0);
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use ril::prelude::*;
+use ril::{ClaheOptions, Histogram, Levels, LookupTable, Threshold};
+
+#[test]
+fn test_histograms() {
+    let image = Image::from_fn(16, 16, |x, y| Rgb::new(x as u8, y as u8 * 2, 7));
+    let [r, g, b] = image.histograms();
+
+    assert_eq!(r.total(), 256);
+    assert_eq!(r.count(3), 16);
+    assert_eq!((g.min(), g.max()), (Some(0), Some(30)));
+    assert_eq!(b.count(7), 256);
+    assert_eq!(r.mean(), Some(7.5));
+    assert_eq!(r.percentile(0.5), Some(8));
+    assert_eq!(r.cumulative()[3], 64);
+
+    // Histograms can be computed from bands as well
+    let (red, ..) = image.bands();
+    assert_eq!(Histogram::from_band(&red), r);
+    let [luma] = red.histograms();
+    assert_eq!(luma, r);
+
+    assert!(Histogram::new().is_empty());
+    assert_eq!(Histogram::new().percentile(0.5), None);
+}
+
+#[test]
+fn test_lookup_tables() {
+    let levels = LookupTable::from_levels(Levels::new(50, 200));
+    assert_eq!((levels.get(0), levels.get(50)), (0, 0));
+    assert_eq!((levels.get(125), levels.get(200)), (128, 255));
+    let brightened = LookupTable::from_levels(Levels::new(0, 255).with_gamma(2.2));
+    assert!(brightened.get(64) > 64);
+    let faded = LookupTable::from_levels(Levels::default().with_output(20, 235));
+    assert_eq!((faded.get(0), faded.get(255)), (20, 235));
+
+    // Curves pass through their control points and are monotonic between them
+    let points = [(0, 10), (64, 40), (128, 200), (255, 255)];
+    let curve = LookupTable::from_curve(&points);
+    for (x, y) in points {
+        assert_eq!(curve.get(x), y);
+    }
+    assert!(curve.0.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(LookupTable::from_curve(&[]), LookupTable::identity());
+    assert_eq!(
+        LookupTable::from_curve(&[(100, 50)]),
+        LookupTable::from_fn(|_| 50)
+    );
+
+    // Tables apply to each color channel, leaving alpha untouched
+    let mut image = Image::new(4, 4, Rgba::new(10, 100, 240, 128));
+    let inverted = LookupTable::from_fn(|v| 255 - v);
+    image.apply_lookup_table(&inverted);
+    assert_eq!(image.pixel(0, 0), &Rgba::new(245, 155, 15, 128));
+    image.apply_lookup_tables(&[inverted, LookupTable::identity(), inverted, inverted]);
+    assert_eq!(image.pixel(0, 0), &Rgba::new(10, 155, 240, 127));
+    assert_eq!(inverted.then(&inverted), LookupTable::identity());
+}
+
+#[test]
+fn test_histogram_adjustments() {
+    // A low-contrast image is stretched over the entire range
+    let image = Image::from_fn(64, 16, |x, _| {
+        Rgba::new(100 + x as u8, 50 + x as u8 / 2, 80, 200)
+    });
+    let mut stretched = image.clone();
+    stretched.auto_contrast(0.0);
+    let [r, g, b, a] = stretched.histograms();
+    assert_eq!((r.min(), r.max()), (Some(0), Some(255)));
+    assert_eq!((g.min(), g.max()), (Some(0), Some(255)));
+    assert_eq!(b.count(80), 1024);
+    assert_eq!(a.count(200), 1024);
+
+    let mut equalized = image.clone();
+    equalized.equalize();
+    let [r, ..] = equalized.histograms();
+    assert_eq!((r.min(), r.max()), (Some(0), Some(255)));
+    assert!(equalized.data.windows(2).take(63).all(|w| w[0].r < w[1].r));
+
+    let mut leveled = image.clone();
+    leveled.adjust_levels(Levels::new(100, 163));
+    assert_eq!(leveled.pixel(0, 0).r, 0);
+    assert_eq!(leveled.pixel(63, 0).r, 255);
+
+    // CLAHE with a single tile and no clipping is plain histogram equalization
+    let image = Image::from_fn(128, 128, |x, y| Luma::new(((x + y) / 4 + 96) as u8));
+    let mut equalized = image.clone();
+    equalized.equalize();
+    let mut clahe = image.clone();
+    clahe.clahe(
+        ClaheOptions::new()
+            .with_tiles(1, 1)
+            .with_clip_limit(f64::INFINITY),
+    );
+    assert_eq!(clahe.data, equalized.data);
+
+    // Clipping limits how much contrast is enhanced
+    let contrast = |image: &Image<Luma>| {
+        let [histogram] = image.histograms();
+        histogram.max().unwrap() - histogram.min().unwrap()
+    };
+    let mut limited = image.clone();
+    limited.clahe(ClaheOptions::new().with_tiles(4, 4).with_clip_limit(1.5));
+    assert!(contrast(&limited) > contrast(&image));
+    assert!(contrast(&limited) < contrast(&equalized));
+
+    // Uniform images stay uniform
+    let mut uniform = Image::new(32, 32, Luma::new(77));
+    uniform.equalize();
+    assert!(uniform.data.iter().all(|p| p.value() == 77));
+    uniform.clahe(ClaheOptions::default());
+    assert!(uniform.data.iter().all(|p| p == uniform.pixel(0, 0)));
+}
+
+#[test]
+fn test_thresholds() {
+    // Bimodal histograms are split in the middle of the gap between the peaks
+    let histogram = Histogram::from_values([50; 100].into_iter().chain([200; 60]));
+    assert_eq!(histogram.otsu_threshold(), Some(124));
+    assert_eq!(Histogram::new().otsu_threshold(), None);
+
+    // A dominant bright background with a tail of darker values
+    let mut histogram = Histogram::new();
+    for v in 0..=255 {
+        for _ in 0..if v >= 220 { 400 } else { u32::from(v) / 20 } {
+            histogram.add(v);
+        }
+    }
+    let triangle = histogram.triangle_threshold().unwrap();
+    assert!((150..220).contains(&triangle));
+    assert_eq!(Histogram::new().triangle_threshold(), None);
+
+    let image = Image::from_fn(16, 16, |x, _| {
+        Rgb::new(x as u8 * 16, x as u8 * 16, x as u8 * 16)
+    });
+    let fixed = image.threshold(Threshold::Fixed(127));
+    assert_eq!(fixed.data, image.clone().convert::<BitPixel>().data);
+    let otsu = image.threshold(Threshold::Otsu);
+    assert_eq!(otsu.dimensions(), (16, 16));
+    assert!(!otsu.pixel(7, 0).value() && otsu.pixel(8, 0).value());
+
+    // Dark text on a page that is lit unevenly from left to right, which no single global
+    // threshold can separate
+    let page = Image::from_fn(96, 32, |x, y| {
+        let background = 60 + x as u8 * 2;
+        if x % 12 == 5 && y % 8 < 6 {
+            Luma::new(background - 50)
+        } else {
+            Luma::new(background)
+        }
+    });
+    let is_text = |x: u32, y: u32| x % 12 == 5 && y % 8 < 6;
+    let errors = |binarized: &Image<BitPixel>| {
+        (0..96)
+            .flat_map(|x| (0..32).map(move |y| (x, y)))
+            .filter(|&(x, y)| binarized.pixel(x, y).value() == is_text(x, y))
+            .count()
+    };
+
+    assert!(errors(&page.threshold(Threshold::Otsu)) > 0);
+    let mean = page.threshold(Threshold::AdaptiveMean {
+        radius: 4,
+        offset: 5.0,
+    });
+    assert_eq!(errors(&mean), 0);
+    let gaussian = page.threshold(Threshold::AdaptiveGaussian {
+        sigma: 2.0,
+        offset: 5.0,
+    });
+    assert_eq!(errors(&gaussian), 0);
+}
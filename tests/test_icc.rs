@@ -0,0 +1,174 @@
+use ril::prelude::*;
+use ril::{ColorTransform, Error, ToneCurve};
+
+fn assert_close(a: Rgb, b: Rgb, tolerance: u8) {
+    let within = |x: u8, y: u8| x.abs_diff(y) <= tolerance;
+    assert!(
+        within(a.r, b.r) && within(a.g, b.g) && within(a.b, b.b),
+        "{a:?} is not within {tolerance} of {b:?}"
+    );
+}
+
+#[test]
+fn test_tone_curves() {
+    for curve in [
+        ToneCurve::Linear,
+        ToneCurve::Gamma(2.2),
+        ToneCurve::SRGB,
+        ToneCurve::Table((0..=1024).map(|i| (i as f32 / 1024.0).powf(1.8)).collect()),
+    ] {
+        for i in 0..=20 {
+            let value = i as f32 / 20.0;
+            let roundtrip = curve.from_linear(curve.to_linear(value));
+            assert!((roundtrip - value).abs() < 1e-3, "{curve:?} at {value}");
+        }
+    }
+
+    assert!((ToneCurve::SRGB.to_linear(0.5) - 0.214_041).abs() < 1e-5);
+}
+
+#[test]
+fn test_color_profile_conversion() {
+    let srgb = ColorProfile::srgb();
+    let p3 = ColorProfile::display_p3();
+
+    // sRGB red is well within the Display P3 gamut
+    let transform = ColorTransform::new(&srgb, &p3);
+    let red = Rgb::new(255, 0, 0).with_color_components(transform.apply([1.0, 0.0, 0.0]));
+    assert_close(red, Rgb::new(234, 51, 35), 1);
+
+    // Converting between profiles and back again should be lossless within rounding error. 16-bit
+    // subpixels are used since 8-bit subpixels lack precision in the shadows when in linear light
+    // or in wider gamuts.
+    let image = Image::from_fn(64, 64, |x, y| Rgb::new(x as u8 * 4, y as u8 * 4, 128));
+    for profile in [
+        ColorProfile::display_p3(),
+        ColorProfile::adobe_rgb(),
+        ColorProfile::linear_srgb(),
+    ] {
+        let mut converted = image.clone().map_pixels(Rgb16::from);
+        converted.convert_color_profile(&srgb, &profile);
+        converted.convert_color_profile(&profile, &srgb);
+
+        for (a, b) in converted.data.into_iter().zip(&image.data) {
+            assert_close(Rgb::from(a), *b, 1);
+        }
+    }
+
+    // White and black are preserved, and grayscale targets drop all color
+    let gray = ColorTransform::new(&srgb, &ColorProfile::gray());
+    let apply = |rgb: Rgb| rgb.with_color_components(gray.apply(rgb.color_components()));
+    assert_eq!(apply(Rgb::white()), Rgb::white());
+    assert_eq!(apply(Rgb::black()), Rgb::black());
+    let [r, g, b] = gray.apply([0.2, 0.8, 0.4]);
+    assert!(r == g && g == b);
+}
+
+#[test]
+fn test_icc_profile_roundtrip() -> ril::Result<()> {
+    for profile in [
+        ColorProfile::srgb(),
+        ColorProfile::display_p3(),
+        ColorProfile::adobe_rgb(),
+        ColorProfile::linear_srgb(),
+        ColorProfile::gray(),
+    ] {
+        let parsed = ColorProfile::from_icc(&profile.to_icc())?;
+        assert_eq!(parsed.description(), profile.description());
+        assert_eq!(parsed.is_gray(), profile.is_gray());
+
+        let transform = ColorTransform::new(&profile, &parsed);
+        for value in [0.1, 0.5, 0.9] {
+            let [r, g, b] = transform.apply([value, 1.0 - value, value / 2.0]);
+            let expected = if profile.is_gray() {
+                [value; 3]
+            } else {
+                [value, 1.0 - value, value / 2.0]
+            };
+
+            for (actual, expected) in [r, g, b].into_iter().zip(expected) {
+                assert!(
+                    (actual - expected).abs() < 2e-3,
+                    "{}",
+                    profile.description()
+                );
+            }
+        }
+    }
+
+    assert!(matches!(
+        ColorProfile::from_icc(b"not an icc profile"),
+        Err(Error::InvalidIccProfile(_))
+    ));
+    Ok(())
+}
+
+/// Returns the offset of the tag with the given signature in the given ICC profile.
+fn tag_offset(icc: &[u8], signature: &[u8]) -> usize {
+    let count = u32::from_be_bytes(icc[128..132].try_into().unwrap()) as usize;
+    let entry = (0..count)
+        .map(|i| &icc[132 + i * 12..][..12])
+        .find(|entry| &entry[..4] == signature)
+        .unwrap();
+
+    u32::from_be_bytes(entry[4..8].try_into().unwrap()) as usize
+}
+
+#[test]
+fn test_degenerate_icc_profiles() {
+    let srgb = ColorProfile::srgb().to_icc();
+
+    // Colorants which cannot be inverted
+    let mut icc = srgb.clone();
+    let offset = tag_offset(&icc, b"rXYZ");
+    icc[offset + 8..offset + 20].fill(0);
+    assert!(matches!(
+        ColorProfile::from_icc(&icc),
+        Err(Error::InvalidIccProfile(_))
+    ));
+
+    // Parametric curves of type 1 and 2 divide by their `a` parameter
+    for kind in [1, 2] {
+        let mut icc = srgb.clone();
+        let offset = tag_offset(&icc, b"rTRC");
+        assert_eq!(&icc[offset..offset + 4], b"para");
+        icc[offset + 9] = kind;
+        icc[offset + 16..offset + 20].fill(0);
+        assert!(matches!(
+            ColorProfile::from_icc(&icc),
+            Err(Error::InvalidIccProfile(_))
+        ));
+    }
+}
+
+#[test]
+fn test_embedded_icc_profile() -> ril::Result<()> {
+    // This image embeds the classic v2 sRGB profile which uses lookup tables for its tone curves
+    let mut image = Image::<Rgb>::open("tests/puffins.jpg")?;
+    let profile = image.metadata().color_profile().unwrap()?;
+    assert!(profile.description().starts_with("sRGB"));
+
+    let original = image.clone();
+    image.convert_to_profile(&ColorProfile::srgb())?;
+    for (a, b) in image.data.iter().zip(&original.data) {
+        assert_close(*a, *b, 1);
+    }
+
+    // The target profile is embedded after conversion
+    image.convert_to_profile(&ColorProfile::display_p3())?;
+    assert_eq!(
+        image.metadata().color_profile().unwrap()?,
+        ColorProfile::from_icc(&ColorProfile::display_p3().to_icc())?,
+    );
+
+    // Grayscale profiles cannot describe RGB data, so an equivalent RGB profile is embedded
+    image.convert_to_profile(&ColorProfile::gray())?;
+    let profile = image.metadata().color_profile().unwrap()?;
+    assert!(!profile.is_gray());
+    let gray = image.clone();
+    image.convert_to_profile(&ColorProfile::srgb())?;
+    for (a, b) in image.data.iter().zip(&gray.data) {
+        assert_close(*a, *b, 1);
+    }
+    Ok(())
+}
@@ -7,3 +7,33 @@ fn test_jpeg() -> ril::Result<()> {
 
     image.save_inferred("tests/out/jpg_encode_output.jpg")
 }
+
+#[test]
+fn test_jpeg_metadata_roundtrip() -> ril::Result<()> {
+    let image = Image::<Rgb>::open("tests/sample.jpg")?;
+    let exif = image
+        .metadata()
+        .exif
+        .as_deref()
+        .expect("sample has EXIF data");
+    assert!(exif.starts_with(b"II*\0") || exif.starts_with(b"MM\0*"));
+
+    let icc_profile = Image::<Rgb>::open("tests/puffins.jpg")?
+        .metadata()
+        .icc_profile
+        .clone()
+        .expect("sample has an ICC profile");
+    let metadata = image
+        .metadata()
+        .clone()
+        .with_icc_profile(icc_profile)
+        .with_xmp(&b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>"[..]);
+    let image = image.with_metadata(metadata);
+
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::Jpeg, &mut buffer)?;
+
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Jpeg, &buffer)?;
+    assert_eq!(decoded.metadata(), image.metadata());
+    Ok(())
+}
@@ -115,3 +115,217 @@ fn test_gh_17() -> ril::Result<()> {
     image.mask_alpha(&mask);
     image.save_inferred("tests/out/gh_17.png")
 }
+
+#[test]
+fn test_16bit_png_roundtrip() -> ril::Result<()> {
+    let rgb = Image::from_fn(64, 32, |x, y| {
+        Rgb16::new(x as u16 * 1021, y as u16 * 2039, (x * y) as u16 * 31)
+    });
+    let mut buffer = Vec::new();
+    rgb.encode(ImageFormat::Png, &mut buffer)?;
+    assert_eq!(
+        Image::<Rgb16>::from_bytes(ImageFormat::Png, &buffer)?.data,
+        rgb.data
+    );
+
+    // decoding into an 8-bit pixel should narrow each channel
+    let narrowed = Image::<Rgb>::from_bytes(ImageFormat::Png, &buffer)?;
+    assert_eq!(narrowed.pixel(63, 31), &Rgb::from(*rgb.pixel(63, 31)));
+
+    let rgba = Image::from_fn(16, 16, |x, y| {
+        Rgba16::new(
+            x as u16 * 4099,
+            1,
+            u16::MAX - y as u16,
+            (x + y) as u16 * 2003,
+        )
+    });
+    let mut buffer = Vec::new();
+    rgba.encode(ImageFormat::Png, &mut buffer)?;
+    assert_eq!(
+        Image::<Rgba16>::from_bytes(ImageFormat::Png, &buffer)?.data,
+        rgba.data
+    );
+
+    let luma = Image::from_fn(256, 8, |x, y| Luma16::new((x * 256 + y) as u16));
+    luma.save_inferred("tests/out/png_16bit_output.png")?;
+    assert_eq!(
+        Image::<Luma16>::open("tests/out/png_16bit_output.png")?.data,
+        luma.data
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_16bit_conversions() {
+    assert_eq!(
+        Rgb16::from(Rgb::new(255, 128, 0)),
+        Rgb16::new(65535, 32896, 0)
+    );
+    assert_eq!(
+        Rgb::from(Rgb16::new(65535, 32896, 128)),
+        Rgb::new(255, 128, 0)
+    );
+    assert_eq!(Luma16::from(Rgb16::white()), Luma16::new(u16::MAX));
+    assert_eq!(Rgba16::from(Luma(255)), Rgba16::white());
+    assert_eq!(Luma::from(Rgba16::black()), Luma(0));
+
+    // 8-bit data should be widened when creating 16-bit pixels
+    let pixel = Luma16::from_raw_parts(ColorType::Luma, 8, &[128]).unwrap();
+    assert_eq!(pixel, Luma16::new(32896));
+
+    // Data of any depth is rescaled such that its maximum value maps onto the maximum value of
+    // the target depth
+    for (bit_depth, value, expected) in [(1, 1, 255), (2, 1, 85), (2, 3, 255), (4, 8, 136)] {
+        let pixel = Luma::from_raw_parts(ColorType::Luma, bit_depth, &[value]).unwrap();
+        assert_eq!(pixel, Luma(expected), "{value} at {bit_depth} bit(s)");
+    }
+    let pixel = Luma::from_raw_parts(ColorType::Luma, 16, &[0x80, 0x80]).unwrap();
+    assert_eq!(pixel, Luma(128));
+
+    // Truncated 16-bit data is rejected
+    match Luma16::from_raw_parts(ColorType::Luma, 16, &[128]) {
+        Err(ril::Error::DecodingError(message)) => assert!(
+            message.ends_with("expected at least 1 16-bit component(s) but received 1 byte(s)"),
+            "{message}"
+        ),
+        other => panic!("expected a decoding error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_float_conversions() {
+    // sRGB extremes map exactly onto linear light
+    assert_eq!(RgbF32::from(Rgb::white()), RgbF32::white());
+    assert_eq!(RgbaF32::from(Rgba::transparent()), RgbaF32::transparent());
+
+    // mid-gray is darker in linear light, and alpha is not gamma-corrected
+    let pixel = RgbaF32::from(Rgba::new(128, 128, 128, 128));
+    assert!((pixel.r - 0.2158).abs() < 1e-4);
+    assert!((pixel.a - 128. / 255.).abs() < f32::EPSILON);
+
+    for value in 0..=255 {
+        let rgb = Rgb::new(value, value / 2, 255 - value);
+        assert_eq!(Rgb::from(RgbF32::from(rgb)), rgb);
+    }
+    // out-of-range values are clamped when quantizing
+    assert_eq!(Rgb::from(RgbF32::new(2.0, -1.0, 0.0)), Rgb::new(255, 0, 0));
+}
+
+#[test]
+fn test_float_png_encode() -> ril::Result<()> {
+    let image = Image::<Rgb>::open("tests/sample.png")?;
+    let float = image.clone().convert::<RgbF32>();
+
+    // PNG has no floating-point representation, this should not panic
+    assert!(matches!(
+        float.encode(ImageFormat::Png, &mut Vec::new()),
+        Err(ril::Error::UnsupportedColorType)
+    ));
+
+    let mut buffer = Vec::new();
+    float
+        .convert::<Rgb>()
+        .encode(ImageFormat::Png, &mut buffer)?;
+    assert_eq!(
+        Image::<Rgb>::from_bytes(ImageFormat::Png, &buffer)?.data,
+        image.data
+    );
+    Ok(())
+}
+
+#[test]
+fn test_luma_alpha_png_roundtrip() -> ril::Result<()> {
+    let image = Image::from_fn(48, 24, |x, y| LumaA::new(x as u8 * 5, y as u8 * 10));
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::Png, &mut buffer)?;
+
+    // IHDR color type 4 is grayscale with alpha
+    assert_eq!(buffer[25], 4);
+    assert_eq!(
+        Image::<LumaA>::from_bytes(ImageFormat::Png, &buffer)?.data,
+        image.data
+    );
+
+    let rgba = Image::<Rgba>::from_bytes(ImageFormat::Png, &buffer)?;
+    assert_eq!(rgba.pixel(3, 2), &Rgba::new(15, 15, 15, 20));
+
+    let dynamic = Image::<Dynamic>::from_bytes(ImageFormat::Png, &buffer)?;
+    assert_eq!(dynamic.pixel(3, 2), &Dynamic::LumaA(LumaA::new(15, 20)));
+    Ok(())
+}
+
+#[test]
+fn test_png_metadata_roundtrip() -> ril::Result<()> {
+    let metadata = Metadata::new()
+        .with_exif(&b"MM\0*\0\0\0\x08\0\0"[..])
+        .with_xmp(&b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>"[..])
+        .with_icc_profile(vec![7; 512])
+        .with_text("Title", "Puffins")
+        .with_text("Comment", "Non-Latin-1 text: \u{1f427}");
+    let image = Image::new(8, 8, Rgb::new(0, 128, 255)).with_metadata(metadata);
+
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::Png, &mut buffer)?;
+
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Png, &buffer)?;
+    assert_eq!(decoded.metadata(), image.metadata());
+    assert_eq!(decoded.metadata().text("Title"), Some("Puffins"));
+    Ok(())
+}
+
+fn exif_with_orientation(orientation: u8) -> Vec<u8> {
+    let mut exif = b"MM\0*\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0\0\0\0\0\0\0\0".to_vec();
+    exif[19] = orientation;
+    exif
+}
+
+#[test]
+fn test_exif_orientation() -> ril::Result<()> {
+    // 3x2 image where every pixel is unique
+    let image = Image::from_fn(3, 2, |x, y| Luma::new((y * 3 + x) as u8));
+    let expected: [(u8, (u32, u32), [u8; 6]); 8] = [
+        (1, (3, 2), [0, 1, 2, 3, 4, 5]),
+        (2, (3, 2), [2, 1, 0, 5, 4, 3]),
+        (3, (3, 2), [5, 4, 3, 2, 1, 0]),
+        (4, (3, 2), [3, 4, 5, 0, 1, 2]),
+        (5, (2, 3), [0, 3, 1, 4, 2, 5]),
+        (6, (2, 3), [3, 0, 4, 1, 5, 2]),
+        (7, (2, 3), [5, 2, 4, 1, 3, 0]),
+        (8, (2, 3), [2, 5, 1, 4, 0, 3]),
+    ];
+
+    for (orientation, dimensions, data) in expected {
+        let mut image = image
+            .clone()
+            .with_metadata(Metadata::new().with_exif(exif_with_orientation(orientation)));
+        assert_eq!(
+            image.metadata().exif_orientation(),
+            Some(orientation as u16)
+        );
+
+        image.apply_exif_orientation();
+        assert_eq!(image.dimensions(), dimensions, "orientation {orientation}");
+        assert_eq!(
+            image.data.iter().map(|p| p.value()).collect::<Vec<_>>(),
+            data,
+            "orientation {orientation}"
+        );
+        assert_eq!(image.metadata().exif_orientation(), Some(1));
+    }
+
+    // applied automatically when opening an image with its orientation
+    let image = image.with_metadata(Metadata::new().with_exif(exif_with_orientation(6)));
+    image.save(ImageFormat::Png, "tests/out/png_orientation_output.png")?;
+
+    assert_eq!(
+        Image::<Luma>::open("tests/out/png_orientation_output.png")?.dimensions(),
+        (3, 2)
+    );
+    let opened = Image::<Luma>::open_with_orientation("tests/out/png_orientation_output.png")?;
+    assert_eq!(opened.dimensions(), (2, 3));
+    assert_eq!(opened.pixel(0, 0), &Luma::new(3));
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use ril::prelude::*;
+use ril::{QuantizeAlgorithm, Quantizer};
+
+fn gradient() -> Image<Rgb> {
+    Image::from_fn(64, 64, |x, y| {
+        Rgb::new((x * 4) as u8, (y * 4) as u8, 255 - (x + y) as u8 * 2)
+    })
+}
+
+/// The mean squared error between the original and the quantized image.
+fn error(original: &Image<Rgb>, quantized: Image<PalettedRgb>) -> f64 {
+    let flattened = quantized.flatten_palette();
+    let sum = original
+        .data
+        .iter()
+        .zip(&flattened.data)
+        .map(|(a, b)| {
+            [(a.r, b.r), (a.g, b.g), (a.b, b.b)]
+                .iter()
+                .map(|&(a, b)| (f64::from(a) - f64::from(b)).powi(2))
+                .sum::<f64>()
+        })
+        .sum::<f64>();
+
+    sum / original.data.len() as f64
+}
+
+#[test]
+fn test_quantize_algorithms() -> ril::Result<()> {
+    let image = gradient();
+
+    for algorithm in [
+        QuantizeAlgorithm::MedianCut,
+        QuantizeAlgorithm::Octree,
+        QuantizeAlgorithm::Wu,
+    ] {
+        let quantizer = Quantizer::new()
+            .with_palette_size(16)
+            .with_algorithm(algorithm.clone());
+        let quantized: Image<PalettedRgb> = image.clone().quantize_with(&quantizer)?;
+        let palette_size = quantized.palette().unwrap().len();
+        assert!((2..=16).contains(&palette_size), "{algorithm:?}");
+        assert!(quantized
+            .data
+            .iter()
+            .all(|p| (p.index as usize) < palette_size));
+
+        let plain = error(&image, quantized);
+        assert!(plain < 1300.0, "{algorithm:?}: {plain}");
+
+        // Refinement never makes the palette worse
+        let refined = error(
+            &image,
+            image
+                .clone()
+                .quantize_with(&quantizer.with_kmeans_iterations(8))?,
+        );
+        assert!(refined <= plain, "{algorithm:?}: {refined} > {plain}");
+    }
+
+    // Images with few colors are quantized losslessly
+    let few = Image::from_fn(8, 8, |x, y| Rgb::new(x as u8 * 30, y as u8 * 30, 0));
+    let quantized: Image<PalettedRgb> = few
+        .clone()
+        .quantize_with(&Quantizer::new().with_algorithm(QuantizeAlgorithm::Wu))?;
+    assert_eq!(quantized.flatten_palette().data, few.data);
+
+    Ok(())
+}
+
+#[test]
+fn test_quantize_fixed_palette() -> ril::Result<()> {
+    let web_safe = QuantizeAlgorithm::web_safe();
+    match &web_safe {
+        QuantizeAlgorithm::Fixed(palette) => assert_eq!(palette.len(), 216),
+        _ => unreachable!(),
+    }
+
+    let image = gradient();
+    let quantized: Image<PalettedRgb> =
+        image.quantize_with(&Quantizer::new().with_algorithm(web_safe))?;
+    assert_eq!(quantized.palette().unwrap().len(), 216);
+    assert_eq!(quantized.pixel(0, 0).color(), Rgb::new(0, 0, 255));
+    assert_eq!(quantized.pixel(63, 63).color(), Rgb::new(255, 255, 0));
+
+    // Fixed palettes are used as-is, even for images with few colors
+    let brand = vec![
+        Rgba::new(12, 34, 56, 255),
+        Rgba::new(200, 100, 50, 255),
+        Rgba::new(250, 250, 250, 255),
+    ];
+    let image = Image::from_fn(4, 1, |x, _| {
+        [
+            Rgb::black(),
+            Rgb::new(210, 90, 40),
+            Rgb::white(),
+            Rgb::new(0, 30, 60),
+        ][x as usize]
+    });
+    let quantized: Image<PalettedRgb> = image
+        .quantize_with(&Quantizer::new().with_algorithm(QuantizeAlgorithm::Fixed(brand.clone())))?;
+    assert_eq!(
+        quantized.palette().unwrap(),
+        brand.iter().map(|c| c.as_rgb()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        quantized.data.iter().map(|p| p.index).collect::<Vec<_>>(),
+        [0, 1, 2, 0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quantize_alpha() -> ril::Result<()> {
+    // A gradient where the left half is fading out and the right quarter is fully transparent
+    // with varying hidden colors
+    let image = Image::from_fn(64, 64, |x, y| {
+        let alpha = match x {
+            0..=31 => 255 - x as u8 * 6,
+            32..=47 => 255,
+            _ => 0,
+        };
+        Rgba::new((x * 4) as u8, (y * 4) as u8, 128, alpha)
+    });
+
+    for algorithm in [
+        QuantizeAlgorithm::MedianCut,
+        QuantizeAlgorithm::Octree,
+        QuantizeAlgorithm::Wu,
+    ] {
+        let quantized: Image<PalettedRgba> = image.clone().quantize_with(
+            &Quantizer::new()
+                .with_palette_size(32)
+                .with_algorithm(algorithm.clone()),
+        )?;
+        let palette = quantized.palette().unwrap();
+        assert!(palette.len() <= 32, "{algorithm:?}");
+
+        // All fully transparent pixels share a single entry, which no other pixel uses
+        let transparent = quantized.pixel(63, 0).index;
+        assert_eq!(palette[transparent as usize].a, 0);
+        for (pixel, original) in quantized.data.iter().zip(&image.data) {
+            assert_eq!(
+                pixel.index == transparent,
+                original.a == 0,
+                "{algorithm:?}: {original:?}"
+            );
+            let alpha = i32::from(palette[pixel.index as usize].a);
+            assert!((alpha - i32::from(original.a)).abs() <= 48, "{algorithm:?}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+mod test_png;
+
+use ril::encodings::tiff::{Compression, DeflateLevel, TiffEncoder, TiffEncoderOptions};
+use ril::prelude::*;
+use test_png::COLORS;
+
+fn encode_with(image: &Image<Rgb>, compression: Compression) -> ril::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let metadata = EncoderMetadata::from(image)
+        .with_config(TiffEncoderOptions::new().with_compression(compression));
+
+    let mut encoder = TiffEncoder::new(&mut buffer, metadata)?;
+    encoder.add_frame(image)?;
+    encoder.finish()?;
+
+    Ok(buffer)
+}
+
+#[test]
+fn test_tiff_compression_roundtrip() -> ril::Result<()> {
+    let image = Image::<Rgb>::open("tests/sample.png")?;
+
+    for compression in [
+        Compression::Uncompressed,
+        Compression::PackBits,
+        Compression::Lzw,
+        Compression::Deflate(DeflateLevel::Fast),
+    ] {
+        let buffer = encode_with(&image, compression)?;
+        assert_eq!(ImageFormat::infer_encoding(&buffer), ImageFormat::Tiff);
+
+        let decoded = Image::<Rgb>::from_bytes_inferred(&buffer)?;
+        assert_eq!(decoded.dimensions(), image.dimensions());
+        assert!(
+            decoded.data == image.data,
+            "{compression:?} did not roundtrip"
+        );
+    }
+
+    std::fs::write(
+        "tests/out/tiff_encode_output.tiff",
+        encode_with(&image, Compression::Lzw)?,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_tiff_luma_and_rgba_roundtrip() -> ril::Result<()> {
+    let luma = Image::from_fn(31, 7, |x, y| Luma::new((x * 8 + y) as u8));
+    let mut buffer = Vec::new();
+    luma.encode(ImageFormat::Tiff, &mut buffer)?;
+    assert_eq!(
+        Image::<Luma>::from_bytes(ImageFormat::Tiff, &buffer)?.data,
+        luma.data
+    );
+
+    let rgba = Image::from_fn(9, 13, |x, y| {
+        Rgba::new(x as u8 * 20, y as u8 * 10, 7, x as u8 * 25)
+    });
+    let mut buffer = Vec::new();
+    rgba.encode(ImageFormat::Tiff, &mut buffer)?;
+    assert_eq!(
+        Image::<Rgba>::from_bytes(ImageFormat::Tiff, &buffer)?.data,
+        rgba.data
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_multipage_tiff() -> ril::Result<()> {
+    let seq = COLORS
+        .into_iter()
+        .map(|color| Frame::from_image(Image::new(64, 32, color)))
+        .collect::<ImageSequence<_>>();
+
+    let mut buffer = Vec::new();
+    seq.encode(ImageFormat::Tiff, &mut buffer)?;
+
+    let pages = ImageSequence::<Rgb>::from_bytes(ImageFormat::Tiff, &buffer)?;
+    assert_eq!(pages.len(), COLORS.len() as u32);
+
+    for (page, color) in pages.zip(COLORS) {
+        let page = page?;
+
+        assert_eq!(page.dimensions(), (64, 32));
+        assert_eq!(page.pixel(0, 0), &color);
+    }
+
+    // decoding as a static image only yields the first page
+    let first = Image::<Rgb>::from_bytes(ImageFormat::Tiff, &buffer)?;
+    assert_eq!(first.pixel(0, 0), &COLORS[0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_tiff_16bit_roundtrip() -> ril::Result<()> {
+    let image = Image::from_fn(23, 11, |x, y| {
+        Rgba16::new(x as u16 * 2851, y as u16 * 5903, 257, u16::MAX - x as u16)
+    });
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::Tiff, &mut buffer)?;
+
+    assert_eq!(
+        Image::<Rgba16>::from_bytes(ImageFormat::Tiff, &buffer)?.data,
+        image.data
+    );
+    Ok(())
+}
+
+/// Builds a little-endian, uncompressed grayscale TIFF from the given directory entries, with
+/// `data` stored right after the header. Every entry is written as an array of LONGs.
+fn build_tiff(entries: &[(u16, Vec<u32>)], data: &[u8]) -> Vec<u8> {
+    let ifd_offset = 8 + data.len() as u32;
+    let mut extra_offset = ifd_offset + 2 + entries.len() as u32 * 12 + 4;
+
+    let mut buffer = b"II*\0".to_vec();
+    buffer.extend(ifd_offset.to_le_bytes());
+    buffer.extend(data);
+    buffer.extend((entries.len() as u16).to_le_bytes());
+
+    let mut extra = Vec::new();
+    for (tag, values) in entries {
+        buffer.extend(tag.to_le_bytes());
+        buffer.extend(4_u16.to_le_bytes());
+        buffer.extend((values.len() as u32).to_le_bytes());
+
+        if let [value] = values[..] {
+            buffer.extend(value.to_le_bytes());
+        } else {
+            buffer.extend(extra_offset.to_le_bytes());
+            extra_offset += values.len() as u32 * 4;
+            extra.extend(values.iter().flat_map(|n| n.to_le_bytes()));
+        }
+    }
+    buffer.extend(0_u32.to_le_bytes());
+    buffer.extend(extra);
+    buffer
+}
+
+#[test]
+fn test_tiff_3bit_samples() -> ril::Result<()> {
+    let (width, height) = (5, 3);
+    let value = |x: u32, y: u32| (x + y * 3) % 8;
+
+    // 15 bits per row, so rows are padded to two bytes and samples cross byte boundaries
+    let mut data = Vec::new();
+    for y in 0..height {
+        let row = (0..width).fold(0_u16, |row, x| row << 3 | value(x, y) as u16) << 1;
+        data.extend(row.to_be_bytes());
+    }
+
+    let buffer = build_tiff(
+        &[
+            (256, vec![width]),
+            (257, vec![height]),
+            (258, vec![3]),
+            (259, vec![1]),
+            (262, vec![1]),
+            (273, vec![8]),
+            (277, vec![1]),
+            (278, vec![height]),
+            (279, vec![data.len() as u32]),
+        ],
+        &data,
+    );
+
+    let image = Image::<Luma>::from_bytes(ImageFormat::Tiff, &buffer)?;
+    let expected = Image::from_fn(width, height, |x, y| {
+        Luma::new((value(x, y) * 255 / 7) as u8)
+    });
+    assert_eq!(image.data, expected.data);
+
+    Ok(())
+}
+
+#[test]
+fn test_tiled_tiff() -> ril::Result<()> {
+    let (width, height) = (20, 18);
+    let value = |x: u32, y: u32| (x * 12 + y) as u8;
+
+    // 16x16 tiles; tiles on the right and bottom edges are padded
+    let mut data = Vec::new();
+    let mut offsets = Vec::new();
+    for tile_y in 0..2 {
+        for tile_x in 0..2 {
+            offsets.push(8 + data.len() as u32);
+            for y in tile_y * 16..tile_y * 16 + 16 {
+                data.extend((tile_x * 16..tile_x * 16 + 16).map(|x| value(x, y)));
+            }
+        }
+    }
+
+    let buffer = build_tiff(
+        &[
+            (256, vec![width]),
+            (257, vec![height]),
+            (258, vec![8]),
+            (259, vec![1]),
+            (262, vec![1]),
+            (277, vec![1]),
+            (322, vec![16]),
+            (323, vec![16]),
+            (324, offsets),
+            (325, vec![256; 4]),
+        ],
+        &data,
+    );
+
+    let image = Image::<Luma>::from_bytes(ImageFormat::Tiff, &buffer)?;
+    assert_eq!(
+        image.data,
+        Image::from_fn(width, height, |x, y| Luma::new(value(x, y))).data
+    );
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use ril::prelude::*;
+
+#[test]
+fn test_rotate_by_right_angles() {
+    let image = Image::from_fn(7, 4, |x, y| Rgb::new(x as u8 * 30, y as u8 * 60, 7));
+
+    for interpolation in [
+        Interpolation::Nearest,
+        Interpolation::Bilinear,
+        Interpolation::Bicubic,
+    ] {
+        let options = RotateOptions::new()
+            .with_interpolation(interpolation)
+            .with_expand(true);
+
+        for degrees in [90, 180, 270, -90] {
+            let rotated = image.clone().rotated_by(f64::from(degrees), options);
+            let expected = image.clone().rotated(degrees);
+
+            assert_eq!(rotated.dimensions(), expected.dimensions());
+            assert_eq!(rotated.data, expected.data, "{interpolation:?} {degrees}");
+        }
+    }
+}
+
+#[test]
+fn test_rotate_by_expand_and_fill() {
+    let image = Image::new(100, 50, Rgb::white());
+
+    let rotated = image.clone().rotated_by(
+        45.0,
+        RotateOptions::new()
+            .with_expand(true)
+            .with_fill(Rgb::new(255, 0, 0)),
+    );
+    assert_eq!(rotated.dimensions(), (107, 107));
+    assert_eq!(rotated.pixel(0, 0), &Rgb::new(255, 0, 0));
+    assert_eq!(rotated.pixel(53, 53), &Rgb::white());
+
+    // without expanding, the dimensions are kept and the corners are cropped off
+    let rotated = image.rotated(45);
+    assert_eq!(rotated.dimensions(), (100, 50));
+    assert_eq!(rotated.pixel(0, 0), &Rgb::black());
+    assert_eq!(rotated.pixel(50, 25), &Rgb::white());
+}
+
+#[test]
+fn test_rotate_by_alpha() {
+    // An opaque red square on a transparent background
+    let image = Image::from_fn(64, 64, |x, y| {
+        if (16..48).contains(&x) && (16..48).contains(&y) {
+            Rgba::new(255, 0, 0, 255)
+        } else {
+            Rgba::transparent()
+        }
+    });
+
+    for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+        let rotated = image.clone().rotated_by(
+            30.0,
+            RotateOptions::new()
+                .with_interpolation(interpolation)
+                .with_expand(true),
+        );
+
+        // The edges are antialiased through the alpha channel without darkening the color
+        assert!(rotated.data.iter().any(|p| p.a > 0 && p.a < 255));
+        for pixel in rotated.data.iter().filter(|p| p.a > 0) {
+            assert_eq!(
+                (pixel.r, pixel.g, pixel.b),
+                (255, 0, 0),
+                "{interpolation:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_rotate_by_16bit() {
+    let image = Image::new(32, 32, Luma16::new(40_000));
+    let rotated = image.rotated_by(
+        10.0,
+        RotateOptions::new()
+            .with_interpolation(Interpolation::Bicubic)
+            .with_fill(Luma16::new(40_000)),
+    );
+
+    assert!(rotated.data.iter().all(|p| p.value().abs_diff(40_000) <= 1));
+}
+
+#[test]
+fn test_warp_affine() {
+    let image = Image::from_fn(8, 6, |x, y| Rgb::new(x as u8 * 30, y as u8 * 40, 7));
+
+    // The identity transformation leaves the image untouched for every interpolation
+    for interpolation in [
+        Interpolation::Nearest,
+        Interpolation::Bilinear,
+        Interpolation::Bicubic,
+    ] {
+        let options = WarpOptions::new().with_interpolation(interpolation);
+        let warped = image
+            .clone()
+            .warped_affine([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], options);
+        assert_eq!(warped.data, image.data, "{interpolation:?}");
+    }
+
+    // Translating by whole pixels shifts the image, resolving the uncovered pixels through the
+    // edge mode
+    let translate = [[1.0, 0.0, 2.0], [0.0, 1.0, 1.0]];
+    let fill = Rgb::new(255, 0, 255);
+    for (edge_mode, expected) in [
+        (EdgeMode::Constant(fill), fill),
+        (EdgeMode::Clamp, *image.pixel(0, 0)),
+        (EdgeMode::Wrap, *image.pixel(6, 5)),
+        (EdgeMode::Reflect, *image.pixel(1, 0)),
+        (EdgeMode::Skip, Rgb::black()),
+    ] {
+        let warped = image
+            .clone()
+            .warped_affine(translate, WarpOptions::new().with_edge_mode(edge_mode));
+        assert_eq!(warped.pixel(2, 1), image.pixel(0, 0));
+        assert_eq!(warped.pixel(7, 5), image.pixel(5, 4));
+        assert_eq!(warped.pixel(0, 0), &expected, "{edge_mode:?}");
+    }
+
+    // Resizing algorithms can be used in place of the closest interpolation algorithm
+    let shear = [[1.0, 0.4, 0.0], [0.0, 1.0, 0.0]];
+    for (algorithm, interpolation) in [
+        (ResizeAlgorithm::Box, Interpolation::Nearest),
+        (ResizeAlgorithm::Hamming, Interpolation::Bilinear),
+        (ResizeAlgorithm::Lanczos3, Interpolation::Bicubic),
+    ] {
+        let expected = image
+            .clone()
+            .warped_affine(shear, WarpOptions::new().with_interpolation(interpolation));
+        let warped = image
+            .clone()
+            .warped_affine(shear, WarpOptions::new().with_interpolation(algorithm));
+        assert_eq!(warped.data, expected.data, "{algorithm:?}");
+
+        let options = RotateOptions::<Rgb>::new().with_interpolation(algorithm);
+        assert_eq!(options.interpolation, interpolation);
+    }
+}
+
+#[test]
+fn test_homography() {
+    let from = [(10.0, 20.0), (200.0, 5.0), (220.0, 180.0), (0.0, 150.0)];
+    let to = [(0.0, 0.0), (99.0, 0.0), (99.0, 99.0), (0.0, 99.0)];
+    let [[a, b, c], [d, e, f], [g, h, i]] = ril::transform::homography(from, to).unwrap();
+
+    for ((x, y), (u, v)) in from.into_iter().zip(to) {
+        let w = g * x + h * y + i;
+        assert!(((a * x + b * y + c) / w - u).abs() < 1e-9);
+        assert!(((d * x + e * y + f) / w - v).abs() < 1e-9);
+    }
+
+    // Collinear points have no solution
+    let collinear = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)];
+    assert!(ril::transform::homography(collinear, to).is_none());
+}
+
+#[test]
+fn test_warp_perspective_deskew() {
+    // A white quadrilateral on a black background, deskewed into a square filling the image
+    let corners = [(20.0, 10.0), (90.0, 25.0), (80.0, 90.0), (10.0, 70.0)];
+    let inside = |x: f64, y: f64| {
+        corners.iter().enumerate().all(|(i, &(x1, y1))| {
+            let (x2, y2) = corners[(i + 1) % 4];
+            (x2 - x1) * (y - y1) - (y2 - y1) * (x - x1) >= 0.0
+        })
+    };
+    let image = Image::from_fn(100, 100, |x, y| {
+        if inside(f64::from(x), f64::from(y)) {
+            Luma::new(255)
+        } else {
+            Luma::new(0)
+        }
+    });
+
+    let target = [(0.0, 0.0), (49.0, 0.0), (49.0, 49.0), (0.0, 49.0)];
+    let matrix = ril::transform::homography(corners, target).unwrap();
+    let deskewed = image.warped_perspective(
+        matrix,
+        WarpOptions::new()
+            .with_interpolation(Interpolation::Nearest)
+            .with_dimensions(50, 50),
+    );
+
+    assert_eq!(deskewed.dimensions(), (50, 50));
+    // The outermost pixels may straddle the edges of the quadrilateral
+    for y in 1..49 {
+        for x in 1..49 {
+            assert_eq!(deskewed.pixel(x, y).value(), 255, "({x}, {y})");
+        }
+    }
+}
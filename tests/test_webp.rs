@@ -1,5 +1,6 @@
 mod test_png;
 
+use ril::encodings::webp::{WebPEncoderOptions, WebPStaticEncoder};
 use ril::prelude::*;
 use std::time::Duration;
 use test_png::COLORS;
@@ -46,3 +47,33 @@ fn test_animated_webp_decode() -> ril::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_luma_alpha_webp_roundtrip() -> ril::Result<()> {
+    let image = Image::from_fn(64, 64, |x, y| LumaA::new(x as u8 * 4, 255 - y as u8 * 2));
+    let mut buffer = Vec::new();
+    let metadata =
+        EncoderMetadata::from(&image).with_config(WebPEncoderOptions::new().with_lossless(true));
+    let mut encoder = WebPStaticEncoder::new(&mut buffer, metadata)?;
+    encoder.add_frame(&image)?;
+    encoder.finish()?;
+
+    let decoded = Image::<LumaA>::from_bytes(ImageFormat::WebP, &buffer)?;
+    assert_eq!(decoded.data, image.data);
+    Ok(())
+}
+
+#[test]
+fn test_webp_metadata_roundtrip() -> ril::Result<()> {
+    let metadata = Metadata::new()
+        .with_exif(&b"II*\0\x08\0\0\0\0\0"[..])
+        .with_xmp(&b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>"[..]);
+    let image = Image::new(16, 16, Rgb::new(255, 0, 0)).with_metadata(metadata);
+
+    let mut buffer = Vec::new();
+    image.encode(ImageFormat::WebP, &mut buffer)?;
+
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::WebP, &buffer)?;
+    assert_eq!(decoded.metadata(), image.metadata());
+    Ok(())
+}